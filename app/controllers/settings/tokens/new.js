@@ -23,7 +23,16 @@ export default class NewTokenController extends Controller {
   @tracked scopesInvalid;
   @tracked crateScopes;
 
-  ENDPOINT_SCOPES = ['change-owners', 'publish-new', 'publish-update', 'yank'];
+  ENDPOINT_SCOPES = [
+    'change-owners',
+    'publish-new',
+    'publish-update',
+    'yank',
+    'read-private',
+    'follow',
+    'manage-invitations',
+    'manage-tokens',
+  ];
 
   scopeDescription = scopeDescription;
 
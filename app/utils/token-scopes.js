@@ -2,8 +2,12 @@ import { htmlSafe } from '@ember/template';
 
 const DESCRIPTIONS = {
   'change-owners': 'Invite new crate owners or remove existing ones',
+  follow: 'Follow and unfollow crates',
+  'manage-invitations': 'Accept or decline crate owner invitations',
+  'manage-tokens': 'List and revoke API tokens',
   'publish-new': 'Publish new crates',
   'publish-update': 'Publish new versions of existing crates',
+  'read-private': 'Read account details, followed crate updates and crate owner invitations',
   yank: 'Yank and unyank crate versions',
 };
 
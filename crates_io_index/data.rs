@@ -24,6 +24,12 @@ pub struct Crate {
     pub links: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rust_version: Option<String>,
+    /// The reason given by the crate owners when yanking this version.
+    ///
+    /// This is only populated for yanked versions, and only if the owners
+    /// provided a reason when yanking them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yank_reason: Option<YankReason>,
     /// The schema version for this entry.
     ///
     /// If this is None, it defaults to version 1. Entries with unknown
@@ -47,6 +53,25 @@ pub struct Crate {
     pub v: Option<u32>,
 }
 
+impl Crate {
+    /// Returns the features of this version, with the `features2` field
+    /// merged on top of the `features` field, like cargo does.
+    pub fn all_features(&self) -> BTreeMap<String, Vec<String>> {
+        let mut features = self.features.clone();
+        if let Some(features2) = &self.features2 {
+            features.extend(features2.clone());
+        }
+        features
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct YankReason {
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct Dependency {
     pub name: String,
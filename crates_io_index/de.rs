@@ -0,0 +1,432 @@
+use crate::{Crate, Repository};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The schema version of index entries that use the `features2` field.
+const FEATURES2_SCHEMA_VERSION: u32 = 2;
+
+/// An error in an index file, with the path and the line of the error, if
+/// known.
+#[derive(Debug, thiserror::Error)]
+pub struct ReadError {
+    pub path: Option<PathBuf>,
+    /// The line number, starting at `1`.
+    pub line: Option<usize>,
+    #[source]
+    pub kind: ReadErrorKind,
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{line}:")?;
+        }
+        if self.path.is_some() || self.line.is_some() {
+            f.write_str(" ")?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReadErrorKind {
+    #[error("failed to read index file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported schema version {0}")]
+    UnsupportedSchemaVersion(u32),
+    #[error("`features2` requires schema version 2, found {0:?}")]
+    Features2WithoutSchemaVersion(Option<u32>),
+    #[error("schema version 2 requires a non-empty `features2` field")]
+    SchemaVersionWithoutFeatures2,
+    #[error("feature `{0}` is defined in both `features` and `features2`")]
+    DuplicateFeature(String),
+    #[error("feature `{0}` in `features` uses the syntax that is reserved for `features2`")]
+    Features2SyntaxInFeatures(String),
+    #[error("dependency `{0}` is not sorted")]
+    UnsortedDependencies(String),
+    #[error("invalid checksum `{0}`, expected 64 lowercase hexadecimal characters")]
+    InvalidChecksum(String),
+    #[error("crate name `{actual}` does not match `{expected}`")]
+    NameMismatch { expected: String, actual: String },
+    #[error("version {0} is listed more than once")]
+    DuplicateVersion(String),
+}
+
+impl ReadErrorKind {
+    fn at_line(self, line: usize) -> ReadError {
+        ReadError {
+            path: None,
+            line: Some(line),
+            kind: self,
+        }
+    }
+}
+
+impl Crate {
+    /// Checks that the entry follows the rules of the index format, as
+    /// written by crates.io.
+    pub fn validate(&self) -> Result<(), ReadErrorKind> {
+        match (self.v, &self.features2) {
+            (None | Some(1), None) => {}
+            (None | Some(1), Some(_)) => {
+                return Err(ReadErrorKind::Features2WithoutSchemaVersion(self.v));
+            }
+            (Some(FEATURES2_SCHEMA_VERSION), Some(features2)) if !features2.is_empty() => {}
+            (Some(FEATURES2_SCHEMA_VERSION), _) => {
+                return Err(ReadErrorKind::SchemaVersionWithoutFeatures2);
+            }
+            (Some(v), _) => return Err(ReadErrorKind::UnsupportedSchemaVersion(v)),
+        }
+
+        for (name, values) in &self.features {
+            if values.iter().any(|value| uses_features2_syntax(value)) {
+                return Err(ReadErrorKind::Features2SyntaxInFeatures(name.clone()));
+            }
+        }
+
+        if let Some(features2) = &self.features2 {
+            if let Some(name) = features2.keys().find(|k| self.features.contains_key(*k)) {
+                return Err(ReadErrorKind::DuplicateFeature(name.clone()));
+            }
+        }
+
+        if let Some(pair) = self.deps.windows(2).find(|pair| pair[0] > pair[1]) {
+            return Err(ReadErrorKind::UnsortedDependencies(pair[1].name.clone()));
+        }
+
+        let is_valid_checksum = self.cksum.len() == 64
+            && self
+                .cksum
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+
+        if !is_valid_checksum {
+            return Err(ReadErrorKind::InvalidChecksum(self.cksum.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Features that enable optional dependencies with `dep:` or weak dependency
+/// features with `?/` can only be used in the `features2` field.
+fn uses_features2_syntax(value: &str) -> bool {
+    value.starts_with("dep:") || value.contains("?/")
+}
+
+/// Parses and validates the newline-delimited entries of an index file.
+///
+/// All entries must belong to the same crate, and every version can only be
+/// listed once. The first invalid entry is reported with its line number.
+pub fn read_crates(content: &[u8]) -> Result<Vec<Crate>, ReadError> {
+    let mut crates: Vec<Crate> = Vec::new();
+    let mut versions = HashSet::new();
+
+    for (i, line) in content.split(|&b| b == b'\n').enumerate() {
+        let line_number = i + 1;
+        if line.is_empty() {
+            continue;
+        }
+
+        let krate: Crate = serde_json::from_slice(line)
+            .map_err(|error| ReadErrorKind::from(error).at_line(line_number))?;
+
+        krate.validate().map_err(|kind| kind.at_line(line_number))?;
+
+        if let Some(first) = crates.first() {
+            if first.name != krate.name {
+                let expected = first.name.clone();
+                let actual = krate.name;
+                let kind = ReadErrorKind::NameMismatch { expected, actual };
+                return Err(kind.at_line(line_number));
+            }
+        }
+
+        if !versions.insert(krate.vers.clone()) {
+            let kind = ReadErrorKind::DuplicateVersion(krate.vers);
+            return Err(kind.at_line(line_number));
+        }
+
+        crates.push(krate);
+    }
+
+    Ok(crates)
+}
+
+/// An index file that was read by [`read_index_dir`].
+#[derive(Debug)]
+pub struct IndexFile {
+    /// The path of the index file, relative to the root of the index.
+    pub path: PathBuf,
+    pub crates: Vec<Crate>,
+}
+
+/// Reads all index files in the directory tree of an index, like a checkout
+/// of the git index.
+///
+/// Files that don't belong to the index, like `config.json` or the `.git`
+/// directory, are skipped. The crate names of the entries have to match the
+/// names of their index files.
+pub fn read_index_dir(root: &Path) -> IndexDirIter {
+    IndexDirIter {
+        root: root.to_path_buf(),
+        pending: vec![root.to_path_buf()],
+        files: Vec::new(),
+    }
+}
+
+/// Iterator over the index files of an index directory, see
+/// [`read_index_dir`].
+pub struct IndexDirIter {
+    root: PathBuf,
+    /// Directories that have not been listed yet.
+    pending: Vec<PathBuf>,
+    /// Files of the listed directories that have not been read yet.
+    files: Vec<PathBuf>,
+}
+
+impl IndexDirIter {
+    fn read_file(&self, path: PathBuf) -> Result<Option<IndexFile>, ReadError> {
+        let relative_path = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+
+        // Crate names are restricted to ASCII characters
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name)
+                if name.is_ascii() && Repository::relative_index_file(name) == relative_path =>
+            {
+                name
+            }
+            _ => return Ok(None),
+        };
+
+        let with_path = |mut error: ReadError| {
+            error.path = Some(relative_path.clone());
+            error
+        };
+
+        let content = fs::read(&path).map_err(|error| ReadError {
+            path: Some(relative_path.clone()),
+            line: None,
+            kind: error.into(),
+        })?;
+
+        let crates = read_crates(&content).map_err(with_path)?;
+
+        // Index files are named after the lowercase crate name
+        let line = crates.iter().position(|k| k.name.to_lowercase() != name);
+        if let Some(line) = line {
+            let expected = name.to_string();
+            let actual = crates[line].name.clone();
+            let kind = ReadErrorKind::NameMismatch { expected, actual };
+            let error = ReadError {
+                path: None,
+                line: None,
+                kind,
+            };
+            return Err(with_path(error));
+        }
+
+        Ok(Some(IndexFile {
+            path: relative_path,
+            crates,
+        }))
+    }
+
+    fn list_dir(&mut self, dir: &Path) -> Result<(), ReadError> {
+        let io_error = |error: std::io::Error| ReadError {
+            path: Some(dir.to_path_buf()),
+            line: None,
+            kind: error.into(),
+        };
+
+        let mut entries = fs::read_dir(dir)
+            .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+            .map_err(io_error)?;
+
+        // Walk the index in a stable order, with the `pending` and `files`
+        // stacks popping the entries in ascending order.
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.file_name()));
+
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            if entry.file_type().map_err(io_error)?.is_dir() {
+                self.pending.push(path);
+            } else {
+                self.files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for IndexDirIter {
+    type Item = Result<IndexFile, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(path) = self.files.pop() {
+                match self.read_file(path) {
+                    Ok(Some(file)) => return Some(Ok(file)),
+                    Ok(None) => continue,
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+
+            let dir = self.pending.pop()?;
+            if let Err(error) = self.list_dir(&dir) {
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+
+    const CKSUM: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn entry(name: &str, vers: &str) -> String {
+        format!(
+            r#"{{"name":"{name}","vers":"{vers}","deps":[],"cksum":"{CKSUM}","features":{{}},"yanked":false}}"#
+        )
+    }
+
+    fn read_error(content: &str) -> ReadError {
+        assert_err!(read_crates(content.as_bytes()))
+    }
+
+    #[test]
+    fn test_read_crates() {
+        let content = format!("{}\n{}\n", entry("foo", "1.0.0"), entry("foo", "1.1.0"));
+        let crates = assert_ok!(read_crates(content.as_bytes()));
+        assert_eq!(crates.len(), 2);
+        assert_eq!(crates[0].vers, "1.0.0");
+        assert_eq!(crates[1].vers, "1.1.0");
+
+        assert_ok_eq!(read_crates(b"").map(|crates| crates.len()), 0);
+    }
+
+    #[test]
+    fn test_read_crates_errors() {
+        let content = format!("{}\n{{\n", entry("foo", "1.0.0"));
+        let error = read_error(&content);
+        assert_eq!(error.line, Some(2));
+        assert!(matches!(error.kind, ReadErrorKind::Json(_)));
+
+        let content = format!("{}\n{}\n", entry("foo", "1.0.0"), entry("bar", "1.1.0"));
+        let error = read_error(&content);
+        assert_eq!(
+            error.to_string(),
+            "2: crate name `bar` does not match `foo`"
+        );
+
+        let content = format!("{}\n\n{}\n", entry("foo", "1.0.0"), entry("foo", "1.0.0"));
+        let error = read_error(&content);
+        assert_eq!(
+            error.to_string(),
+            "3: version 1.0.0 is listed more than once"
+        );
+
+        let content = entry("foo", "1.0.0").replace(CKSUM, "0123456789ABCDEF");
+        let error = read_error(&content);
+        assert_eq!(error.line, Some(1));
+        assert!(matches!(error.kind, ReadErrorKind::InvalidChecksum(_)));
+    }
+
+    #[test]
+    fn test_validate_schema_version() {
+        let features = r#""features":{}"#;
+        let features2 = r#""features":{},"features2":{"serde":["dep:serde"]}"#;
+
+        let parse = |content: String| -> Crate { serde_json::from_str(&content).unwrap() };
+
+        let krate =
+            parse(entry("foo", "1.0.0").replace(features, &format!(r#"{features2},"v":2"#)));
+        assert_ok!(krate.validate());
+        let all_features = krate.all_features();
+        assert_eq!(all_features.get("serde"), Some(&vec!["dep:serde".to_string()]));
+
+        let krate = parse(entry("foo", "1.0.0").replace(features, features2));
+        assert_err!(krate.validate());
+
+        let krate = parse(entry("foo", "1.0.0").replace(features, r#""features":{},"v":2"#));
+        assert_err!(krate.validate());
+
+        let krate = parse(entry("foo", "1.0.0").replace(features, r#""features":{},"v":3"#));
+        assert_err!(krate.validate());
+
+        let invalid = r#""features":{"serde":["dep:serde"]}"#;
+        let krate = parse(entry("foo", "1.0.0").replace(features, invalid));
+        assert_err!(krate.validate());
+
+        let invalid = r#""features":{"serde":[]},"features2":{"serde":["dep:serde"]},"v":2"#;
+        let krate = parse(entry("foo", "1.0.0").replace(features, invalid));
+        assert_err!(krate.validate());
+    }
+
+    #[test]
+    fn test_validate_dependency_order() {
+        let dep = |name: &str| {
+            format!(
+                r#"{{"name":"{name}","req":"^1","features":[],"optional":false,"default_features":true,"target":null,"kind":"normal"}}"#
+            )
+        };
+
+        let sorted = format!(r#""deps":[{},{}]"#, dep("a"), dep("b"));
+        let content = entry("foo", "1.0.0").replace(r#""deps":[]"#, &sorted);
+        assert_ok!(read_crates(content.as_bytes()));
+
+        let unsorted = format!(r#""deps":[{},{}]"#, dep("b"), dep("a"));
+        let content = entry("foo", "1.0.0").replace(r#""deps":[]"#, &unsorted);
+        let error = read_error(&content);
+        assert_eq!(error.to_string(), "1: dependency `a` is not sorted");
+    }
+
+    #[test]
+    fn test_read_index_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        write("config.json", "{}");
+        write(".git/HEAD", "ref: refs/heads/master");
+        write("3/f/foo", &entry("foo", "1.0.0"));
+        write("se/rd/serde", &entry("Serde", "1.0.0"));
+        write("3/b/foo", &entry("foo", "1.0.0"));
+
+        let files: Vec<_> = read_index_dir(root).collect::<Result<_, _>>().unwrap();
+        let paths: Vec<_> = files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("3/f/foo"), PathBuf::from("se/rd/serde")]
+        );
+        assert_eq!(files[1].crates[0].name, "Serde");
+
+        write("1/a", &entry("b", "1.0.0"));
+        let errors: Vec<_> = read_index_dir(root).filter_map(Result::err).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "1/a: crate name `b` does not match `a`"
+        );
+    }
+}
@@ -5,12 +5,16 @@ extern crate tracing;
 
 mod credentials;
 mod data;
+mod de;
 mod repo;
 mod ser;
 #[cfg(feature = "testing")]
 pub mod testing;
 
 pub use crate::credentials::Credentials;
-pub use crate::data::{Crate, Dependency, DependencyKind};
+pub use crate::data::{Crate, Dependency, DependencyKind, YankReason};
+pub use crate::de::{
+    read_crates, read_index_dir, IndexDirIter, IndexFile, ReadError, ReadErrorKind,
+};
 pub use crate::repo::{Repository, RepositoryConfig};
 pub use crate::ser::write_crates;
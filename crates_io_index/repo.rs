@@ -191,13 +191,24 @@ impl Repository {
     /// repository working folder!
     #[instrument(skip_all, fields(message = %msg))]
     fn perform_commit_and_push(&self, msg: &str, modified_file: &Path) -> anyhow::Result<()> {
-        // git add $file
+        self.perform_commit(msg, &[modified_file])?;
+        self.push()
+    }
+
+    /// Commits the specified files with the specified commit message.
+    ///
+    /// Note that `modified_files` expects file paths **relative** to the
+    /// repository working folder!
+    fn perform_commit(&self, msg: &str, modified_files: &[&Path]) -> anyhow::Result<()> {
+        // git add $files
         let mut index = self.repository.index()?;
 
-        if self.checkout_path.path().join(modified_file).exists() {
-            index.add_path(modified_file)?;
-        } else {
-            index.remove_path(modified_file)?;
+        for modified_file in modified_files {
+            if self.checkout_path.path().join(modified_file).exists() {
+                index.add_path(modified_file)?;
+            } else {
+                index.remove_path(modified_file)?;
+            }
         }
 
         index.write()?;
@@ -211,7 +222,7 @@ impl Repository {
         self.repository
             .commit(Some("HEAD"), &sig, &sig, msg, &tree, &[&parent])?;
 
-        self.push()
+        Ok(())
     }
 
     /// Gets a list of files that have been modified since a given `starting_commit`
@@ -259,9 +270,10 @@ impl Repository {
         Ok(files)
     }
 
-    /// Push the current branch to the provided refname
+    /// Pushes the current branch to the `master` branch on the `origin`
+    /// remote.
     #[instrument(skip_all)]
-    fn push(&self) -> anyhow::Result<()> {
+    pub fn push(&self) -> anyhow::Result<()> {
         self.run_command(Command::new("git").args(["push", "origin", "HEAD:master"]))
     }
 
@@ -284,6 +296,22 @@ impl Repository {
             })
     }
 
+    /// Commits the specified files with the specified commit message, without
+    /// pushing the commit. This allows multiple commits to be pushed at once
+    /// with [Self::push].
+    ///
+    /// Note that `modified_files` expects **absolute** file paths!
+    pub fn commit(&self, message: &str, modified_files: &[&Path]) -> anyhow::Result<()> {
+        info!("Committing \"{message}\"");
+
+        let relative_paths = modified_files
+            .iter()
+            .map(|path| path.strip_prefix(self.checkout_path.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.perform_commit(message, &relative_paths)
+    }
+
     /// Fetches any changes from the `origin` remote and performs a hard reset
     /// to the tip of the `origin/master` branch.
     #[instrument(skip_all)]
@@ -0,0 +1,190 @@
+use crate::{open_archive, validate_entry, TarballError};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use tar::EntryType;
+use tracing::instrument;
+
+/// A file or directory inside of a crate file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TarballFile {
+    /// The path of the file, relative to the `$name-$vers/` root directory.
+    pub path: String,
+    /// The uncompressed size of the file in bytes.
+    pub size: u64,
+    #[serde(rename = "type")]
+    pub kind: FileKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    File,
+    Directory,
+}
+
+/// Lists the files and directories inside of a crate file.
+///
+/// The entries are validated the same way as in [`crate::process_tarball()`],
+/// so a crate file that would have been rejected at publish time results in
+/// an error here too.
+#[instrument(skip_all, fields(%pkg_name))]
+pub fn list_files<R: Read>(
+    pkg_name: &str,
+    tarball: R,
+    max_unpack: u64,
+) -> Result<Vec<TarballFile>, TarballError> {
+    let mut archive = open_archive(tarball, max_unpack);
+
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry.map_err(TarballError::Malformed)?;
+        validate_entry(pkg_name, &entry)?;
+
+        let kind = match entry.header().entry_type() {
+            EntryType::Regular | EntryType::Continuous => FileKind::File,
+            EntryType::Directory => FileKind::Directory,
+            _ => continue,
+        };
+
+        let entry_path = entry.path()?;
+        let Some(path) = relative_path(pkg_name, &entry_path) else {
+            continue;
+        };
+
+        files.push(TarballFile {
+            path,
+            size: entry.size(),
+            kind,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Reads the contents of the file at `path`, relative to the `$name-$vers/`
+/// root directory, out of a crate file.
+///
+/// Returns `Ok(None)` if the crate file does not contain a regular file at
+/// the given path.
+#[instrument(skip(tarball, max_unpack))]
+pub fn read_file<R: Read>(
+    pkg_name: &str,
+    tarball: R,
+    max_unpack: u64,
+    path: &str,
+) -> Result<Option<Vec<u8>>, TarballError> {
+    let mut archive = open_archive(tarball, max_unpack);
+
+    for entry in archive.entries()? {
+        let mut entry = entry.map_err(TarballError::Malformed)?;
+        validate_entry(pkg_name, &entry)?;
+
+        let entry_type = entry.header().entry_type();
+        if entry_type != EntryType::Regular && entry_type != EntryType::Continuous {
+            continue;
+        }
+
+        if relative_path(pkg_name, &entry.path()?).as_deref() != Some(path) {
+            continue;
+        }
+
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(TarballError::Malformed)?;
+
+        return Ok(Some(contents));
+    }
+
+    Ok(None)
+}
+
+/// Strips the `$name-$vers/` root directory from the entry path and returns
+/// it with `/` separators, or `None` for the root directory itself.
+fn relative_path(pkg_name: &str, entry_path: &Path) -> Option<String> {
+    let path = entry_path.strip_prefix(pkg_name).ok()?;
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+
+    let components = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+
+    Some(components.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TarballBuilder;
+
+    const LIMIT: u64 = 512 * 1024 * 1024;
+
+    fn tarball() -> Vec<u8> {
+        TarballBuilder::new()
+            .add_file(
+                "foo-0.0.1/Cargo.toml",
+                b"[package]\nname = \"foo\"\nversion = \"0.0.1\"\n",
+            )
+            .add_file("foo-0.0.1/src/lib.rs", b"pub fn foo() {}\n")
+            .build()
+    }
+
+    #[test]
+    fn list_files_test() {
+        let tarball = tarball();
+
+        let files = assert_ok!(list_files("foo-0.0.1", &*tarball, LIMIT));
+        assert_eq!(
+            files,
+            vec![
+                TarballFile {
+                    path: "Cargo.toml".into(),
+                    size: 41,
+                    kind: FileKind::File,
+                },
+                TarballFile {
+                    path: "src/lib.rs".into(),
+                    size: 16,
+                    kind: FileKind::File,
+                },
+            ]
+        );
+
+        assert_err!(list_files("bar-0.0.1", &*tarball, LIMIT));
+    }
+
+    #[test]
+    fn read_file_test() {
+        let tarball = tarball();
+
+        let contents = assert_ok!(read_file("foo-0.0.1", &*tarball, LIMIT, "src/lib.rs"));
+        assert_eq!(assert_some!(contents), b"pub fn foo() {}\n");
+
+        let contents = assert_ok!(read_file("foo-0.0.1", &*tarball, LIMIT, "src/main.rs"));
+        assert_none!(contents);
+
+        let contents = assert_ok!(read_file("foo-0.0.1", &*tarball, LIMIT, ""));
+        assert_none!(contents);
+
+        assert_err!(read_file("bar-0.0.1", &*tarball, LIMIT, "src/lib.rs"));
+    }
+
+    #[test]
+    fn list_files_rejects_symlinks() {
+        let mut builder = TarballBuilder::new().add_file("foo-0.0.1/src/lib.rs", b"");
+
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_size(0);
+        assert_ok!(builder
+            .as_mut()
+            .append_link(&mut header, "foo-0.0.1/link", "/etc/passwd"));
+
+        let tarball = builder.build();
+        assert_err!(list_files("foo-0.0.1", &*tarball, LIMIT));
+    }
+}
@@ -4,6 +4,7 @@ extern crate claims;
 
 #[cfg(any(feature = "builder", test))]
 pub use crate::builder::TarballBuilder;
+pub use crate::files::{list_files, read_file, FileKind, TarballFile};
 use crate::limit_reader::LimitErrorReader;
 use crate::manifest::validate_manifest;
 pub use crate::vcs_info::CargoVcsInfo;
@@ -17,6 +18,7 @@ use tracing::instrument;
 
 #[cfg(any(feature = "builder", test))]
 mod builder;
+mod files;
 mod limit_reader;
 mod manifest;
 mod vcs_info;
@@ -53,15 +55,8 @@ pub fn process_tarball<R: Read>(
     tarball: R,
     max_unpack: u64,
 ) -> Result<TarballInfo, TarballError> {
-    // All our data is currently encoded with gzip
-    let decoder = GzDecoder::new(tarball);
-
-    // Don't let gzip decompression go into the weeeds, apply a fixed cap after
-    // which point we say the decompressed source is "too large".
-    let decoder = LimitErrorReader::new(decoder, max_unpack);
-
     // Use this I/O object now to take a peek inside
-    let mut archive = tar::Archive::new(decoder);
+    let mut archive = open_archive(tarball, max_unpack);
 
     let pkg_root = Path::new(&pkg_name);
 
@@ -71,27 +66,8 @@ pub fn process_tarball<R: Read>(
     for entry in archive.entries()? {
         let mut entry = entry.map_err(TarballError::Malformed)?;
 
-        // Verify that all entries actually start with `$name-$vers/`.
-        // Historically Cargo didn't verify this on extraction so you could
-        // upload a tarball that contains both `foo-0.1.0/` source code as well
-        // as `bar-0.1.0/` source code, and this could overwrite other crates in
-        // the registry!
+        validate_entry(pkg_name, &entry)?;
         let entry_path = entry.path()?;
-        if !entry_path.starts_with(pkg_name) {
-            return Err(TarballError::InvalidPath(entry_path.display().to_string()));
-        }
-
-        // Historical versions of the `tar` crate which Cargo uses internally
-        // don't properly prevent hard links and symlinks from overwriting
-        // arbitrary files on the filesystem. As a bit of a hammer we reject any
-        // tarball with these sorts of links. Cargo doesn't currently ever
-        // generate a tarball with these file types so this should work for now.
-        let entry_type = entry.header().entry_type();
-        if entry_type.is_hard_link() || entry_type.is_symlink() {
-            return Err(TarballError::UnexpectedSymlink(
-                entry_path.display().to_string(),
-            ));
-        }
 
         // Let's go hunting for the VCS info and crate manifest. The only valid place for these is
         // in the package root in the tarball.
@@ -139,6 +115,43 @@ pub fn process_tarball<R: Read>(
     Ok(TarballInfo { manifest, vcs_info })
 }
 
+fn open_archive<R: Read>(tarball: R, max_unpack: u64) -> tar::Archive<impl Read> {
+    // All our data is currently encoded with gzip
+    let decoder = GzDecoder::new(tarball);
+
+    // Don't let gzip decompression go into the weeeds, apply a fixed cap after
+    // which point we say the decompressed source is "too large".
+    let decoder = LimitErrorReader::new(decoder, max_unpack);
+
+    tar::Archive::new(decoder)
+}
+
+fn validate_entry<R: Read>(pkg_name: &str, entry: &tar::Entry<'_, R>) -> Result<(), TarballError> {
+    // Verify that all entries actually start with `$name-$vers/`.
+    // Historically Cargo didn't verify this on extraction so you could
+    // upload a tarball that contains both `foo-0.1.0/` source code as well
+    // as `bar-0.1.0/` source code, and this could overwrite other crates in
+    // the registry!
+    let entry_path = entry.path()?;
+    if !entry_path.starts_with(pkg_name) {
+        return Err(TarballError::InvalidPath(entry_path.display().to_string()));
+    }
+
+    // Historical versions of the `tar` crate which Cargo uses internally
+    // don't properly prevent hard links and symlinks from overwriting
+    // arbitrary files on the filesystem. As a bit of a hammer we reject any
+    // tarball with these sorts of links. Cargo doesn't currently ever
+    // generate a tarball with these file types so this should work for now.
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_hard_link() || entry_type.is_symlink() {
+        return Err(TarballError::UnexpectedSymlink(
+            entry_path.display().to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::process_tarball;
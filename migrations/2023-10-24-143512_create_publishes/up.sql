@@ -0,0 +1,18 @@
+CREATE TABLE publishes (
+    id SERIAL PRIMARY KEY,
+    version_id INTEGER NOT NULL REFERENCES versions(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    api_token_id INTEGER REFERENCES api_tokens(id),
+    status INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    tarball BYTEA,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE publishes IS 'Uploads accepted by the `PUT /api/v1/crates/new` endpoint, which are finished by the `process_publish` background job.';
+COMMENT ON COLUMN publishes.status IS '`0` = queued, `1` = processing, `2` = succeeded, `3` = failed';
+COMMENT ON COLUMN publishes.error IS 'Error message of the last failed processing attempt.';
+COMMENT ON COLUMN publishes.tarball IS 'Uploaded `.crate` file. This is cleared once the file has been uploaded to the storage backend.';
+
+CREATE INDEX publishes_version_id_index ON publishes (version_id);
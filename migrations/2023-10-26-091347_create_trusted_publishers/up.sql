@@ -0,0 +1,17 @@
+CREATE TABLE trusted_publishers (
+    id SERIAL PRIMARY KEY,
+    crate_id INTEGER NOT NULL REFERENCES crates(id) ON DELETE CASCADE,
+    repository VARCHAR NOT NULL,
+    workflow_filename VARCHAR NOT NULL,
+    environment VARCHAR,
+    created_by INTEGER NOT NULL REFERENCES users(id),
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE trusted_publishers IS 'GitHub Actions workflows that are allowed to exchange their OIDC tokens for short-lived, crate-scoped API tokens.';
+COMMENT ON COLUMN trusted_publishers.repository IS 'GitHub repository in `owner/name` format. Compared case-insensitively.';
+COMMENT ON COLUMN trusted_publishers.workflow_filename IS 'Filename of the workflow in the `.github/workflows` directory of the repository, e.g. `release.yml`.';
+COMMENT ON COLUMN trusted_publishers.environment IS 'GitHub Actions environment the workflow has to run in. `NULL` allows any environment.';
+COMMENT ON COLUMN trusted_publishers.created_by IS 'Owner that configured the trusted publisher. API tokens are minted on behalf of this user.';
+
+CREATE INDEX trusted_publishers_crate_id_index ON trusted_publishers (crate_id);
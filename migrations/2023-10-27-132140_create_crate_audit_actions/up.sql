@@ -0,0 +1,18 @@
+CREATE TABLE crate_audit_actions (
+    id SERIAL PRIMARY KEY,
+    crate_name VARCHAR NOT NULL,
+    user_id INTEGER REFERENCES users(id),
+    api_token_id INTEGER REFERENCES api_tokens(id),
+    action INTEGER NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    time TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE crate_audit_actions IS 'Audit log of owner, token and crate settings changes. Version level actions are recorded in `version_owner_actions` instead.';
+COMMENT ON COLUMN crate_audit_actions.crate_name IS 'Name of the affected crate. This intentionally is not a foreign key to the `crates` table, so that the audit log survives the deletion of the crate.';
+COMMENT ON COLUMN crate_audit_actions.user_id IS 'User that performed the action, or `NULL` if it was performed by the crates.io team via `crates-admin`.';
+COMMENT ON COLUMN crate_audit_actions.api_token_id IS 'API token that was used to perform the action, if any.';
+COMMENT ON COLUMN crate_audit_actions.action IS '`0` = invite_owner, `1` = accept_invitation, `2` = decline_invitation, `3` = add_team, `4` = remove_owner, `5` = create_token, `6` = revoke_token, `7` = add_trusted_publisher, `8` = remove_trusted_publisher, `9` = admin_transfer, `10` = admin_delete_version, `11` = admin_delete_crate';
+COMMENT ON COLUMN crate_audit_actions.details IS 'Action specific details, like the login of the invited owner or the name of the created API token.';
+
+CREATE INDEX crate_audit_actions_crate_name_index ON crate_audit_actions (crate_name, id);
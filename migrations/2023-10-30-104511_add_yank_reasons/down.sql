@@ -0,0 +1,3 @@
+ALTER TABLE version_owner_actions
+    DROP COLUMN yank_category,
+    DROP COLUMN yank_message;
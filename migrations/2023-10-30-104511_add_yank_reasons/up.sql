@@ -0,0 +1,6 @@
+ALTER TABLE version_owner_actions
+    ADD COLUMN yank_category INTEGER,
+    ADD COLUMN yank_message TEXT;
+
+COMMENT ON COLUMN version_owner_actions.yank_category IS 'Optional category of the reason for a `yank` action: `0` = other, `1` = security, `2` = broken_build, `3` = mistake';
+COMMENT ON COLUMN version_owner_actions.yank_message IS 'Optional free-form message explaining why the version was yanked.';
@@ -0,0 +1,10 @@
+ALTER TABLE crates
+    ADD COLUMN deprecation INTEGER,
+    ADD COLUMN deprecation_message TEXT,
+    ADD COLUMN successor VARCHAR;
+
+COMMENT ON COLUMN crates.deprecation IS 'Deprecation state of the crate as set by its owners: `NULL` = not deprecated, `0` = deprecated, `1` = unmaintained';
+COMMENT ON COLUMN crates.deprecation_message IS 'Optional free-form message explaining why the crate is deprecated.';
+COMMENT ON COLUMN crates.successor IS 'Optional name of the crate that replaces this deprecated crate.';
+
+COMMENT ON COLUMN crate_audit_actions.action IS '`0` = invite_owner, `1` = accept_invitation, `2` = decline_invitation, `3` = add_team, `4` = remove_owner, `5` = create_token, `6` = revoke_token, `7` = add_trusted_publisher, `8` = remove_trusted_publisher, `9` = admin_transfer, `10` = admin_delete_version, `11` = admin_delete_crate, `12` = deprecate, `13` = undeprecate';
@@ -0,0 +1,34 @@
+CREATE TABLE advisories (
+    id VARCHAR PRIMARY KEY,
+    crate_id INTEGER NOT NULL REFERENCES crates(id) ON DELETE CASCADE,
+    title VARCHAR NOT NULL,
+    description TEXT NOT NULL,
+    date DATE NOT NULL,
+    url VARCHAR,
+    aliases TEXT[] NOT NULL DEFAULT '{}',
+    informational VARCHAR,
+    patched TEXT[] NOT NULL DEFAULT '{}',
+    unaffected TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE advisories IS 'Security advisories imported from a RustSec-format advisory database.';
+COMMENT ON COLUMN advisories.id IS 'Identifier of the advisory, e.g. `RUSTSEC-2023-0001`.';
+COMMENT ON COLUMN advisories.date IS 'Date on which the advisory was reported.';
+COMMENT ON COLUMN advisories.aliases IS 'Other identifiers of the advisory, e.g. CVE or GHSA identifiers.';
+COMMENT ON COLUMN advisories.informational IS 'Kind of informational advisory, e.g. `unmaintained` or `unsound`. `NULL` for vulnerabilities.';
+COMMENT ON COLUMN advisories.patched IS 'Version requirements matching the versions that contain a fix.';
+COMMENT ON COLUMN advisories.unaffected IS 'Version requirements matching the versions that were never affected.';
+COMMENT ON COLUMN advisories.updated_at IS 'Time at which the advisory was last imported.';
+
+CREATE INDEX advisories_crate_id_index ON advisories (crate_id);
+
+CREATE TABLE version_advisories (
+    version_id INTEGER NOT NULL REFERENCES versions(id) ON DELETE CASCADE,
+    advisory_id VARCHAR NOT NULL REFERENCES advisories(id) ON DELETE CASCADE,
+    PRIMARY KEY (version_id, advisory_id)
+);
+
+COMMENT ON TABLE version_advisories IS 'Versions that are affected by an advisory, i.e. that are matched by neither the `patched` nor the `unaffected` requirements of the advisory.';
+
+CREATE INDEX version_advisories_advisory_id_index ON version_advisories (advisory_id);
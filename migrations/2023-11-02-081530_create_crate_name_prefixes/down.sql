@@ -0,0 +1,2 @@
+DROP TABLE crate_name_prefix_owners;
+DROP TABLE crate_name_prefixes;
@@ -0,0 +1,21 @@
+CREATE TABLE crate_name_prefixes (
+    id SERIAL PRIMARY KEY,
+    prefix VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE crate_name_prefixes IS 'Crate name prefixes that were claimed by an organization. New crates named `<prefix>` or `<prefix>-*` can only be published by the owners of the prefix.';
+COMMENT ON COLUMN crate_name_prefixes.prefix IS 'The claimed prefix, without the trailing separator. Compared using `canon_crate_name()`, so `-` and `_` are interchangeable.';
+
+CREATE UNIQUE INDEX crate_name_prefixes_canon_prefix_index ON crate_name_prefixes (canon_crate_name(prefix));
+
+CREATE TABLE crate_name_prefix_owners (
+    prefix_id INTEGER NOT NULL REFERENCES crate_name_prefixes(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    PRIMARY KEY (prefix_id, user_id)
+);
+
+COMMENT ON TABLE crate_name_prefix_owners IS 'Users that are allowed to publish new crates under a claimed crate name prefix, and to manage the owners of the prefix.';
+
+CREATE INDEX crate_name_prefix_owners_user_id_index ON crate_name_prefix_owners (user_id);
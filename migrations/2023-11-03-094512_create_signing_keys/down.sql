@@ -0,0 +1,2 @@
+DROP TABLE version_signatures;
+DROP TABLE signing_keys;
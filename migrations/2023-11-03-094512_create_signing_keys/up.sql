@@ -0,0 +1,26 @@
+CREATE TABLE signing_keys (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    public_key TEXT NOT NULL,
+    fingerprint VARCHAR NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    revoked_at TIMESTAMP
+);
+
+COMMENT ON TABLE signing_keys IS 'Public keys that users registered to sign the crate files they publish.';
+COMMENT ON COLUMN signing_keys.public_key IS 'ECDSA P-256 public key in PEM format.';
+COMMENT ON COLUMN signing_keys.fingerprint IS 'Hex-encoded SHA-256 digest of the DER-encoded public key, used to reference the key when publishing.';
+COMMENT ON COLUMN signing_keys.revoked_at IS 'Revoked keys can not be used for new signatures anymore, but existing signatures stay verifiable.';
+
+CREATE INDEX signing_keys_user_id_index ON signing_keys (user_id);
+
+CREATE TABLE version_signatures (
+    version_id INTEGER PRIMARY KEY REFERENCES versions(id) ON DELETE CASCADE,
+    signing_key_id INTEGER NOT NULL REFERENCES signing_keys(id) ON DELETE CASCADE,
+    signature BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE version_signatures IS 'Detached signatures of the crate files of published versions. The signature was verified against the uploaded crate file at publish time.';
+COMMENT ON COLUMN version_signatures.signature IS 'DER-encoded ECDSA P-256 (SHA-256) signature of the crate file.';
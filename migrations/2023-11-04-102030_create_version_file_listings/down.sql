@@ -0,0 +1 @@
+DROP TABLE version_file_listings;
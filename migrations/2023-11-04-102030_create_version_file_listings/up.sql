@@ -0,0 +1,8 @@
+CREATE TABLE version_file_listings (
+    version_id INTEGER PRIMARY KEY REFERENCES versions(id) ON DELETE CASCADE,
+    files JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE version_file_listings IS 'Listings of the files inside of the crate files of published versions.';
+COMMENT ON COLUMN version_file_listings.files IS 'JSON array of `{"path", "size", "type"}` objects, with paths relative to the root directory of the crate file.';
@@ -0,0 +1,4 @@
+COMMENT ON COLUMN crate_audit_actions.action IS '`0` = invite_owner, `1` = accept_invitation, `2` = decline_invitation, `3` = add_team, `4` = remove_owner, `5` = create_token, `6` = revoke_token, `7` = add_trusted_publisher, `8` = remove_trusted_publisher, `9` = admin_transfer, `10` = admin_delete_version, `11` = admin_delete_crate, `12` = deprecate, `13` = undeprecate';
+
+DROP TABLE webhook_deliveries;
+DROP TABLE webhooks;
@@ -0,0 +1,43 @@
+CREATE TABLE webhooks (
+    id SERIAL PRIMARY KEY,
+    crate_id INTEGER NOT NULL REFERENCES crates(id) ON DELETE CASCADE,
+    url VARCHAR NOT NULL,
+    secret VARCHAR NOT NULL,
+    events TEXT[] NOT NULL,
+    created_by INTEGER NOT NULL REFERENCES users(id),
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE webhooks IS 'HTTPS endpoints that are notified about events of a crate.';
+COMMENT ON COLUMN webhooks.url IS 'URL that the event payloads are `POST`ed to.';
+COMMENT ON COLUMN webhooks.secret IS 'Secret that is used to sign the event payloads with HMAC-SHA256. Only shown to the owner once, when the webhook is created.';
+COMMENT ON COLUMN webhooks.events IS 'Events that the webhook is subscribed to, e.g. `publish` or `owner-change`.';
+COMMENT ON COLUMN webhooks.created_by IS 'Owner that registered the webhook.';
+
+CREATE INDEX webhooks_crate_id_index ON webhooks (crate_id);
+
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event VARCHAR NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_status INTEGER,
+    last_error VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    last_attempt_at TIMESTAMP,
+    delivered_at TIMESTAMP,
+    failed_at TIMESTAMP
+);
+
+COMMENT ON TABLE webhook_deliveries IS 'Delivery log of the events that were sent, or are still being sent, to the webhooks.';
+COMMENT ON COLUMN webhook_deliveries.payload IS 'JSON body that is sent to the webhook.';
+COMMENT ON COLUMN webhook_deliveries.attempts IS 'Number of delivery attempts that have been made so far.';
+COMMENT ON COLUMN webhook_deliveries.last_status IS 'HTTP status code of the response to the last delivery attempt, if any response was received.';
+COMMENT ON COLUMN webhook_deliveries.last_error IS 'Error message of the last failed delivery attempt.';
+COMMENT ON COLUMN webhook_deliveries.delivered_at IS 'Time of the successful delivery. `NULL` while the delivery is pending or has failed.';
+COMMENT ON COLUMN webhook_deliveries.failed_at IS 'Time at which the delivery was given up on after too many failed attempts.';
+
+CREATE INDEX webhook_deliveries_webhook_id_index ON webhook_deliveries (webhook_id);
+
+COMMENT ON COLUMN crate_audit_actions.action IS '`0` = invite_owner, `1` = accept_invitation, `2` = decline_invitation, `3` = add_team, `4` = remove_owner, `5` = create_token, `6` = revoke_token, `7` = add_trusted_publisher, `8` = remove_trusted_publisher, `9` = admin_transfer, `10` = admin_delete_version, `11` = admin_delete_crate, `12` = deprecate, `13` = undeprecate, `14` = add_webhook, `15` = remove_webhook';
@@ -0,0 +1,8 @@
+CREATE TABLE feed_tokens (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token BYTEA NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE feed_tokens IS 'Tokens that authenticate the feed of new releases of the crates that a user follows. Feed readers can not log in, so the token is part of the feed URL.';
+COMMENT ON COLUMN feed_tokens.token IS 'SHA256 hash of the token. The plaintext token is only shown to the user once, when it is created.';
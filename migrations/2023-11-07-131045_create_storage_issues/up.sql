@@ -0,0 +1,16 @@
+CREATE TABLE storage_issues (
+    id SERIAL PRIMARY KEY,
+    kind INTEGER NOT NULL,
+    crate_name VARCHAR NOT NULL,
+    version VARCHAR NOT NULL,
+    version_id INTEGER REFERENCES versions(id) ON DELETE CASCADE,
+    expected_checksum CHAR(64),
+    actual_checksum CHAR(64),
+    detected_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE storage_issues IS 'Problems with the crate files in the storage backend that were found by the last run of the `verify_storage` background job. The table is replaced on every run.';
+COMMENT ON COLUMN storage_issues.kind IS '`0` = missing, `1` = checksum_mismatch, `2` = orphaned';
+COMMENT ON COLUMN storage_issues.version_id IS 'Version that the crate file belongs to. `NULL` for orphaned crate files, which have no matching version.';
+COMMENT ON COLUMN storage_issues.expected_checksum IS 'SHA256 checksum of the version in the `versions` table.';
+COMMENT ON COLUMN storage_issues.actual_checksum IS 'SHA256 checksum of the stored crate file.';
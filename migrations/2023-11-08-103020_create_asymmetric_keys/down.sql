@@ -0,0 +1 @@
+DROP TABLE asymmetric_keys;
@@ -0,0 +1,17 @@
+CREATE TABLE asymmetric_keys (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    public_key TEXT NOT NULL,
+    key_id VARCHAR NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+COMMENT ON TABLE asymmetric_keys IS 'Public keys that users registered to authenticate cargo requests with signed PASETO tokens instead of API tokens.';
+COMMENT ON COLUMN asymmetric_keys.public_key IS 'ECDSA P-384 public key in its PASERK `k3.public.` encoding.';
+COMMENT ON COLUMN asymmetric_keys.key_id IS 'PASERK `k3.pid.` ID of the public key, which cargo sends in the footer of every token.';
+COMMENT ON COLUMN asymmetric_keys.revoked_at IS 'Revoked keys can not be used to authenticate requests anymore.';
+
+CREATE INDEX asymmetric_keys_user_id_index ON asymmetric_keys (user_id);
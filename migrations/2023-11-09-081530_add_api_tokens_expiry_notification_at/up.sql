@@ -0,0 +1,3 @@
+ALTER TABLE api_tokens ADD COLUMN expiry_notification_at TIMESTAMP;
+
+COMMENT ON COLUMN api_tokens.expiry_notification_at IS 'Time at which the owner of the token was reminded that the token is about to expire.';
@@ -0,0 +1,14 @@
+CREATE TABLE api_token_usages (
+    id BIGSERIAL PRIMARY KEY,
+    api_token_id INTEGER NOT NULL REFERENCES api_tokens(id) ON DELETE CASCADE,
+    used_at TIMESTAMP NOT NULL DEFAULT now(),
+    ip_address VARCHAR,
+    user_agent VARCHAR,
+    endpoint VARCHAR
+);
+
+COMMENT ON TABLE api_token_usages IS 'Recent uses of API tokens, to help users find out where and how a possibly leaked token was used. Old rows are pruned by the daily database maintenance job.';
+COMMENT ON COLUMN api_token_usages.ip_address IS 'IP address of the client, as determined by the `real_ip` middleware.';
+COMMENT ON COLUMN api_token_usages.endpoint IS 'HTTP method and matched route of the request, e.g. `PUT /api/v1/crates/new`.';
+
+CREATE INDEX api_token_usages_api_token_id_used_at_index ON api_token_usages (api_token_id, used_at DESC);
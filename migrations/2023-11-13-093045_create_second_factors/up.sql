@@ -0,0 +1,30 @@
+CREATE TABLE totp_secrets (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    enabled_at TIMESTAMP,
+    last_used_step BIGINT
+);
+
+COMMENT ON TABLE totp_secrets IS 'Shared secrets of the TOTP authenticator apps that users enrolled as a second factor.';
+COMMENT ON COLUMN totp_secrets.secret IS 'Shared secret that the one-time codes are derived from.';
+COMMENT ON COLUMN totp_secrets.enabled_at IS 'Time at which the enrollment was confirmed with a valid code. Secrets without this timestamp are not used as a second factor yet.';
+COMMENT ON COLUMN totp_secrets.last_used_step IS 'Time step of the last accepted code, so that every code can only be used once.';
+
+CREATE TABLE webauthn_credentials (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    credential_id VARCHAR NOT NULL UNIQUE,
+    public_key BYTEA NOT NULL,
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMP
+);
+
+COMMENT ON TABLE webauthn_credentials IS 'WebAuthn credentials, like security keys or passkeys, that users enrolled as a second factor.';
+COMMENT ON COLUMN webauthn_credentials.credential_id IS 'Base64url-encoded ID that the authenticator assigned to the credential.';
+COMMENT ON COLUMN webauthn_credentials.public_key IS 'DER-encoded ECDSA P-256 public key of the credential.';
+COMMENT ON COLUMN webauthn_credentials.sign_count IS 'Signature counter reported by the authenticator, used to detect cloned authenticators.';
+
+CREATE INDEX webauthn_credentials_user_id_index ON webauthn_credentials (user_id);
@@ -0,0 +1,41 @@
+use crate::db;
+use crate::storage::Storage;
+use crate::worker::jobs::{check_index, enqueue_fixes};
+use crates_io_index::{Repository, RepositoryConfig};
+
+#[derive(clap::Parser, Debug)]
+#[command(
+    name = "check-index",
+    about = "Compare the git index and the sparse index with the database"
+)]
+pub struct Opts {
+    /// Enqueue index sync jobs for the crates whose index files drifted
+    /// from the database.
+    #[arg(long)]
+    fix: bool,
+}
+
+pub fn run(opts: Opts) -> anyhow::Result<()> {
+    let conn = &mut db::oneoff_connection()?;
+    let storage = Storage::from_environment();
+
+    println!("fetching git repo");
+    let config = RepositoryConfig::from_environment()?;
+    let repo = Repository::open(&config)?;
+    repo.reset_head()?;
+    println!("HEAD is at {}", repo.head_oid()?);
+
+    let drifts = check_index(conn, &repo, &storage)?;
+    for drift in &drifts {
+        println!("{drift}");
+    }
+
+    println!("found {} drifted index files", drifts.len());
+
+    if opts.fix && !drifts.is_empty() {
+        enqueue_fixes(conn, &drifts)?;
+        println!("enqueued index sync jobs for the drifted index files");
+    }
+
+    Ok(())
+}
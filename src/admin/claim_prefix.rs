@@ -0,0 +1,59 @@
+use crate::admin::dialoguer;
+use crate::db;
+use crate::models::{CrateNamePrefix, NewCrateNamePrefix, User};
+use crate::schema::users;
+use diesel::prelude::*;
+
+#[derive(clap::Parser, Debug)]
+#[command(
+    name = "claim-prefix",
+    about = "Claim a crate name prefix, so that only its owners can publish new crates under it."
+)]
+pub struct Opts {
+    /// The prefix to claim, e.g. `mycompany` for `mycompany-*` crates
+    prefix: String,
+    /// GitHub login of the initial owner of the prefix
+    owner: String,
+    /// Don't ask for confirmation: yes, we are sure. Best for scripting.
+    #[arg(short, long)]
+    yes: bool,
+}
+
+pub fn run(opts: Opts) -> anyhow::Result<()> {
+    let conn = &mut db::oneoff_connection()?;
+    conn.transaction(|conn| claim(opts, conn))?;
+    Ok(())
+}
+
+fn claim(opts: Opts, conn: &mut PgConnection) -> anyhow::Result<()> {
+    // Allow the prefix to be specified as `mycompany-*` or `mycompany::` too
+    let prefix = opts.prefix.trim_end_matches(['*', ':', '-', '_']);
+    if !CrateNamePrefix::valid_prefix(prefix) {
+        anyhow::bail!("`{prefix}` is not a valid crate name prefix");
+    }
+
+    if let Some(existing) = CrateNamePrefix::find_by_prefix(conn, prefix).optional()? {
+        anyhow::bail!("the prefix `{}` has already been claimed", existing.prefix);
+    }
+
+    let owner: User = users::table
+        .filter(users::gh_login.eq(&opts.owner))
+        .first(conn)?;
+
+    if !opts.yes {
+        let prompt = format!(
+            "Are you sure you want to claim the prefix `{prefix}` for {}?",
+            owner.gh_login
+        );
+        if !dialoguer::confirm(&prompt) {
+            return Ok(());
+        }
+    }
+
+    let prefix = NewCrateNamePrefix { prefix }.insert(conn)?;
+    prefix.add_owner(conn, owner.id)?;
+
+    println!("claimed prefix `{}` for {}", prefix.prefix, owner.gh_login);
+
+    Ok(())
+}
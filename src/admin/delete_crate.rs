@@ -1,3 +1,4 @@
+use crate::models::{insert_crate_audit_action, CrateAction};
 use crate::storage::Storage;
 use crate::worker::jobs;
 use crate::{admin::dialoguer, db, schema::crates};
@@ -61,6 +62,14 @@ pub fn run(opts: Opts) -> anyhow::Result<()> {
             info!(%name, "Deleting crate from the database");
             if let Err(error) = diesel::delete(crates::table.find(id)).execute(conn) {
                 warn!(%name, %id, ?error, "Failed to delete crate from the database");
+            } else {
+                let action = CrateAction::AdminDeleteCrate;
+                let details = serde_json::json!({ "crate_id": id });
+                if let Err(error) =
+                    insert_crate_audit_action(conn, name, None, None, action, details)
+                {
+                    warn!(%name, ?error, "Failed to record crate deletion in the audit log");
+                }
             }
         } else {
             info!(%name, "Skipping missing crate");
@@ -1,3 +1,4 @@
+use crate::models::{insert_crate_audit_action, CrateAction};
 use crate::schema::crates;
 use crate::storage::Storage;
 use crate::worker::jobs;
@@ -56,6 +57,8 @@ pub fn run(opts: Opts) -> anyhow::Result<()> {
     )
     .execute(conn);
 
+    let deleted_any = matches!(result, Ok(num_deleted) if num_deleted > 0);
+
     match result {
         Ok(num_deleted) if num_deleted == opts.versions.len() => {}
         Ok(num_deleted) => {
@@ -70,6 +73,15 @@ pub fn run(opts: Opts) -> anyhow::Result<()> {
         }
     }
 
+    if deleted_any {
+        let action = CrateAction::AdminDeleteVersion;
+        let details = serde_json::json!({ "versions": opts.versions });
+        if let Err(error) = insert_crate_audit_action(conn, crate_name, None, None, action, details)
+        {
+            warn!(%crate_name, ?error, "Failed to record version deletion in the audit log");
+        }
+    }
+
     info!(%crate_name, "Enqueuing index sync jobs");
     if let Err(error) = jobs::enqueue_sync_to_index(crate_name, conn) {
         warn!(%crate_name, ?error, "Failed to enqueue index sync jobs");
@@ -94,6 +106,15 @@ pub fn run(opts: Opts) -> anyhow::Result<()> {
             }
             Ok(_) => {}
         }
+
+        debug!(%crate_name, %version, "Deleting signature file from S3");
+        match rt.block_on(store.delete_crate_signature(crate_name, version)) {
+            Err(object_store::Error::NotFound { .. }) => {}
+            Err(error) => {
+                warn!(%crate_name, %version, ?error, "Failed to delete signature file from S3")
+            }
+            Ok(_) => {}
+        }
     }
 
     Ok(())
@@ -1,10 +1,11 @@
 use crate::db;
-use crate::schema::background_jobs;
+use crate::schema::{background_jobs, crates, versions};
 use crate::worker::jobs;
 use crate::worker::swirl::BackgroundJob;
 use anyhow::Result;
 use diesel::prelude::*;
 use secrecy::{ExposeSecret, SecretString};
+use std::path::PathBuf;
 
 #[derive(clap::Parser, Debug)]
 #[command(
@@ -26,6 +27,32 @@ pub enum Command {
         #[arg(long = "dry-run")]
         dry_run: bool,
     },
+    ImportAdvisories {
+        /// Path to a local checkout of a RustSec-format advisory database
+        #[arg(env = "ADVISORY_DB_PATH")]
+        path: PathBuf,
+    },
+    /// List the files inside of the crate files of all versions of a crate
+    ListCrateFiles {
+        name: String,
+    },
+    /// Compare the stored crate files with the checksums in the database
+    VerifyStorage,
+    /// Compare the git index and the sparse index with the database
+    CheckIndex {
+        /// Enqueue index sync jobs for the crates whose index files drifted
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Remind users of expiring API tokens and revoke long expired tokens
+    ProcessExpiringTokens {
+        /// Number of days before the expiry at which users are reminded
+        #[arg(long, default_value_t = 3)]
+        notify_days: i64,
+        /// Number of days after the expiry at which tokens are revoked
+        #[arg(long, default_value_t = 30)]
+        revoke_days: i64,
+    },
 }
 
 pub fn run(command: Command) -> Result<()> {
@@ -55,5 +82,25 @@ pub fn run(command: Command) -> Result<()> {
         Command::NormalizeIndex { dry_run } => {
             Ok(jobs::NormalizeIndex::new(dry_run).enqueue(conn)?)
         }
+        Command::ImportAdvisories { path } => Ok(jobs::ImportAdvisories::new(path).enqueue(conn)?),
+        Command::ListCrateFiles { name } => {
+            let version_ids: Vec<i32> = versions::table
+                .inner_join(crates::table)
+                .filter(crates::name.eq(&name))
+                .select(versions::id)
+                .load(conn)?;
+
+            for version_id in version_ids {
+                jobs::ListCrateFiles::new(version_id).enqueue(conn)?;
+            }
+
+            Ok(())
+        }
+        Command::VerifyStorage => Ok(jobs::VerifyStorage.enqueue(conn)?),
+        Command::CheckIndex { fix } => Ok(jobs::CheckIndex::new(fix).enqueue(conn)?),
+        Command::ProcessExpiringTokens {
+            notify_days,
+            revoke_days,
+        } => Ok(jobs::ProcessExpiringTokens::new(notify_days, revoke_days).enqueue(conn)?),
     }
 }
@@ -1,3 +1,5 @@
+pub mod check_index;
+pub mod claim_prefix;
 pub mod delete_crate;
 pub mod delete_version;
 pub mod dialoguer;
@@ -10,5 +12,6 @@ pub mod render_readmes;
 pub mod test_pagerduty;
 pub mod transfer_crates;
 pub mod upload_index;
+pub mod verify_storage;
 pub mod verify_token;
 pub mod yank_version;
@@ -1,7 +1,7 @@
 use crate::{
     admin::dialoguer,
     db,
-    models::{Crate, OwnerKind, User},
+    models::{insert_crate_audit_action, Crate, CrateAction, OwnerKind, User},
     schema::{crate_owners, crates, users},
 };
 use std::process::exit;
@@ -65,6 +65,10 @@ fn transfer(opts: Opts, conn: &mut PgConnection) -> anyhow::Result<()> {
         if owners.len() != 1 {
             println!("warning: not exactly one owner for {}", krate.name);
         }
+
+        let action = CrateAction::AdminTransfer;
+        let details = serde_json::json!({ "from": from.gh_login, "to": to.gh_login });
+        insert_crate_audit_action(conn, &krate.name, None, None, action, details)?;
     }
 
     diesel::update(crate_owners)
@@ -0,0 +1,45 @@
+use crate::db;
+use crate::models::{StorageIssue, StorageIssueKind};
+use crate::storage::Storage;
+use crate::worker::jobs::verify_storage;
+
+#[derive(clap::Parser, Debug)]
+#[command(
+    name = "verify-storage",
+    about = "Compare the stored crate files with the checksums in the database"
+)]
+pub struct Opts {
+    /// Only print the issues, without replacing the results of the last
+    /// verification in the `storage_issues` table.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+}
+
+pub fn run(opts: Opts) -> anyhow::Result<()> {
+    let conn = &mut db::oneoff_connection()?;
+    let storage = Storage::from_environment();
+
+    let issues = verify_storage(conn, &storage)?;
+
+    for issue in &issues {
+        let name = &issue.crate_name;
+        let version = &issue.version;
+        match issue.kind {
+            StorageIssueKind::Missing => println!("missing: {name} {version}"),
+            StorageIssueKind::ChecksumMismatch => println!(
+                "checksum mismatch: {name} {version} (expected {}, found {})",
+                issue.expected_checksum.as_deref().unwrap_or_default(),
+                issue.actual_checksum.as_deref().unwrap_or_default(),
+            ),
+            StorageIssueKind::Orphaned => println!("orphaned: {name} {version}"),
+        }
+    }
+
+    println!("found {} issues", issues.len());
+
+    if !opts.dry_run {
+        StorageIssue::replace_all(conn, &issues)?;
+    }
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ use crate::downloads_counter::DownloadsCounter;
 use crate::email::Emails;
 use crate::github::{GitHubClient, RealGitHubClient};
 use crate::metrics::{InstanceMetrics, ServiceMetrics};
+use crate::mirror::Mirror;
 use crate::rate_limiter::RateLimiter;
 use crate::storage::Storage;
 use axum::extract::{FromRef, FromRequestParts, State};
@@ -47,11 +48,14 @@ pub struct App {
     pub downloads_counter: DownloadsCounter,
 
     /// Backend used to send emails
-    pub emails: Emails,
+    pub emails: Arc<Emails>,
 
     /// Storage backend for crate files and other large objects.
     pub storage: Arc<Storage>,
 
+    /// Client for the upstream registry, if the server acts as a mirror
+    pub mirror: Option<Mirror>,
+
     /// Metrics related to the service as a whole
     pub service_metrics: ServiceMetrics,
 
@@ -168,8 +172,12 @@ impl App {
             github_oauth,
             version_id_cacher,
             downloads_counter: DownloadsCounter::new(),
-            emails: Emails::from_environment(&config),
+            emails: Arc::new(Emails::from_environment(&config)),
             storage: Arc::new(Storage::from_config(&config.storage)),
+            mirror: config
+                .mirror
+                .as_ref()
+                .map(|mirror| Mirror::new(mirror, &config.domain_name)),
             service_metrics: ServiceMetrics::new().expect("could not initialize service metrics"),
             instance_metrics,
             http_client,
@@ -1,21 +1,28 @@
 use crate::controllers;
 use crate::controllers::util::RequestPartsExt;
+use crate::middleware::app::RequestApp;
 use crate::middleware::log_request::RequestLogExt;
+use crate::middleware::real_ip::RealIp;
 use crate::middleware::session::RequestSession;
-use crate::models::token::{CrateScope, EndpointScope};
-use crate::models::{ApiToken, User};
+use crate::models::token::{CrateScope, EndpointScope, NewApiTokenUsage};
+use crate::models::{ApiToken, AsymmetricKey, User};
+use crate::util::asymmetric_token::{AsymmetricToken, AsymmetricTokenError, Claims, Mutation};
 use crate::util::errors::{
-    account_locked, forbidden, internal, AppError, AppResult, InsecurelyGeneratedTokenRevoked,
+    account_locked, forbidden, internal, second_factor_required, AppError, AppResult,
+    InsecurelyGeneratedTokenRevoked,
 };
+use axum::extract::MatchedPath;
 use chrono::Utc;
 use diesel::PgConnection;
-use http::header;
+use http::{header, Method};
 
 #[derive(Debug, Clone)]
 pub struct AuthCheck {
     allow_token: bool,
     endpoint_scope: Option<EndpointScope>,
     crate_name: Option<String>,
+    mutation: Option<Mutation>,
+    second_factor: bool,
 }
 
 impl AuthCheck {
@@ -27,6 +34,8 @@ impl AuthCheck {
             allow_token: true,
             endpoint_scope: None,
             crate_name: None,
+            mutation: None,
+            second_factor: false,
         }
     }
 
@@ -36,6 +45,8 @@ impl AuthCheck {
             allow_token: false,
             endpoint_scope: None,
             crate_name: None,
+            mutation: None,
+            second_factor: false,
         }
     }
 
@@ -44,6 +55,8 @@ impl AuthCheck {
             allow_token: self.allow_token,
             endpoint_scope: Some(endpoint_scope),
             crate_name: self.crate_name.clone(),
+            mutation: self.mutation.clone(),
+            second_factor: self.second_factor,
         }
     }
 
@@ -52,6 +65,32 @@ impl AuthCheck {
             allow_token: self.allow_token,
             endpoint_scope: self.endpoint_scope,
             crate_name: Some(crate_name.to_string()),
+            mutation: self.mutation.clone(),
+            second_factor: self.second_factor,
+        }
+    }
+
+    /// Sets the operation that asymmetric tokens have to be signed for to be
+    /// accepted by this endpoint.
+    pub fn with_mutation(&self, mutation: Mutation) -> Self {
+        Self {
+            allow_token: self.allow_token,
+            endpoint_scope: self.endpoint_scope,
+            crate_name: self.crate_name.clone(),
+            mutation: Some(mutation),
+            second_factor: self.second_factor,
+        }
+    }
+
+    /// Requires users that enrolled a second factor to have verified it
+    /// recently, if they are authenticated via a cookie session.
+    pub fn require_second_factor(&self) -> Self {
+        Self {
+            allow_token: self.allow_token,
+            endpoint_scope: self.endpoint_scope,
+            crate_name: self.crate_name.clone(),
+            mutation: self.mutation.clone(),
+            second_factor: true,
         }
     }
 
@@ -64,7 +103,10 @@ impl AuthCheck {
         let auth = authenticate(request, conn)?;
 
         if let Some(token) = auth.api_token() {
-            if !self.allow_token {
+            // Endpoints that don't accept API tokens in general can still be
+            // used with tokens that were explicitly scoped for them.
+            let is_scoped = self.endpoint_scope.is_some() && token.endpoint_scopes.is_some();
+            if !self.allow_token && !is_scoped {
                 let error_message =
                     "API Token authentication was explicitly disallowed for this API";
                 return Err(internal(error_message).chain(forbidden()));
@@ -81,6 +123,36 @@ impl AuthCheck {
             }
         }
 
+        if let Authentication::AsymmetricToken(token) = &auth {
+            if !self.allow_token {
+                let error_message =
+                    "Asymmetric token authentication was explicitly disallowed for this API";
+                return Err(internal(error_message).chain(forbidden()));
+            }
+
+            // Cargo signs tokens without a `mutation` claim for read-only
+            // requests, so they must not be accepted by endpoints that change
+            // anything.
+            let is_read = request.method() == Method::GET || request.method() == Method::HEAD;
+            if self.mutation.is_none() && !is_read {
+                let error_message = "Asymmetric tokens can not be used for this API";
+                return Err(internal(error_message).chain(forbidden()));
+            }
+
+            if let Err(error) = token.claims.validate_mutation(self.mutation.as_ref()) {
+                return Err(internal(error).chain(forbidden()));
+            }
+        }
+
+        if let Authentication::Cookie(cookie) = &auth {
+            if self.second_factor
+                && !request.session().has_recent_second_factor()
+                && cookie.user.has_second_factor(conn)?
+            {
+                return Err(second_factor_required());
+            }
+        }
+
         Ok(auth)
     }
 
@@ -120,6 +192,7 @@ impl AuthCheck {
 pub enum Authentication {
     Cookie(CookieAuthentication),
     Token(TokenAuthentication),
+    AsymmetricToken(AsymmetricTokenAuthentication),
 }
 
 #[derive(Debug)]
@@ -133,6 +206,12 @@ pub struct TokenAuthentication {
     user: User,
 }
 
+#[derive(Debug)]
+pub struct AsymmetricTokenAuthentication {
+    claims: Claims,
+    user: User,
+}
+
 impl Authentication {
     pub fn user_id(&self) -> i32 {
         self.user().id
@@ -153,6 +232,7 @@ impl Authentication {
         match self {
             Authentication::Cookie(cookie) => &cookie.user,
             Authentication::Token(token) => &token.user,
+            Authentication::AsymmetricToken(token) => &token.user,
         }
     }
 }
@@ -181,6 +261,54 @@ fn authenticate_via_cookie<T: RequestPartsExt>(
     Ok(Some(CookieAuthentication { user }))
 }
 
+#[instrument(skip_all)]
+fn authenticate_via_asymmetric_token<T: RequestPartsExt>(
+    req: &T,
+    conn: &mut PgConnection,
+) -> AppResult<Option<AsymmetricTokenAuthentication>> {
+    let maybe_authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .filter(|value| AsymmetricToken::is_asymmetric(value));
+
+    let Some(header_value) = maybe_authorization else {
+        return Ok(None);
+    };
+
+    let invalid_token = |error: AsymmetricTokenError| internal(error).chain(forbidden());
+
+    let token = AsymmetricToken::parse(header_value).map_err(invalid_token)?;
+
+    let index_urls = &req.app().config.index_urls;
+    if !index_urls.contains(&token.footer.url) {
+        let error = AsymmetricTokenError::UrlMismatch(token.footer.url.clone());
+        return Err(invalid_token(error));
+    }
+
+    let key = AsymmetricKey::find_active(conn, &token.footer.kip)?
+        .ok_or_else(|| internal("unknown asymmetric key").chain(forbidden()))?;
+
+    let public_key = key
+        .public_key()
+        .ok_or_else(|| internal("invalid public key in database"))?;
+
+    let claims = token.verify(&public_key).map_err(invalid_token)?;
+    claims.validate(Utc::now()).map_err(invalid_token)?;
+
+    key.update_last_used_at(conn);
+
+    let user = User::find(conn, key.user_id)
+        .map_err(|err| err.chain(internal("user_id from key not found in database")))?;
+
+    ensure_not_locked(&user)?;
+
+    req.request_log().add("uid", key.user_id);
+    req.request_log().add("asymmetric_key_id", key.id);
+
+    Ok(Some(AsymmetricTokenAuthentication { claims, user }))
+}
+
 #[instrument(skip_all)]
 fn authenticate_via_token<T: RequestPartsExt>(
     req: &T,
@@ -211,6 +339,8 @@ fn authenticate_via_token<T: RequestPartsExt>(
     req.request_log().add("uid", token.user_id);
     req.request_log().add("tokenid", token.id);
 
+    record_token_usage(req, conn, token.id);
+
     Ok(Some(TokenAuthentication { user, token }))
 }
 
@@ -224,6 +354,12 @@ fn authenticate<T: RequestPartsExt>(req: &T, conn: &mut PgConnection) -> AppResu
         Err(err) => return Err(err),
     }
 
+    match authenticate_via_asymmetric_token(req, conn) {
+        Ok(None) => {}
+        Ok(Some(auth)) => return Ok(Authentication::AsymmetricToken(auth)),
+        Err(err) => return Err(err),
+    }
+
     match authenticate_via_token(req, conn) {
         Ok(None) => {}
         Ok(Some(auth)) => return Ok(Authentication::Token(auth)),
@@ -234,6 +370,31 @@ fn authenticate<T: RequestPartsExt>(req: &T, conn: &mut PgConnection) -> AppResu
     return Err(internal("no cookie session or auth header found").chain(forbidden()));
 }
 
+/// Records where and how the token was used, so that users can review the
+/// uses of a token if they suspect that it was leaked.
+fn record_token_usage<T: RequestPartsExt>(req: &T, conn: &mut PgConnection, api_token_id: i32) {
+    let ip_address = req.extensions().get::<RealIp>().map(|ip| ip.to_string());
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|h| h.to_str().ok());
+
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| req.uri().path());
+
+    let usage = NewApiTokenUsage {
+        api_token_id,
+        ip_address,
+        user_agent,
+        endpoint: Some(format!("{} {path}", req.method())),
+    };
+
+    usage.insert(conn);
+}
+
 fn ensure_not_locked(user: &User) -> AppResult<()> {
     if let Some(reason) = &user.account_lock_reason {
         let still_locked = if let Some(until) = user.account_lock_until {
@@ -347,4 +508,17 @@ mod tests {
         assert!(!auth_check.crate_scope_matches(Some(&vec![cs("anyhow")])));
         assert!(!auth_check.crate_scope_matches(Some(&vec![cs("actix-*")])));
     }
+
+    #[test]
+    fn read_private_endpoint() {
+        let auth_check = AuthCheck::only_cookie().with_endpoint_scope(EndpointScope::ReadPrivate);
+
+        assert!(auth_check.endpoint_scope_matches(None));
+        assert!(auth_check.endpoint_scope_matches(Some(&vec![EndpointScope::ReadPrivate])));
+        assert!(!auth_check.endpoint_scope_matches(Some(&vec![EndpointScope::PublishUpdate])));
+        assert!(!auth_check.endpoint_scope_matches(Some(&vec![EndpointScope::ManageTokens])));
+
+        assert!(auth_check.crate_scope_matches(None));
+        assert!(!auth_check.crate_scope_matches(Some(&vec![cs("tokio-console")])));
+    }
 }
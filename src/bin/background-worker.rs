@@ -18,8 +18,10 @@ extern crate tracing;
 use crates_io::cloudfront::CloudFront;
 use crates_io::config;
 use crates_io::db::DieselPool;
+use crates_io::email::Emails;
 use crates_io::fastly::Fastly;
 use crates_io::storage::Storage;
+use crates_io::worker::jobs::GitIndexBatching;
 use crates_io::worker::swirl::Runner;
 use crates_io::worker::{Environment, RunnerExt};
 use crates_io::{db, ssh};
@@ -75,13 +77,23 @@ fn main() -> anyhow::Result<()> {
     let cloudfront = CloudFront::from_environment();
     let fastly = Fastly::from_environment();
     let storage = Arc::new(Storage::from_config(&config.storage));
+    let emails = Arc::new(Emails::from_environment(&config));
+    let git_index_batching = GitIndexBatching::from_environment()?;
 
     let client = Client::builder()
         .timeout(Duration::from_secs(45))
         .build()
         .expect("Couldn't build client");
 
-    let environment = Environment::new(repository, client, cloudfront, fastly, storage);
+    let environment = Environment::new(
+        repository,
+        client,
+        cloudfront,
+        fastly,
+        storage,
+        emails,
+        git_index_batching,
+    );
 
     let environment = Arc::new(environment);
 
@@ -4,8 +4,9 @@
 extern crate tracing;
 
 use crates_io::admin::{
-    delete_crate, delete_version, enqueue_job, git_import, migrate, populate, render_readmes,
-    test_pagerduty, transfer_crates, upload_index, verify_token, yank_version,
+    check_index, claim_prefix, delete_crate, delete_version, enqueue_job, git_import, migrate,
+    populate, render_readmes, test_pagerduty, transfer_crates, upload_index, verify_storage,
+    verify_token, yank_version,
 };
 
 #[derive(clap::Parser, Debug)]
@@ -18,10 +19,13 @@ enum Command {
     TestPagerduty(test_pagerduty::Opts),
     TransferCrates(transfer_crates::Opts),
     VerifyToken(verify_token::Opts),
+    VerifyStorage(verify_storage::Opts),
     Migrate(migrate::Opts),
     UploadIndex(upload_index::Opts),
     YankVersion(yank_version::Opts),
     GitImport(git_import::Opts),
+    ClaimPrefix(claim_prefix::Opts),
+    CheckIndex(check_index::Opts),
     #[clap(subcommand)]
     EnqueueJob(enqueue_job::Command),
 }
@@ -46,10 +50,13 @@ fn main() -> anyhow::Result<()> {
         Command::TestPagerduty(opts) => test_pagerduty::run(opts),
         Command::TransferCrates(opts) => transfer_crates::run(opts),
         Command::VerifyToken(opts) => verify_token::run(opts),
+        Command::VerifyStorage(opts) => verify_storage::run(opts),
         Command::Migrate(opts) => migrate::run(opts),
         Command::UploadIndex(opts) => upload_index::run(opts),
         Command::YankVersion(opts) => yank_version::run(opts),
         Command::GitImport(opts) => git_import::run(opts),
+        Command::ClaimPrefix(opts) => claim_prefix::run(opts),
+        Command::CheckIndex(opts) => check_index::run(opts),
         Command::EnqueueJob(command) => enqueue_job::run(command),
     }
 }
@@ -17,6 +17,7 @@ fn main() -> Result<()> {
     check_failing_background_jobs(conn)?;
     check_stalled_update_downloads(conn)?;
     check_spam_attack(conn)?;
+    check_storage_issues(conn)?;
     Ok(())
 }
 
@@ -143,6 +144,35 @@ fn check_spam_attack(conn: &mut PgConnection) -> Result<()> {
     Ok(())
 }
 
+/// Check for crate files with issues that were found by the last run of the
+/// `verify_storage` background job.
+fn check_storage_issues(conn: &mut PgConnection) -> Result<()> {
+    use crates_io::models::StorageIssue;
+
+    const EVENT_KEY: &str = "storage_issues";
+
+    println!("Checking for missing or corrupted crate files");
+
+    let issue_count = StorageIssue::count(conn)?;
+
+    let event = if issue_count > 0 {
+        on_call::Event::Trigger {
+            incident_key: Some(EVENT_KEY.into()),
+            description: format!(
+                "{issue_count} crate files are missing, corrupted or orphaned in the storage backend"
+            ),
+        }
+    } else {
+        on_call::Event::Resolve {
+            incident_key: EVENT_KEY.into(),
+            description: Some("No storage issues found".into()),
+        }
+    };
+
+    log_and_trigger_event(event)?;
+    Ok(())
+}
+
 fn log_and_trigger_event(event: on_call::Event) -> Result<()> {
     match event {
         on_call::Event::Trigger {
@@ -0,0 +1,30 @@
+use crates_io_env_vars::var;
+
+pub struct MirrorConfig {
+    /// The base URL of the sparse index of the upstream registry, ending
+    /// with a `/` (e.g. `https://index.crates.io/`).
+    pub upstream_index: String,
+}
+
+impl MirrorConfig {
+    /// Returns the mirror configuration if the server is configured to act
+    /// as a read-only mirror of an upstream registry.
+    ///
+    /// - `MIRROR_UPSTREAM_INDEX`: The URL of the sparse index of the upstream
+    ///   registry, with or without the `sparse+` prefix used by cargo. If set,
+    ///   the server acts as a pull-through mirror of this registry.
+    pub fn from_environment() -> anyhow::Result<Option<Self>> {
+        let Some(upstream_index) = var("MIRROR_UPSTREAM_INDEX")? else {
+            return Ok(None);
+        };
+
+        let upstream_index = upstream_index
+            .strip_prefix("sparse+")
+            .unwrap_or(&upstream_index)
+            .trim_end_matches('/');
+
+        Ok(Some(Self {
+            upstream_index: format!("{upstream_index}/"),
+        }))
+    }
+}
@@ -8,6 +8,7 @@ use crate::Env;
 use super::base::Base;
 use super::database_pools::DatabasePools;
 use crate::config::balance_capacity::BalanceCapacityConfig;
+use crate::config::mirror::MirrorConfig;
 use crate::storage::StorageConfig;
 use crates_io_env_vars::{required_var, var, var_parsed};
 use http::HeaderValue;
@@ -57,6 +58,24 @@ pub struct Server {
     pub cdn_user_agent: String,
     pub balance_capacity: BalanceCapacityConfig,
 
+    /// Should crate owners be allowed to register webhooks with plain
+    /// `http://` URLs? Only `https://` URLs are accepted otherwise.
+    pub allow_http_webhooks: bool,
+
+    /// The configuration of the pull-through mirror mode. If set, the server
+    /// acts as a read-only mirror of an upstream registry and fetches index
+    /// files and crate files from it on demand.
+    pub mirror: Option<MirrorConfig>,
+
+    /// Should the server act as a private registry? If so, reading the index,
+    /// downloading crates and reading the API requires authentication, as
+    /// described by the `auth-required` registry protocol of cargo.
+    pub private_registry: bool,
+
+    /// The index URLs that cargo may use for this registry. Asymmetric
+    /// tokens are only accepted if they were signed for one of these URLs.
+    pub index_urls: Vec<String>,
+
     /// Should the server serve the frontend assets in the `dist` directory?
     pub serve_dist: bool,
 
@@ -99,6 +118,15 @@ impl Server {
     ///   endpoint even with a healthy database pool.
     /// - `BLOCKED_ROUTES`: A comma separated list of HTTP route patterns that are manually blocked
     ///   by an operator (e.g. `/crates/:crate_id/:version/download`).
+    /// - `ALLOW_HTTP_WEBHOOKS`: Whether to accept plain `http://` URLs for webhooks, e.g. for
+    ///   local development.
+    /// - `MIRROR_UPSTREAM_INDEX`: The sparse index URL of an upstream registry. If set, the server
+    ///   acts as a read-only, pull-through mirror of that registry.
+    /// - `PRIVATE_REGISTRY`: Whether all read access requires authentication, e.g. for internal
+    ///   company registries.
+    /// - `INDEX_URLS`: A comma separated list of the index URLs that cargo may use for this
+    ///   registry, which asymmetric tokens have to be signed for. Defaults to the sparse index
+    ///   of the server, `sparse+https://<domain>/index/`.
     ///
     /// # Panics
     ///
@@ -146,6 +174,11 @@ impl Server {
             Some(s) => s.split(',').map(String::from).collect(),
         };
 
+        let index_urls = match var("INDEX_URLS")? {
+            None => vec![format!("sparse+https://{}/index/", domain_name())],
+            Some(s) => s.split(',').map(|s| s.trim().to_string()).collect(),
+        };
+
         let max_blocking_threads = var_parsed("SERVER_THREADS")?;
 
         // Dynamically load the configuration for all the rate limiting actions. See
@@ -223,6 +256,10 @@ impl Server {
             cdn_user_agent: var("WEB_CDN_USER_AGENT")?
                 .unwrap_or_else(|| "Amazon CloudFront".into()),
             balance_capacity: BalanceCapacityConfig::from_environment()?,
+            allow_http_webhooks: var("ALLOW_HTTP_WEBHOOKS")?.is_some(),
+            mirror: MirrorConfig::from_environment()?,
+            private_registry: var("PRIVATE_REGISTRY")?.is_some(),
+            index_urls,
             serve_dist: true,
             serve_html: true,
             content_security_policy: Some(content_security_policy.parse()?),
@@ -64,16 +64,23 @@ mod prelude {
 pub mod helpers;
 pub mod util;
 
+pub mod asymmetric_key;
 pub mod category;
-mod conduit_axum;
+pub(crate) mod conduit_axum;
 pub mod crate_owner_invitation;
+pub mod feed;
 pub mod git;
 pub mod github;
+pub mod index;
 pub mod keyword;
 pub mod krate;
 pub mod metrics;
+pub mod prefix;
+pub mod signing_key;
 pub mod site_metadata;
 pub mod team;
 pub mod token;
+pub mod trusted_publishing;
 pub mod user;
 pub mod version;
+pub mod webhook;
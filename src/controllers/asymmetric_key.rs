@@ -0,0 +1,105 @@
+//! Endpoints for managing the public keys that users authenticate cargo
+//! requests with, as described by cargo's asymmetric token protocol.
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{AsymmetricKey, NewAsymmetricKey};
+use crate::schema::asymmetric_keys;
+use diesel::dsl::{exists, now, select};
+use serde_json as json;
+
+/// Maximum number of (non-revoked) asymmetric keys per user.
+const MAX_ASYMMETRIC_KEYS_PER_USER: i64 = 20;
+
+/// Handles the `GET /me/asymmetric_keys` route.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        let keys: Vec<AsymmetricKey> = AsymmetricKey::belonging_to(auth.user())
+            .select(AsymmetricKey::as_select())
+            .filter(asymmetric_keys::revoked_at.is_null())
+            .order(asymmetric_keys::id.desc())
+            .load(conn)?;
+
+        Ok(Json(json!({ "asymmetric_keys": keys })))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/asymmetric_keys` route.
+///
+/// Registers a new ECDSA P-384 public key in its PASERK `k3.public.`
+/// encoding, as printed by `cargo login --registry <name>` with the
+/// `cargo:paseto` credential provider.
+pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct NewAsymmetricKeyRequest {
+            asymmetric_key: NewAsymmetricKeyParams,
+        }
+
+        #[derive(Deserialize)]
+        struct NewAsymmetricKeyParams {
+            name: String,
+            public_key: String,
+        }
+
+        let new: NewAsymmetricKeyRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid new asymmetric key request: {e:?}")))?;
+        let params = new.asymmetric_key;
+
+        let name = params.name.trim();
+        if name.is_empty() {
+            return Err(bad_request("name must have a value"));
+        }
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+
+        let Some(new_key) = NewAsymmetricKey::parse(user.id, name.to_string(), &params.public_key)
+        else {
+            return Err(bad_request(
+                "public_key must be an ECDSA P-384 public key in PASERK `k3.public.` format",
+            ));
+        };
+
+        let count: i64 = AsymmetricKey::belonging_to(user)
+            .filter(asymmetric_keys::revoked_at.is_null())
+            .count()
+            .get_result(conn)?;
+        if count >= MAX_ASYMMETRIC_KEYS_PER_USER {
+            return Err(bad_request(&format!(
+                "maximum asymmetric keys per user is: {MAX_ASYMMETRIC_KEYS_PER_USER}"
+            )));
+        }
+
+        let query = asymmetric_keys::table.filter(asymmetric_keys::key_id.eq(&new_key.key_id));
+        if select(exists(query)).get_result(conn)? {
+            return Err(bad_request("this public key has already been registered"));
+        }
+
+        let key = new_key.insert(conn)?;
+
+        Ok(Json(json!({ "asymmetric_key": key })))
+    })
+    .await
+}
+
+/// Handles the `DELETE /me/asymmetric_keys/:id` route.
+pub async fn revoke(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        diesel::update(AsymmetricKey::belonging_to(auth.user()).find(id))
+            .filter(asymmetric_keys::revoked_at.is_null())
+            .set(asymmetric_keys::revoked_at.eq(now))
+            .execute(conn)?;
+
+        Ok(Json(json!({})))
+    })
+    .await
+}
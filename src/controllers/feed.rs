@@ -0,0 +1,234 @@
+//! Atom feeds of new releases, for use in feed readers.
+//!
+//! There are feeds for the releases of a single crate, for the releases of
+//! all crates that a user follows, and for newly published crates. Feed
+//! readers can not log in, so the feed of followed crates is authenticated
+//! by a feed token in its URL instead of the session cookie.
+
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{Crate, FeedToken, Follow, User, Version};
+use crate::schema::{crates, follows, users, versions};
+use crate::util::errors::forbidden;
+use crate::views::atom::{self, Author, Entry, Feed};
+use secrecy::ExposeSecret;
+
+/// Maximum number of entries of a feed
+const MAX_ENTRIES: i64 = 25;
+
+/// Handles the `GET /crates/:crate_id/releases.atom` route.
+pub async fn crate_releases(app: AppState, Path(crate_name): Path<String>) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read()?;
+        let krate: Crate = Crate::by_name(&crate_name).first(conn)?;
+
+        let versions: Vec<(Version, Option<User>)> = Version::belonging_to(&krate)
+            .left_outer_join(users::table)
+            .select((versions::all_columns, users::all_columns.nullable()))
+            .order((versions::created_at.desc(), versions::id.desc()))
+            .limit(MAX_ENTRIES)
+            .load(conn)?;
+
+        let domain = &app.config.domain_name;
+        let feed = Feed {
+            id: format!(
+                "https://{domain}/api/v1/crates/{}/releases.atom",
+                krate.name
+            ),
+            title: format!("New releases of {}", krate.name),
+            link: format!("https://{domain}/crates/{}", krate.name),
+            entries: versions
+                .into_iter()
+                .map(|(version, publisher)| version_entry(&app, &krate.name, version, publisher))
+                .collect(),
+        };
+
+        Ok(atom_response(&feed))
+    })
+    .await
+}
+
+/// Handles the `GET /feeds/updates.atom` route.
+///
+/// Returns the new releases of the crates that the owner of the feed token
+/// in the `token` query parameter follows.
+pub async fn updates(app: AppState, req: Parts) -> AppResult<Response> {
+    conduit_compat(move || {
+        let token = req.query().get("token").cloned().ok_or_else(forbidden)?;
+
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let user = FeedToken::find_user(conn, &token)?.ok_or_else(forbidden)?;
+
+        let followed_crates = Follow::belonging_to(&user).select(follows::crate_id);
+        let versions: Vec<(Version, String, Option<User>)> = versions::table
+            .inner_join(crates::table)
+            .left_outer_join(users::table)
+            .filter(crates::id.eq_any(followed_crates))
+            .order((versions::created_at.desc(), versions::id.desc()))
+            .select((
+                versions::all_columns,
+                crates::name,
+                users::all_columns.nullable(),
+            ))
+            .limit(MAX_ENTRIES)
+            .load(conn)?;
+
+        let domain = &app.config.domain_name;
+        let feed = Feed {
+            id: format!("https://{domain}/api/v1/feeds/updates.atom"),
+            title: format!("New releases of the crates followed by {}", user.gh_login),
+            link: format!("https://{domain}/dashboard"),
+            entries: versions
+                .into_iter()
+                .map(|(version, crate_name, publisher)| {
+                    version_entry(&app, &crate_name, version, publisher)
+                })
+                .collect(),
+        };
+
+        Ok(atom_response(&feed))
+    })
+    .await
+}
+
+/// Handles the `GET /feeds/crates.atom` route.
+///
+/// Returns the first versions of the most recently created crates.
+pub async fn new_crates(app: AppState) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read()?;
+
+        let new_crates: Vec<i32> = crates::table
+            .select(crates::id)
+            .order((crates::created_at.desc(), crates::id.desc()))
+            .limit(MAX_ENTRIES)
+            .load(conn)?;
+
+        let mut versions: Vec<(Version, String, Option<User>)> = versions::table
+            .inner_join(crates::table)
+            .left_outer_join(users::table)
+            .filter(crates::id.eq_any(new_crates))
+            .distinct_on(versions::crate_id)
+            .order((versions::crate_id, versions::created_at, versions::id))
+            .select((
+                versions::all_columns,
+                crates::name,
+                users::all_columns.nullable(),
+            ))
+            .load(conn)?;
+
+        versions.sort_by_key(|(version, _, _)| std::cmp::Reverse((version.created_at, version.id)));
+
+        let domain = &app.config.domain_name;
+        let feed = Feed {
+            id: format!("https://{domain}/api/v1/feeds/crates.atom"),
+            title: format!("New crates on {domain}"),
+            link: format!("https://{domain}/crates?sort=new"),
+            entries: versions
+                .into_iter()
+                .map(|(version, crate_name, publisher)| {
+                    version_entry(&app, &crate_name, version, publisher)
+                })
+                .collect(),
+        };
+
+        Ok(atom_response(&feed))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/feed_token` route.
+///
+/// Creates a new feed token, replacing the previous one, if any. The
+/// plaintext token is only returned by this endpoint.
+pub async fn create_token(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        let (feed_token, plaintext) = FeedToken::regenerate(conn, auth.user_id())?;
+        let plaintext = plaintext.expose_secret();
+
+        let domain = &app.config.domain_name;
+        let url = format!("https://{domain}/api/v1/feeds/updates.atom?token={plaintext}");
+
+        #[derive(Serialize)]
+        struct EncodableFeedToken<'a> {
+            token: &'a str,
+            url: String,
+            #[serde(flatten)]
+            feed_token: FeedToken,
+        }
+
+        let feed_token = EncodableFeedToken {
+            token: plaintext,
+            url,
+            feed_token,
+        };
+
+        Ok(Json(json!({ "feed_token": feed_token })))
+    })
+    .await
+}
+
+/// Handles the `DELETE /me/feed_token` route.
+pub async fn revoke_token(app: AppState, req: Parts) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        FeedToken::revoke(conn, auth.user_id())?;
+
+        ok_true()
+    })
+    .await
+}
+
+fn version_entry(
+    app: &AppState,
+    crate_name: &str,
+    version: Version,
+    publisher: Option<User>,
+) -> Entry {
+    let domain = &app.config.domain_name;
+    let url = format!("https://{domain}/crates/{crate_name}/{}", version.num);
+    let readme_url = app.storage.readme_location(crate_name, &version.num);
+
+    let mut summary = match &publisher {
+        Some(publisher) => format!(
+            "{crate_name} {} was published by {}.",
+            version.num, publisher.gh_login
+        ),
+        None => format!("{crate_name} {} was published.", version.num),
+    };
+
+    let mut title = format!("{crate_name} {}", version.num);
+    let mut categories = vec![];
+    if version.yanked {
+        title.push_str(" (yanked)");
+        summary.push_str(" This version has been yanked.");
+        categories.push("yanked");
+    }
+
+    let author = publisher.map(|publisher| Author {
+        uri: Some(format!("https://{domain}/users/{}", publisher.gh_login)),
+        name: publisher.gh_login,
+    });
+
+    Entry {
+        id: url.clone(),
+        title,
+        link: url,
+        related: vec![("related", "README", readme_url)],
+        author,
+        categories,
+        summary,
+        published: version.created_at,
+        updated: version.updated_at,
+    }
+}
+
+fn atom_response(feed: &Feed) -> Response {
+    ([(header::CONTENT_TYPE, atom::CONTENT_TYPE)], feed.render()).into_response()
+}
@@ -0,0 +1,54 @@
+//! The sparse index of the server, if it acts as a pull-through mirror of an
+//! upstream registry or as a private registry.
+//!
+//! Cargo can use these registries with a `sparse+https://<domain>/index/`
+//! registry URL. Index files of a mirror that are not stored yet are fetched
+//! from the upstream registry. Crate files are served by the download
+//! endpoint.
+
+use crate::app::AppState;
+use crate::controllers::frontend_prelude::*;
+use crate::models::Crate;
+use crate::util::errors::{internal, not_found};
+use crates_io_index::Repository;
+
+/// Handles the `GET /index/config.json` route.
+pub async fn index_config(app: AppState) -> Json<Value> {
+    let domain = &app.config.domain_name;
+
+    let mut config = json!({
+        "dl": format!("https://{domain}/api/v1/crates"),
+        "api": format!("https://{domain}"),
+    });
+
+    if app.config.private_registry {
+        config["auth-required"] = true.into();
+    }
+
+    Json(config)
+}
+
+/// Handles the `GET /index/*path` route.
+pub async fn index_file(app: AppState, Path(path): Path<String>) -> AppResult<Response> {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    if !Crate::valid_name(name) || Repository::relative_index_file_for_url(name) != path {
+        return Err(not_found());
+    }
+
+    let content = match &app.mirror {
+        Some(mirror) => mirror
+            .index_file(&app.storage, name)
+            .await
+            .map_err(|error| {
+                internal(format!("Failed to fetch index file of {name}: {error:#}"))
+            })?,
+        None => match app.storage.download_index_file(name).await {
+            Ok(content) => Some(content),
+            Err(object_store::Error::NotFound { .. }) => None,
+            Err(error) => return Err(internal(format!("Failed to read index file: {error}"))),
+        },
+    };
+
+    let content = content.ok_or_else(not_found)?;
+    Ok(([(header::CONTENT_TYPE, "text/plain")], content).into_response())
+}
@@ -1,3 +1,6 @@
+pub mod advisories;
+pub mod audit;
+pub mod deprecation;
 pub mod downloads;
 pub mod follow;
 pub mod metadata;
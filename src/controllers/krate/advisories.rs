@@ -0,0 +1,47 @@
+//! Endpoint for listing the security advisories of a crate
+
+use crate::controllers::frontend_prelude::*;
+use crate::models::{Advisory, Crate};
+use crate::schema::{advisories, version_advisories, versions};
+use crate::views::EncodableAdvisory;
+use std::collections::HashMap;
+
+/// Handles the `GET /crates/:crate_id/advisories` route.
+///
+/// Returns the security advisories of the crate, newest first, together with
+/// the versions of the crate that are affected by each advisory.
+pub async fn list(app: AppState, Path(crate_name): Path<String>) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read()?;
+        let krate: Crate = Crate::by_name(&crate_name).first(conn)?;
+
+        let advisories: Vec<Advisory> = Advisory::belonging_to(&krate)
+            .select(Advisory::as_select())
+            .order((advisories::date.desc(), advisories::id.desc()))
+            .load(conn)?;
+
+        let ids = advisories.iter().map(|a| &a.id).collect::<Vec<_>>();
+        let affected: Vec<(String, String)> = version_advisories::table
+            .inner_join(versions::table)
+            .filter(version_advisories::advisory_id.eq_any(ids))
+            .select((version_advisories::advisory_id, versions::num))
+            .load(conn)?;
+
+        let mut affected_versions: HashMap<String, Vec<String>> = HashMap::new();
+        for (advisory_id, num) in affected {
+            affected_versions.entry(advisory_id).or_default().push(num);
+        }
+
+        let advisories = advisories
+            .into_iter()
+            .map(|advisory| {
+                let mut versions = affected_versions.remove(&advisory.id).unwrap_or_default();
+                versions.sort_by_cached_key(|num| semver::Version::parse(num).ok());
+                EncodableAdvisory::from(advisory, versions)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Json(json!({ "advisories": advisories })))
+    })
+    .await
+}
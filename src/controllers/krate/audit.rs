@@ -0,0 +1,38 @@
+//! Endpoint for reading the audit log of a crate
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{Crate, CrateAuditAction, Rights};
+use crate::views::EncodableCrateAuditAction;
+
+/// Handles the `GET /crates/:crate_id/audit` route.
+///
+/// Returns the owner, token and crate settings changes of the crate, newest
+/// first. Only owners of the crate, including members of owning teams, are
+/// allowed to read the audit log.
+pub async fn list(
+    app: AppState,
+    Path(crate_name): Path<String>,
+    req: Parts,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::default().check(&req, conn)?;
+
+        let krate: Crate = Crate::by_name(&crate_name).first(conn)?;
+        let owners = krate.owners(conn)?;
+        if auth.user().rights(&app, &owners)? < Rights::Publish {
+            return Err(bad_request(
+                "only owners have permission to read the audit log",
+            ));
+        }
+
+        let actions = CrateAuditAction::by_crate_name(conn, &krate.name)?
+            .into_iter()
+            .map(|(action, user)| EncodableCrateAuditAction::from(action, user))
+            .collect::<Vec<_>>();
+
+        Ok(Json(json!({ "audit_actions": actions })))
+    })
+    .await
+}
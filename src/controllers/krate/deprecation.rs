@@ -0,0 +1,162 @@
+//! Endpoints for marking a crate as deprecated or unmaintained
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{
+    insert_crate_audit_action, Crate, CrateAction, DeprecationState, Rights, User,
+};
+use crate::schema::crates;
+use serde_json as json;
+
+/// Maximum number of characters of the deprecation message.
+const MAX_DEPRECATION_MESSAGE_LENGTH: usize = 500;
+
+/// Handles the `PUT /crates/:crate_id/deprecation` route.
+///
+/// Marks the crate as deprecated or unmaintained, optionally with a message
+/// and the name of a successor crate. Calling this endpoint again replaces
+/// the previous deprecation state.
+pub async fn deprecate(
+    app: AppState,
+    Path(crate_name): Path<String>,
+    req: BytesRequest,
+) -> AppResult<Response> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct DeprecationRequest {
+            state: DeprecationState,
+            message: Option<String>,
+            successor: Option<String>,
+        }
+
+        let request: DeprecationRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid deprecation request: {e}")))?;
+
+        let message = validate_message(request.message.as_deref())?;
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::default()
+            .for_crate(&crate_name)
+            .check(&req, conn)?;
+        let user = auth.user();
+        let krate = find_owned_crate(&app, &crate_name, user, conn)?;
+
+        let successor = match request.successor.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(name) => {
+                let successor: Option<Crate> = Crate::by_name(name).first(conn).optional()?;
+                let Some(successor) = successor else {
+                    let error = format!("successor crate `{name}` does not exist");
+                    return Err(bad_request(&error));
+                };
+
+                if successor.id == krate.id {
+                    return Err(bad_request("a crate can not be its own successor"));
+                }
+
+                Some(successor.name)
+            }
+        };
+
+        conn.transaction(|conn| {
+            diesel::update(&krate)
+                .set((
+                    crates::deprecation.eq(request.state),
+                    crates::deprecation_message.eq(message),
+                    crates::successor.eq(&successor),
+                ))
+                .execute(conn)?;
+
+            let details = json!({
+                "state": request.state,
+                "message": message,
+                "successor": successor,
+            });
+            let action = CrateAction::Deprecate;
+            let token_id = auth.api_token_id();
+            insert_crate_audit_action(conn, &krate.name, Some(user.id), token_id, action, details)
+        })?;
+
+        ok_true()
+    })
+    .await
+}
+
+/// Handles the `DELETE /crates/:crate_id/deprecation` route.
+pub async fn undeprecate(
+    app: AppState,
+    Path(crate_name): Path<String>,
+    req: Parts,
+) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::default()
+            .for_crate(&crate_name)
+            .check(&req, conn)?;
+        let user = auth.user();
+        let krate = find_owned_crate(&app, &crate_name, user, conn)?;
+
+        if krate.deprecation.is_none() {
+            return ok_true();
+        }
+
+        conn.transaction(|conn| {
+            diesel::update(&krate)
+                .set((
+                    crates::deprecation.eq(None::<DeprecationState>),
+                    crates::deprecation_message.eq(None::<String>),
+                    crates::successor.eq(None::<String>),
+                ))
+                .execute(conn)?;
+
+            let action = CrateAction::Undeprecate;
+            let token_id = auth.api_token_id();
+            insert_crate_audit_action(
+                conn,
+                &krate.name,
+                Some(user.id),
+                token_id,
+                action,
+                json!({}),
+            )
+        })?;
+
+        ok_true()
+    })
+    .await
+}
+
+/// Trims the optional deprecation message and checks that it is not too long.
+fn validate_message(message: Option<&str>) -> AppResult<Option<&str>> {
+    let message = message.map(str::trim).filter(|message| !message.is_empty());
+
+    if let Some(message) = message {
+        if message.chars().count() > MAX_DEPRECATION_MESSAGE_LENGTH {
+            return Err(bad_request(&format!(
+                "deprecation message must not be longer than {MAX_DEPRECATION_MESSAGE_LENGTH} characters"
+            )));
+        }
+    }
+
+    Ok(message)
+}
+
+/// Loads the crate with the given name and checks that the user is one of
+/// its (non-team) owners.
+fn find_owned_crate(
+    app: &AppState,
+    crate_name: &str,
+    user: &User,
+    conn: &mut PgConnection,
+) -> AppResult<Crate> {
+    let krate: Crate = Crate::by_name(crate_name).first(conn)?;
+    let owners = krate.owners(conn)?;
+
+    if user.rights(app, &owners)? != Rights::Full {
+        return Err(bad_request(
+            "only owners have permission to deprecate this crate",
+        ));
+    }
+
+    Ok(krate)
+}
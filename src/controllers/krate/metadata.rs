@@ -12,7 +12,7 @@ use crate::controllers::helpers::pagination::PaginationOptions;
 
 use crate::models::{
     Category, Crate, CrateCategory, CrateKeyword, CrateVersions, Keyword, RecentCrateDownloads,
-    TopVersions, User, Version, VersionOwnerAction,
+    TopVersions, User, Version, VersionAdvisory, VersionOwnerAction,
 };
 use crate::schema::*;
 use crate::views::{
@@ -161,7 +161,8 @@ pub async fn show(app: AppState, Path(name): Path<String>, req: Parts) -> AppRes
                 versions_and_publishers
                     .into_iter()
                     .zip(VersionOwnerAction::for_versions(conn, &versions)?)
-                    .map(|((v, pb), aas)| (v, pb, aas))
+                    .zip(VersionAdvisory::for_versions(conn, &versions)?)
+                    .map(|(((v, pb), aas), advs)| (v, pb, aas, advs))
                     .collect::<Vec<_>>(),
             )
         } else {
@@ -220,7 +221,7 @@ pub async fn show(app: AppState, Path(name): Path<String>, req: Parts) -> AppRes
         );
         let encodable_versions = versions_publishers_and_audit_actions.map(|vpa| {
             vpa.into_iter()
-                .map(|(v, pb, aas)| EncodableVersion::from(v, &krate.name, pb, aas))
+                .map(|(v, pb, aas, advs)| EncodableVersion::from(v, &krate.name, pb, aas, advs))
                 .collect::<Vec<_>>()
         });
         let encodable_keywords = kws.map(|kws| {
@@ -343,7 +344,8 @@ pub async fn versions(state: AppState, Path(crate_name): Path<String>) -> AppRes
         let versions = versions_and_publishers
             .into_iter()
             .zip(VersionOwnerAction::for_versions(conn, &versions)?)
-            .map(|((v, pb), aas)| EncodableVersion::from(v, &crate_name, pb, aas))
+            .zip(VersionAdvisory::for_versions(conn, &versions)?)
+            .map(|(((v, pb), aas), advs)| EncodableVersion::from(v, &crate_name, pb, aas, advs))
             .collect::<Vec<_>>();
 
         Ok(Json(json!({ "versions": versions })))
@@ -387,9 +389,12 @@ pub async fn reverse_dependencies(
         let versions = versions_and_publishers
             .into_iter()
             .zip(VersionOwnerAction::for_versions(conn, &versions)?)
-            .map(|((version, krate_name, published_by), actions)| {
-                EncodableVersion::from(version, &krate_name, published_by, actions)
-            })
+            .zip(VersionAdvisory::for_versions(conn, &versions)?)
+            .map(
+                |(((version, krate_name, published_by), actions), advisories)| {
+                    EncodableVersion::from(version, &krate_name, published_by, actions, advisories)
+                },
+            )
             .collect::<Vec<_>>();
 
         Ok(Json(json!({
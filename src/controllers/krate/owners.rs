@@ -3,8 +3,12 @@
 use crate::auth::AuthCheck;
 use crate::controllers::prelude::*;
 use crate::models::token::EndpointScope;
-use crate::models::{Crate, Owner, Rights, Team, User};
+use crate::models::{
+    insert_crate_audit_action, Crate, CrateAction, Owner, Rights, Team, User, WebhookEvent,
+};
+use crate::util::asymmetric_token::Mutation;
 use crate::views::EncodableOwner;
+use crate::worker::jobs;
 use axum::body::Bytes;
 
 /// Handles the `GET /crates/:crate_id/owners` route.
@@ -105,9 +109,14 @@ fn modify_owners(
     let auth = AuthCheck::default()
         .with_endpoint_scope(EndpointScope::ChangeOwners)
         .for_crate(crate_name)
+        .with_mutation(Mutation::Owners {
+            name: crate_name.to_string(),
+        })
+        .require_second_factor()
         .check(req, conn)?;
 
     let user = auth.user();
+    let api_token_id = auth.api_token_id();
 
     conn.transaction(|conn| {
         let krate: Crate = Crate::by_name(crate_name).first(conn)?;
@@ -136,11 +145,50 @@ fn modify_owners(
                 }
                 let msg = krate.owner_add(app, conn, user, login)?;
                 msgs.push(msg);
+
+                // Team logins always contain a colon. Teams are added
+                // immediately, while users have to accept an invitation first.
+                let action = if login.contains(':') {
+                    CrateAction::AddTeam
+                } else {
+                    CrateAction::InviteOwner
+                };
+                let details = json!({ "owner": login });
+                insert_crate_audit_action(
+                    conn,
+                    &krate.name,
+                    Some(user.id),
+                    api_token_id,
+                    action,
+                    details,
+                )?;
+
+                // Invited users are only reported to the webhooks once they
+                // accepted the invitation.
+                if action == CrateAction::AddTeam {
+                    let details =
+                        json!({ "action": "added", "owner": login, "actor": user.gh_login });
+                    jobs::enqueue_webhooks(conn, krate.id, WebhookEvent::OwnerChange, details)?;
+                }
             }
             msgs.join(",")
         } else {
             for login in &logins {
                 krate.owner_remove(conn, login)?;
+
+                let details = json!({ "owner": login });
+                insert_crate_audit_action(
+                    conn,
+                    &krate.name,
+                    Some(user.id),
+                    api_token_id,
+                    CrateAction::RemoveOwner,
+                    details,
+                )?;
+
+                let details =
+                    json!({ "action": "removed", "owner": login, "actor": user.gh_login });
+                jobs::enqueue_webhooks(conn, krate.id, WebhookEvent::OwnerChange, details)?;
             }
             if User::owning(&krate, conn)?.is_empty() {
                 return Err(cargo_err(
@@ -4,6 +4,7 @@ use crate::auth::AuthCheck;
 use crate::worker::jobs;
 use crate::worker::swirl::BackgroundJob;
 use axum::body::Bytes;
+use base64::{engine::general_purpose, Engine};
 use cargo_manifest::{Dependency, DepsSet, TargetDepsSet};
 use crates_io_tarball::{process_tarball, TarballError};
 use diesel::connection::DefaultLoadingMode;
@@ -12,14 +13,14 @@ use hex::ToHex;
 use hyper::body::Buf;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use tokio::runtime::Handle;
 use url::Url;
 
 use crate::controllers::cargo_prelude::*;
 use crate::models::krate::MAX_NAME_LENGTH;
 use crate::models::{
-    insert_version_owner_action, Category, Crate, DependencyKind, Keyword, NewCrate, NewVersion,
-    Rights, VersionAction,
+    insert_version_owner_action, Category, Crate, CrateNamePrefix, DependencyKind, Keyword,
+    NewCrate, NewPublish, NewVersion, Publish, Rights, SigningKey, VersionAction, VersionSignature,
+    WebhookEvent,
 };
 
 use crate::licenses::parse_license_expr;
@@ -28,10 +29,12 @@ use crate::models::token::EndpointScope;
 use crate::rate_limiter::LimitedAction;
 use crate::schema::*;
 use crate::sql::canon_crate_name;
-use crate::util::errors::{cargo_err, internal, AppResult};
+use crate::util::asymmetric_token::Mutation;
+use crate::util::errors::{cargo_err, not_found, AppResult};
 use crate::util::Maximums;
 use crate::views::{
-    EncodableCrate, EncodableCrateDependency, GoodCrate, PublishMetadata, PublishWarnings,
+    EncodableCrate, EncodableCrateDependency, EncodablePublish, GoodCrate, PublishMetadata,
+    PublishSignature, PublishWarnings,
 };
 
 const MISSING_RIGHTS_ERROR_MESSAGE: &str = "this crate exists but you don't seem to be an owner. \
@@ -43,9 +46,10 @@ const MISSING_RIGHTS_ERROR_MESSAGE: &str = "this crate exists but you don't seem
 /// Used by `cargo publish` to publish a new crate or to publish a new version of an
 /// existing crate.
 ///
-/// Currently blocks the HTTP thread, perhaps some function calls can spawn new
-/// threads and return completion or error through other methods  a `cargo publish
-/// --status` command, via crates.io's front end, or email.
+/// The uploaded tarball is persisted as a pending publish and uploaded to the
+/// storage backend by the `ProcessPublish` background job, which also syncs the
+/// crate to the index. The status of the publish can be polled through the
+/// `GET /publishes/:id` route.
 pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCrate>> {
     let (req, bytes) = req.0.into_parts();
     let (json_bytes, tarball_bytes) = split_body(bytes)?;
@@ -79,6 +83,8 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
     request_log.add("crate_name", &*metadata.name);
     request_log.add("crate_version", &version_string);
 
+    let hex_cksum: String = Sha256::digest(&tarball_bytes).encode_hex();
+
     conduit_compat(move || {
         let conn = &mut *app.db_write()?;
 
@@ -94,9 +100,16 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
             None => EndpointScope::PublishNew,
         };
 
+        let mutation = Mutation::Publish {
+            name: metadata.name.to_string(),
+            vers: metadata.vers.to_string(),
+            cksum: hex_cksum.clone(),
+        };
+
         let auth = AuthCheck::default()
             .with_endpoint_scope(endpoint_scope)
             .for_crate(&metadata.name)
+            .with_mutation(mutation)
             .check(&req, conn)?;
 
         let api_token_id = auth.api_token_id();
@@ -134,6 +147,12 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
             )));
         }
 
+        let signature = metadata
+            .signature
+            .as_ref()
+            .map(|signature| verify_signature(signature, &tarball_bytes, user.id, conn))
+            .transpose()?;
+
         let pkg_name = format!("{}-{}", &*metadata.name, &version_string);
         let tarball_info = process_tarball(&pkg_name, &*tarball_bytes, maximums.max_unpack_size)?;
 
@@ -290,7 +309,20 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
             // To avoid race conditions, we try to insert
             // first so we know whether to add an owner
             let krate = match persist.create(conn, user.id).optional()? {
-                Some(krate) => krate,
+                Some(krate) => {
+                    // New crates under a claimed prefix can only be
+                    // published by the owners of the prefix
+                    if let Some(prefix) = CrateNamePrefix::find_for_crate_name(conn, &name)? {
+                        if !prefix.is_owned_by(conn, user.id)? {
+                            return Err(cargo_err(&format_args!(
+                                "the crate name prefix `{}` is reserved for the owners of the prefix",
+                                prefix.prefix
+                            )));
+                        }
+                    }
+
+                    krate
+                }
                 None => persist.update(conn)?,
             };
 
@@ -315,9 +347,6 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
                 }
             }
 
-            // Read tarball from request
-            let hex_cksum: String = Sha256::digest(&tarball_bytes).encode_hex();
-
             // Persist the new version of this crate
             let version = NewVersion::new(
                 krate.id,
@@ -334,14 +363,24 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
             )?
             .save(conn, &verified_email_address)?;
 
-            insert_version_owner_action(
+            let action = insert_version_owner_action(
                 conn,
                 version.id,
                 user.id,
                 api_token_id,
                 VersionAction::Publish,
+                None,
             )?;
 
+            jobs::SendVersionNotifications::new(action.id).enqueue(conn)?;
+
+            let details = json!({ "version": version.num, "actor": user.gh_login });
+            jobs::enqueue_webhooks(conn, krate.id, WebhookEvent::Publish, details)?;
+
+            if let Some((signing_key_id, signature)) = &signature {
+                VersionSignature::insert(conn, version.id, *signing_key_id, signature)?;
+            }
+
             let deps = convert_dependencies(
                 tarball_info.manifest.dependencies.as_ref(),
                 tarball_info.manifest.dev_dependencies.as_ref(),
@@ -356,6 +395,16 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
             // Link this new version to all dependencies
             add_dependencies(conn, &deps, version.id)?;
 
+            // Notify the dependencies that this crate did not depend on before
+            for dependency_id in new_dependency_ids(conn, &krate, version.id)? {
+                let details = json!({ "dependent": krate.name, "dependent_version": version.num });
+                jobs::enqueue_webhooks(conn, dependency_id, WebhookEvent::NewDependent, details)?;
+            }
+
+            // Make sure that all dependency requirements can be resolved,
+            // collecting warnings for the ones that only match yanked versions
+            let dependency_warnings = check_dependency_versions(conn, &deps)?;
+
             // Update all keywords for this crate
             Keyword::update_crate(conn, &krate, &keywords)?;
 
@@ -382,27 +431,27 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
                 }
             }
 
-            // Upload crate tarball
-            Handle::current()
-                .block_on(app.storage.upload_crate_file(
-                    &krate.name,
-                    &version_string,
-                    tarball_bytes,
-                ))
-                .map_err(|e| internal(format!("failed to upload crate: {e}")))?;
+            // Persist the tarball as a pending publish. The `ProcessPublish`
+            // background job uploads it to the storage backend and then
+            // syncs the crate to the index.
+            let publish = NewPublish {
+                version_id: version.id,
+                user_id: user.id,
+                api_token_id,
+                tarball: &tarball_bytes,
+            }
+            .insert(conn)?;
 
-            jobs::enqueue_sync_to_index(&krate.name, conn)?;
+            jobs::ProcessPublish::new(publish.id).enqueue(conn)?;
 
-            // The `other` field on `PublishWarnings` was introduced to handle a temporary warning
-            // that is no longer needed. As such, crates.io currently does not return any `other`
-            // warnings at this time, but if we need to, the field is available.
             let warnings = PublishWarnings {
                 invalid_categories: ignored_invalid_categories,
                 invalid_badges: vec![],
-                other: vec![],
+                other: dependency_warnings,
             };
 
             Ok(Json(GoodCrate {
+                publish: EncodablePublish::from(publish, &krate.name, &version_string),
                 krate: EncodableCrate::from_minimal(krate, Some(&top_versions), None, false, None),
                 warnings,
             }))
@@ -411,6 +460,36 @@ pub async fn publish(app: AppState, req: BytesRequest) -> AppResult<Json<GoodCra
     .await
 }
 
+/// Handles the `GET /publishes/:publish_id` route.
+///
+/// Returns the status of a publish that was accepted by the `PUT /crates/new`
+/// route. Only the user that published the version is allowed to see it.
+pub async fn status(
+    app: AppState,
+    Path(publish_id): Path<i32>,
+    req: Parts,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let user_id = AuthCheck::default().check(&req, conn)?.user_id();
+
+        let publish = Publish::find(conn, publish_id)?;
+        if publish.user_id != user_id {
+            return Err(not_found());
+        }
+
+        let (crate_name, version): (String, String) = versions::table
+            .find(publish.version_id)
+            .inner_join(crates::table)
+            .select((crates::name, versions::num))
+            .first(conn)?;
+
+        let publish = EncodablePublish::from(publish, &crate_name, &version);
+        Ok(Json(json!({ "publish": publish })))
+    })
+    .await
+}
+
 /// Counts the number of versions for `crate_id` that were published within
 /// the last 24 hours.
 fn count_versions_published_today(crate_id: i32, conn: &mut PgConnection) -> QueryResult<i64> {
@@ -463,6 +542,37 @@ fn split_body(mut bytes: Bytes) -> AppResult<(Bytes, Bytes)> {
     Ok((json_bytes, tarball_bytes))
 }
 
+/// Verifies the detached signature of the uploaded crate file against the
+/// referenced signing key of the publishing user.
+///
+/// Returns the id of the signing key and the decoded signature.
+fn verify_signature(
+    signature: &PublishSignature,
+    tarball_bytes: &[u8],
+    user_id: i32,
+    conn: &mut PgConnection,
+) -> AppResult<(i32, Vec<u8>)> {
+    let Some(key) = SigningKey::find_active(conn, user_id, &signature.key)? else {
+        return Err(cargo_err(&format_args!(
+            "unknown signing key `{}`, signing keys have to be registered before they can be used",
+            signature.key
+        )));
+    };
+
+    let decoded = general_purpose::STANDARD
+        .decode(&signature.signature)
+        .map_err(|_| cargo_err("the signature of the crate file is not valid base64"))?;
+
+    if !key.verify(tarball_bytes, &decoded) {
+        return Err(cargo_err(&format_args!(
+            "the signature of the crate file could not be verified with the signing key `{}`",
+            key.fingerprint
+        )));
+    }
+
+    Ok((key.id, decoded))
+}
+
 fn is_reserved_name(name: &str, conn: &mut PgConnection) -> QueryResult<bool> {
     select(exists(reserved_crate_names::table.filter(
         canon_crate_name(reserved_crate_names::name).eq(canon_crate_name(name)),
@@ -686,6 +796,101 @@ pub fn add_dependencies(
     Ok(())
 }
 
+/// Returns the IDs of the crates that the given version depends on, but that
+/// no other version of the crate has depended on before.
+fn new_dependency_ids(
+    conn: &mut PgConnection,
+    krate: &Crate,
+    version_id: i32,
+) -> QueryResult<Vec<i32>> {
+    let previous_dependencies: Vec<i32> = dependencies::table
+        .inner_join(versions::table)
+        .filter(versions::crate_id.eq(krate.id))
+        .filter(versions::id.ne(version_id))
+        .select(dependencies::crate_id)
+        .distinct()
+        .load(conn)?;
+
+    dependencies::table
+        .filter(dependencies::version_id.eq(version_id))
+        .filter(dependencies::crate_id.ne(krate.id))
+        .filter(dependencies::crate_id.ne_all(previous_dependencies))
+        .select(dependencies::crate_id)
+        .distinct()
+        .order(dependencies::crate_id)
+        .load(conn)
+}
+
+/// Resolves the version requirements of all dependencies against the
+/// published versions of the dependency crates.
+///
+/// Publishing fails if no version of a normal or build dependency matches
+/// its requirement, since the published crate could never be built. For
+/// dev-dependencies, and for requirements that are only satisfied by yanked
+/// versions, a warning is returned instead.
+///
+/// Pre-release versions are only matched by requirements that explicitly
+/// opt into them, just like cargo does it.
+#[instrument(skip_all)]
+pub fn check_dependency_versions(
+    conn: &mut PgConnection,
+    deps: &[EncodableCrateDependency],
+) -> AppResult<Vec<String>> {
+    let mut versions: HashMap<String, Vec<(semver::Version, bool)>> = HashMap::new();
+
+    let rows = versions::table
+        .inner_join(crates::table)
+        .select((crates::name, versions::num, versions::yanked))
+        .filter(crates::name.eq_any(deps.iter().map(|d| &d.name)))
+        .load_iter::<(String, String, bool), DefaultLoadingMode>(conn)?;
+
+    for row in rows {
+        let (name, num, yanked) = row?;
+        if let Ok(num) = semver::Version::parse(&num) {
+            versions.entry(name).or_default().push((num, yanked));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for dep in deps {
+        // The requirement was already validated by `validate_dependency()`
+        let Ok(req) = semver::VersionReq::parse(&dep.version_req) else {
+            continue;
+        };
+
+        let mut matching = versions
+            .get(&dep.name)
+            .into_iter()
+            .flatten()
+            .filter(|(num, _)| req.matches(num))
+            .peekable();
+
+        if matching.peek().is_none() {
+            let kind = dep.kind.unwrap_or(DependencyKind::Normal);
+            let message = format!(
+                "no published version of dependency `{}` matches the version requirement `{}`",
+                dep.name, dep.version_req
+            );
+
+            if kind == DependencyKind::Dev {
+                warnings.push(message);
+                continue;
+            }
+
+            return Err(cargo_err(&message));
+        }
+
+        if matching.all(|(_, yanked)| *yanked) {
+            warnings.push(format!(
+                "all versions of dependency `{}` that match the version requirement `{}` have been yanked",
+                dep.name, dep.version_req
+            ));
+        }
+    }
+
+    Ok(warnings)
+}
+
 impl From<TarballError> for BoxedAppError {
     fn from(error: TarballError) -> Self {
         match error {
@@ -48,6 +48,10 @@ pub async fn search(app: AppState, req: Parts) -> AppResult<Json<Value>> {
             .get("include_yanked")
             .map(|s| s == "yes")
             .unwrap_or(true);
+        let include_deprecated = params
+            .get("include_deprecated")
+            .map(|s| s == "yes")
+            .unwrap_or(true);
 
         // Remove 0x00 characters from the query string because Postgres can not
         // handle them and will return an error, which would cause us to throw
@@ -223,6 +227,13 @@ pub async fn search(app: AppState, req: Parts) -> AppResult<Json<Value>> {
             ));
         }
 
+        if !include_deprecated {
+            // Calculating the total number of results with filters is not supported yet.
+            supports_seek = false;
+
+            query = query.filter(crates::deprecation.is_null());
+        }
+
         // Any sort other than 'relevance' (default) would ignore exact crate name matches
         if sort == Some("downloads") {
             // Custom sorting is not supported yet with seek.
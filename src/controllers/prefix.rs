@@ -0,0 +1,96 @@
+//! Endpoints for managing the owners of claimed crate name prefixes
+//!
+//! Prefixes are claimed by the crates.io team with the `crates-admin
+//! claim-prefix` command. Afterwards the owners of a prefix can manage the
+//! list of owners themselves.
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{CrateNamePrefix, Owner};
+use crate::views::EncodablePublicUser;
+
+/// Handles the `GET /prefixes/:prefix/owners` route.
+pub async fn owners(app: AppState, Path(prefix): Path<String>) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read()?;
+        let prefix = CrateNamePrefix::find_by_prefix(conn, &prefix)?;
+
+        let owners = prefix
+            .owners(conn)?
+            .into_iter()
+            .map(EncodablePublicUser::from)
+            .collect::<Vec<_>>();
+
+        Ok(Json(json!({ "users": owners })))
+    })
+    .await
+}
+
+/// Handles the `PUT /prefixes/:prefix/owners` route.
+///
+/// Unlike crate owners, prefix owners are added immediately without having
+/// to accept an invitation first.
+pub async fn add_owners(
+    app: AppState,
+    Path(prefix): Path<String>,
+    req: BytesRequest,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || modify_owners(&app, &prefix, &req, true)).await
+}
+
+/// Handles the `DELETE /prefixes/:prefix/owners` route.
+pub async fn remove_owners(
+    app: AppState,
+    Path(prefix): Path<String>,
+    req: BytesRequest,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || modify_owners(&app, &prefix, &req, false)).await
+}
+
+fn modify_owners(
+    app: &AppState,
+    prefix: &str,
+    req: &BytesRequest,
+    add: bool,
+) -> AppResult<Json<Value>> {
+    #[derive(Deserialize)]
+    struct OwnersRequest {
+        owners: Vec<String>,
+    }
+
+    let request: OwnersRequest = serde_json::from_slice(req.body())
+        .map_err(|e| bad_request(&format!("invalid json request: {e}")))?;
+
+    let conn = &mut *app.db_write()?;
+    let user_id = AuthCheck::default().check(req, conn)?.user_id();
+
+    conn.transaction(|conn| {
+        let prefix = CrateNamePrefix::find_by_prefix(conn, prefix)?;
+        if !prefix.is_owned_by(conn, user_id)? {
+            return Err(bad_request(
+                "only owners have permission to modify the owners of this prefix",
+            ));
+        }
+
+        for login in &request.owners {
+            let owner = match Owner::find_by_login(conn, login)? {
+                Owner::User(user) => user,
+                Owner::Team(_) => {
+                    return Err(bad_request("teams can not own crate name prefixes"));
+                }
+            };
+
+            if add {
+                prefix.add_owner(conn, owner.id)?;
+            } else {
+                prefix.remove_owner(conn, owner.id)?;
+            }
+        }
+
+        if prefix.owners(conn)?.is_empty() {
+            return Err(bad_request("cannot remove all owners of a prefix"));
+        }
+
+        Ok(Json(json!({ "ok": true })))
+    })
+}
@@ -0,0 +1,107 @@
+//! Endpoints for managing the public keys that users sign their crate files
+//! with.
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::{NewSigningKey, SigningKey};
+use crate::schema::signing_keys;
+use diesel::dsl::{exists, now, select};
+use serde_json as json;
+
+/// Maximum number of (non-revoked) signing keys per user.
+const MAX_SIGNING_KEYS_PER_USER: i64 = 20;
+
+/// Handles the `GET /me/signing_keys` route.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        let keys: Vec<SigningKey> = SigningKey::belonging_to(auth.user())
+            .select(SigningKey::as_select())
+            .filter(signing_keys::revoked_at.is_null())
+            .order(signing_keys::id.desc())
+            .load(conn)?;
+
+        Ok(Json(json!({ "signing_keys": keys })))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/signing_keys` route.
+///
+/// Registers a new ECDSA P-256 public key in PEM format. The returned
+/// fingerprint has to be passed along with signatures at publish time.
+pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct NewSigningKeyRequest {
+            signing_key: NewSigningKeyParams,
+        }
+
+        #[derive(Deserialize)]
+        struct NewSigningKeyParams {
+            name: String,
+            public_key: String,
+        }
+
+        let new: NewSigningKeyRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid new signing key request: {e:?}")))?;
+        let params = new.signing_key;
+
+        let name = params.name.trim();
+        if name.is_empty() {
+            return Err(bad_request("name must have a value"));
+        }
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+
+        let Some(new_key) = NewSigningKey::parse(user.id, name.to_string(), &params.public_key)
+        else {
+            return Err(bad_request(
+                "public_key must be an ECDSA P-256 public key in PEM format",
+            ));
+        };
+
+        let count: i64 = SigningKey::belonging_to(user)
+            .filter(signing_keys::revoked_at.is_null())
+            .count()
+            .get_result(conn)?;
+        if count >= MAX_SIGNING_KEYS_PER_USER {
+            return Err(bad_request(&format!(
+                "maximum signing keys per user is: {MAX_SIGNING_KEYS_PER_USER}"
+            )));
+        }
+
+        let query = signing_keys::table.filter(signing_keys::fingerprint.eq(&new_key.fingerprint));
+        if select(exists(query)).get_result(conn)? {
+            return Err(bad_request("this signing key has already been registered"));
+        }
+
+        let key = new_key.insert(conn)?;
+
+        Ok(Json(json!({ "signing_key": key })))
+    })
+    .await
+}
+
+/// Handles the `DELETE /me/signing_keys/:id` route.
+///
+/// Revoked keys can not be used to sign new versions anymore, but the
+/// signatures of already published versions remain available.
+pub async fn revoke(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        diesel::update(SigningKey::belonging_to(auth.user()).find(id))
+            .filter(signing_keys::revoked_at.is_null())
+            .set(signing_keys::revoked_at.eq(now))
+            .execute(conn)?;
+
+        Ok(Json(json!({})))
+    })
+    .await
+}
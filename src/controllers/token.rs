@@ -1,12 +1,12 @@
 use super::frontend_prelude::*;
 
-use crate::models::ApiToken;
-use crate::schema::api_tokens;
+use crate::models::{insert_token_audit_actions, ApiToken, ApiTokenUsage, CrateAction};
+use crate::schema::{api_token_usages, api_tokens};
 use crate::util::rfc3339;
 use crate::views::EncodableApiTokenWithToken;
 
 use crate::auth::AuthCheck;
-use crate::models::token::{CrateScope, EndpointScope};
+use crate::models::token::{CrateScope, EndpointScope, MAX_USAGES_PER_TOKEN};
 use axum::extract::Query;
 use axum::response::IntoResponse;
 use chrono::NaiveDateTime;
@@ -38,7 +38,9 @@ pub async fn list(
 ) -> AppResult<Json<Value>> {
     conduit_compat(move || {
         let conn = &mut *app.db_read_prefer_primary()?;
-        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let auth = AuthCheck::only_cookie()
+            .with_endpoint_scope(EndpointScope::ManageTokens)
+            .check(&req, conn)?;
         let user = auth.user();
 
         let tokens: Vec<ApiToken> = ApiToken::belonging_to(user)
@@ -93,7 +95,9 @@ pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
 
         let conn = &mut *app.db_write()?;
 
-        let auth = AuthCheck::default().check(&req, conn)?;
+        let auth = AuthCheck::default()
+            .require_second_factor()
+            .check(&req, conn)?;
         if auth.api_token_id().is_some() {
             return Err(bad_request(
                 "cannot use an API token to create a new API token",
@@ -134,6 +138,18 @@ pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
             .transpose()
             .map_err(|_err| bad_request("invalid endpoint scope"))?;
 
+        let has_crate_scopes = crate_scopes
+            .as_ref()
+            .is_some_and(|scopes| !scopes.is_empty());
+        let has_account_scopes = endpoint_scopes
+            .as_ref()
+            .is_some_and(|scopes| scopes.iter().any(EndpointScope::is_account_scope));
+        if has_crate_scopes && has_account_scopes {
+            return Err(bad_request(
+                "crate scopes can not be combined with account endpoint scopes",
+            ));
+        }
+
         let api_token = ApiToken::insert_with_scopes(
             conn,
             user.id,
@@ -142,6 +158,8 @@ pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
             endpoint_scopes,
             new.api_token.expired_at,
         )?;
+        insert_token_audit_actions(conn, &api_token.model, None, CrateAction::CreateToken)?;
+
         let api_token = EncodableApiTokenWithToken::from(api_token);
 
         Ok(Json(json!({ "api_token": api_token })))
@@ -149,15 +167,56 @@ pub async fn new(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
     .await
 }
 
+/// Handles the `GET /me/tokens/:id/usage` route.
+///
+/// Returns the most recent uses of the token, including revoked and expired
+/// tokens, so that users can find out where a leaked token was used.
+pub async fn usage(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie()
+            .with_endpoint_scope(EndpointScope::ManageTokens)
+            .check(&req, conn)?;
+        let user = auth.user();
+
+        let token: ApiToken = ApiToken::belonging_to(user)
+            .find(id)
+            .select(ApiToken::as_select())
+            .first(conn)?;
+
+        let usage: Vec<ApiTokenUsage> = ApiTokenUsage::belonging_to(&token)
+            .select(ApiTokenUsage::as_select())
+            .order((
+                api_token_usages::used_at.desc(),
+                api_token_usages::id.desc(),
+            ))
+            .limit(MAX_USAGES_PER_TOKEN)
+            .load(conn)?;
+
+        Ok(Json(json!({ "usage": usage })))
+    })
+    .await
+}
+
 /// Handles the `DELETE /me/tokens/:id` route.
 pub async fn revoke(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult<Json<Value>> {
     conduit_compat(move || {
         let conn = &mut *app.db_write()?;
-        let auth = AuthCheck::default().check(&req, conn)?;
+        let auth = AuthCheck::default()
+            .with_endpoint_scope(EndpointScope::ManageTokens)
+            .check(&req, conn)?;
         let user = auth.user();
-        diesel::update(ApiToken::belonging_to(user).find(id))
+        let revoked_token = diesel::update(ApiToken::belonging_to(user).find(id))
+            .filter(api_tokens::revoked.eq(false))
             .set(api_tokens::revoked.eq(true))
-            .execute(conn)?;
+            .returning(ApiToken::as_returning())
+            .get_result(conn)
+            .optional()?;
+
+        if let Some(token) = revoked_token {
+            let api_token_id = auth.api_token_id();
+            insert_token_audit_actions(conn, &token, api_token_id, CrateAction::RevokeToken)?;
+        }
 
         Ok(Json(json!({})))
     })
@@ -173,9 +232,13 @@ pub async fn revoke_current(app: AppState, req: Parts) -> AppResult<Response> {
             .api_token_id()
             .ok_or_else(|| bad_request("token not provided"))?;
 
-        diesel::update(api_tokens::table.filter(api_tokens::id.eq(api_token_id)))
+        let token = diesel::update(api_tokens::table.filter(api_tokens::id.eq(api_token_id)))
             .set(api_tokens::revoked.eq(true))
-            .execute(conn)?;
+            .returning(ApiToken::as_returning())
+            .get_result(conn)?;
+
+        let api_token_id = Some(api_token_id);
+        insert_token_audit_actions(conn, &token, api_token_id, CrateAction::RevokeToken)?;
 
         Ok(StatusCode::NO_CONTENT.into_response())
     })
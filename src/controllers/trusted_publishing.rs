@@ -0,0 +1,394 @@
+//! Trusted publishing allows GitHub Actions workflows to publish new versions
+//! of a crate without having to store a long-lived API token in the
+//! repository secrets.
+//!
+//! Crate owners configure which repository, workflow and (optionally)
+//! environment is trusted to publish their crate. The workflow can then
+//! exchange the OIDC token that GitHub issues to it for a short-lived API
+//! token, which is scoped to the crate and the `publish-update` endpoint.
+
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::models::token::{CrateScope, EndpointScope};
+use crate::models::{
+    insert_crate_audit_action, insert_token_audit_actions, ApiToken, Crate, CrateAction,
+    NewTrustedPublisher, Rights, TrustedPublisher, User,
+};
+use crate::schema::trusted_publishers;
+use crate::util::errors::not_found;
+use crate::views::{EncodableApiTokenWithToken, EncodableTrustedPublisher};
+use chrono::{Duration, NaiveDateTime, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde_json as json;
+
+/// The `iss` claim of OIDC tokens issued to GitHub Actions workflows
+const GITHUB_ACTIONS_ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+/// Number of minutes that the minted API tokens are valid for
+const TOKEN_LIFETIME_MINUTES: i64 = 30;
+
+// Minimum number of seconds to wait before refreshing cache of GitHub's OIDC keys
+static JWKS_CACHE_LIFETIME_SECONDS: i64 = 60 * 60; // 1 hour
+
+// Cache of OIDC keys that have been fetched from GitHub
+static JWKS_CACHE: Lazy<Mutex<Option<(JwkSet, NaiveDateTime)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Handles the `GET /crates/:crate_id/trusted_publishers` route.
+pub async fn list(
+    app: AppState,
+    Path(crate_name): Path<String>,
+    req: Parts,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let krate = find_owned_crate(&app, &crate_name, auth.user(), conn)?;
+
+        let publishers = TrustedPublisher::belonging_to(&krate)
+            .select(TrustedPublisher::as_select())
+            .order(trusted_publishers::id)
+            .load(conn)?
+            .into_iter()
+            .map(EncodableTrustedPublisher::from)
+            .collect::<Vec<_>>();
+
+        Ok(Json(json!({ "trusted_publishers": publishers })))
+    })
+    .await
+}
+
+/// Handles the `PUT /crates/:crate_id/trusted_publishers` route.
+pub async fn create(
+    app: AppState,
+    Path(crate_name): Path<String>,
+    req: BytesRequest,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct NewTrustedPublisherRequest {
+            trusted_publisher: NewTrustedPublisherParams,
+        }
+
+        #[derive(Deserialize)]
+        struct NewTrustedPublisherParams {
+            repository: String,
+            workflow_filename: String,
+            environment: Option<String>,
+        }
+
+        let new: NewTrustedPublisherRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid new trusted publisher request: {e:?}")))?;
+        let params = new.trusted_publisher;
+
+        let repository = params.repository.trim();
+        if !is_valid_repository(repository) {
+            return Err(bad_request("repository must be in `owner/name` format"));
+        }
+
+        let workflow_filename = params.workflow_filename.trim();
+        if !is_valid_workflow_filename(workflow_filename) {
+            return Err(bad_request(
+                "workflow_filename must be the name of a `.yml` or `.yaml` file",
+            ));
+        }
+
+        let environment = params.environment.as_deref().map(str::trim);
+        if environment.is_some_and(str::is_empty) {
+            return Err(bad_request("environment must not be empty"));
+        }
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+        let krate = find_owned_crate(&app, &crate_name, user, conn)?;
+
+        let publisher = NewTrustedPublisher {
+            crate_id: krate.id,
+            repository,
+            workflow_filename,
+            environment,
+            created_by: user.id,
+        }
+        .insert(conn)?;
+
+        let details = audit_details(&publisher);
+        let action = CrateAction::AddTrustedPublisher;
+        insert_crate_audit_action(conn, &krate.name, Some(user.id), None, action, details)?;
+
+        let publisher = EncodableTrustedPublisher::from(publisher);
+        Ok(Json(json!({ "trusted_publisher": publisher })))
+    })
+    .await
+}
+
+/// Handles the `DELETE /crates/:crate_id/trusted_publishers/:id` route.
+pub async fn delete(
+    app: AppState,
+    Path((crate_name, id)): Path<(String, i32)>,
+    req: Parts,
+) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+        let krate = find_owned_crate(&app, &crate_name, user, conn)?;
+
+        let publisher = diesel::delete(TrustedPublisher::belonging_to(&krate).find(id))
+            .returning(TrustedPublisher::as_returning())
+            .get_result(conn)
+            .optional()?
+            .ok_or_else(not_found)?;
+
+        let details = audit_details(&publisher);
+        let action = CrateAction::RemoveTrustedPublisher;
+        insert_crate_audit_action(conn, &krate.name, Some(user.id), None, action, details)?;
+
+        ok_true()
+    })
+    .await
+}
+
+/// Handles the `PUT /trusted_publishing/tokens` route.
+///
+/// Exchanges the OIDC token of a GitHub Actions workflow for a short-lived
+/// API token that can be used to publish a new version of the requested
+/// crate. The request is rejected unless one of the trusted publishers of
+/// the crate matches the repository, workflow and environment claims of the
+/// OIDC token.
+pub async fn exchange(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct ExchangeRequest {
+            #[serde(rename = "crate")]
+            krate: String,
+            jwt: String,
+        }
+
+        let request: ExchangeRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid token exchange request: {e:?}")))?;
+
+        let claims = verify_github_actions_jwt(&app, &request.jwt)?;
+        let workflow_filename = claims
+            .workflow_filename()
+            .ok_or_else(|| bad_request("invalid `workflow_ref` claim"))?;
+
+        let conn = &mut *app.db_write()?;
+        let krate: Crate = Crate::by_name(&request.krate)
+            .first(conn)
+            .optional()?
+            .ok_or_else(|| bad_request("no matching trusted publisher found"))?;
+
+        let publishers: Vec<TrustedPublisher> = TrustedPublisher::belonging_to(&krate)
+            .select(TrustedPublisher::as_select())
+            .order(trusted_publishers::id)
+            .load(conn)?;
+
+        let publisher = publishers
+            .into_iter()
+            .find(|publisher| {
+                publisher.matches(
+                    &claims.repository,
+                    workflow_filename,
+                    claims.environment.as_deref(),
+                )
+            })
+            .ok_or_else(|| bad_request("no matching trusted publisher found"))?;
+
+        let crate_scope = CrateScope::try_from(krate.name.as_str())
+            .map_err(|_| server_error("failed to build crate scope"))?;
+
+        let expired_at = (Utc::now() + Duration::minutes(TOKEN_LIFETIME_MINUTES)).naive_utc();
+        let name = format!("trusted publishing: {}", claims.repository);
+
+        let token = ApiToken::insert_with_scopes(
+            conn,
+            publisher.created_by,
+            &name,
+            Some(vec![crate_scope]),
+            Some(vec![EndpointScope::PublishUpdate]),
+            Some(expired_at),
+        )?;
+        insert_token_audit_actions(conn, &token.model, None, CrateAction::CreateToken)?;
+
+        info!(
+            krate.name = %krate.name,
+            trusted_publisher.id = publisher.id,
+            repository = %claims.repository,
+            "Minted API token via trusted publishing",
+        );
+
+        let api_token = EncodableApiTokenWithToken::from(token);
+        Ok(Json(json!({ "api_token": api_token })))
+    })
+    .await
+}
+
+/// Loads the crate with the given name and checks that the user is one of
+/// its (non-team) owners.
+fn find_owned_crate(
+    app: &AppState,
+    crate_name: &str,
+    user: &User,
+    conn: &mut PgConnection,
+) -> AppResult<Crate> {
+    let krate: Crate = Crate::by_name(crate_name).first(conn)?;
+    let owners = krate.owners(conn)?;
+
+    if user.rights(app, &owners)? != Rights::Full {
+        return Err(bad_request(
+            "only owners have permission to manage trusted publishers",
+        ));
+    }
+
+    Ok(krate)
+}
+
+fn audit_details(publisher: &TrustedPublisher) -> Value {
+    json!({
+        "repository": publisher.repository,
+        "workflow_filename": publisher.workflow_filename,
+        "environment": publisher.environment,
+    })
+}
+
+fn is_valid_repository(repository: &str) -> bool {
+    let Some((owner, name)) = repository.split_once('/') else {
+        return false;
+    };
+
+    let is_valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    };
+
+    is_valid_part(owner) && is_valid_part(name)
+}
+
+fn is_valid_workflow_filename(filename: &str) -> bool {
+    !filename.contains('/') && (filename.ends_with(".yml") || filename.ends_with(".yaml"))
+}
+
+/// The subset of claims of a GitHub Actions OIDC token that are relevant for
+/// trusted publishing.
+///
+/// See <https://docs.github.com/en/actions/deployment/security-hardening-your-deployments/about-security-hardening-with-openid-connect#understanding-the-oidc-token>.
+#[derive(Debug, Deserialize)]
+struct GitHubActionsClaims {
+    repository: String,
+    /// e.g. `rust-lang/foo/.github/workflows/release.yml@refs/tags/v1.0.0`
+    workflow_ref: String,
+    environment: Option<String>,
+}
+
+impl GitHubActionsClaims {
+    /// Extracts the workflow filename from the `workflow_ref` claim.
+    fn workflow_filename(&self) -> Option<&str> {
+        let (path, _git_ref) = self.workflow_ref.split_once('@')?;
+        let prefix = format!("{}/.github/workflows/", self.repository);
+        path.strip_prefix(&prefix)
+            .filter(|filename| !filename.is_empty() && !filename.contains('/'))
+    }
+}
+
+/// Verifies the signature, issuer, audience and expiry of a GitHub Actions
+/// OIDC token and returns its claims.
+///
+/// The audience of the token has to be the domain name of this crates.io
+/// instance.
+fn verify_github_actions_jwt(app: &AppState, jwt: &str) -> AppResult<GitHubActionsClaims> {
+    let header = jsonwebtoken::decode_header(jwt)
+        .map_err(|e| bad_request(&format!("failed to decode OIDC token: {e}")))?;
+
+    let key_id = header
+        .kid
+        .ok_or_else(|| bad_request("OIDC token is missing a key id"))?;
+
+    let jwks = get_github_actions_jwks(app, &key_id)
+        .map_err(|e| bad_request(&format!("failed to fetch GitHub Actions OIDC keys: {e}")))?;
+
+    let jwk = jwks
+        .find(&key_id)
+        .ok_or_else(|| bad_request(&format!("unknown key id {key_id}")))?;
+
+    let key = DecodingKey::from_jwk(jwk)
+        .map_err(|e| bad_request(&format!("failed to parse GitHub Actions OIDC key: {e}")))?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[GITHUB_ACTIONS_ISSUER]);
+    validation.set_audience(&[&app.config.domain_name]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+
+    let data = jsonwebtoken::decode::<GitHubActionsClaims>(jwt, &key, &validation)
+        .map_err(|e| bad_request(&format!("invalid OIDC token: {e}")))?;
+
+    Ok(data.claims)
+}
+
+/// Returns the OIDC keys of GitHub Actions from the cache, or fetches them
+/// from GitHub if the cache is expired or does not contain the requested key
+/// id yet (e.g. because GitHub has rotated their keys).
+fn get_github_actions_jwks(app: &AppState, key_id: &str) -> AppResult<JwkSet> {
+    let mut cache = JWKS_CACHE.lock();
+    if let Some((jwks, timestamp)) = cache.as_ref() {
+        let expires_at = *timestamp + Duration::seconds(JWKS_CACHE_LIFETIME_SECONDS);
+        if Utc::now().naive_utc() < expires_at && jwks.find(key_id).is_some() {
+            return Ok(jwks.clone());
+        }
+    }
+
+    let jwks = app.github.actions_oidc_keys()?;
+    *cache = Some((jwks.clone(), Utc::now().naive_utc()));
+
+    Ok(jwks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_validation() {
+        assert!(is_valid_repository("rust-lang/crates.io"));
+        assert!(is_valid_repository("foo_bar/baz-1"));
+        assert!(!is_valid_repository("rust-lang"));
+        assert!(!is_valid_repository("/crates.io"));
+        assert!(!is_valid_repository("rust-lang/"));
+        assert!(!is_valid_repository("rust-lang/crates.io/foo"));
+        assert!(!is_valid_repository("rust lang/crates.io"));
+    }
+
+    #[test]
+    fn workflow_filename_validation() {
+        assert!(is_valid_workflow_filename("release.yml"));
+        assert!(is_valid_workflow_filename("release.yaml"));
+        assert!(!is_valid_workflow_filename("release"));
+        assert!(!is_valid_workflow_filename(".github/workflows/release.yml"));
+    }
+
+    #[test]
+    fn workflow_filename_from_claims() {
+        let claims = |workflow_ref: &str| GitHubActionsClaims {
+            repository: "rust-lang/foo".into(),
+            workflow_ref: workflow_ref.into(),
+            environment: None,
+        };
+
+        let c = claims("rust-lang/foo/.github/workflows/release.yml@refs/tags/v1.0.0");
+        assert_eq!(c.workflow_filename(), Some("release.yml"));
+
+        let c = claims("rust-lang/bar/.github/workflows/release.yml@refs/heads/main");
+        assert_eq!(c.workflow_filename(), None);
+
+        let c = claims("rust-lang/foo/.github/workflows/release.yml");
+        assert_eq!(c.workflow_filename(), None);
+
+        let c = claims("rust-lang/foo/.github/workflows/nested/release.yml@refs/heads/main");
+        assert_eq!(c.workflow_filename(), None);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod me;
 pub mod other;
+pub mod second_factor;
 pub mod session;
@@ -6,8 +6,10 @@ use crate::controllers::frontend_prelude::*;
 use crate::controllers::helpers::*;
 
 use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::models::token::EndpointScope;
 use crate::models::{
-    CrateOwner, Email, Follow, NewEmail, OwnerKind, User, Version, VersionOwnerAction,
+    CrateOwner, Email, Follow, NewEmail, OwnerKind, User, Version, VersionAdvisory,
+    VersionOwnerAction,
 };
 use crate::schema::{crate_owners, crates, emails, follows, users, versions};
 use crate::views::{EncodableMe, EncodablePrivateUser, EncodableVersion, OwnedCrate};
@@ -16,7 +18,10 @@ use crate::views::{EncodableMe, EncodablePrivateUser, EncodableVersion, OwnedCra
 pub async fn me(app: AppState, req: Parts) -> AppResult<Json<EncodableMe>> {
     conduit_compat(move || {
         let conn = &mut *app.db_read_prefer_primary()?;
-        let user_id = AuthCheck::only_cookie().check(&req, conn)?.user_id();
+        let user_id = AuthCheck::only_cookie()
+            .with_endpoint_scope(EndpointScope::ReadPrivate)
+            .check(&req, conn)?
+            .user_id();
 
         let (user, verified, email, verification_sent): (User, Option<bool>, Option<String>, bool) =
             users::table
@@ -58,7 +63,9 @@ pub async fn me(app: AppState, req: Parts) -> AppResult<Json<EncodableMe>> {
 pub async fn updates(app: AppState, req: Parts) -> AppResult<Json<Value>> {
     conduit_compat(move || {
         let conn = &mut app.db_read_prefer_primary()?;
-        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let auth = AuthCheck::only_cookie()
+            .with_endpoint_scope(EndpointScope::ReadPrivate)
+            .check(&req, conn)?;
         let user = auth.user();
 
         let followed_crates = Follow::belonging_to(user).select(follows::crate_id);
@@ -79,12 +86,13 @@ pub async fn updates(app: AppState, req: Parts) -> AppResult<Json<Value>> {
         let data = data
             .into_iter()
             .zip(VersionOwnerAction::for_versions(conn, &versions)?)
-            .map(|((v, cn, pb), voas)| (v, cn, pb, voas));
+            .zip(VersionAdvisory::for_versions(conn, &versions)?)
+            .map(|(((v, cn, pb), voas), advs)| (v, cn, pb, voas, advs));
 
         let versions = data
             .into_iter()
-            .map(|(version, crate_name, published_by, actions)| {
-                EncodableVersion::from(version, &crate_name, published_by, actions)
+            .map(|(version, crate_name, published_by, actions, advisories)| {
+                EncodableVersion::from(version, &crate_name, published_by, actions, advisories)
             })
             .collect::<Vec<_>>();
 
@@ -108,7 +116,9 @@ pub async fn update_user(
 
         let conn = &mut state.db_write()?;
 
-        let auth = AuthCheck::default().check(&req, conn)?;
+        let auth = AuthCheck::default()
+            .require_second_factor()
+            .check(&req, conn)?;
         let user = auth.user();
 
         // need to check if current user matches user to be updated
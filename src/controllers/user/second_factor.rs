@@ -0,0 +1,350 @@
+//! Endpoints for enrolling TOTP authenticator apps and WebAuthn credentials
+//! as a second factor, and for verifying them before sensitive actions.
+//!
+//! Once a user enrolled a second factor, actions like creating API tokens or
+//! changing crate owners via a cookie session require a recent verification
+//! of the second factor, which is recorded in the session.
+
+use crate::auth::AuthCheck;
+use crate::controllers::frontend_prelude::*;
+use crate::middleware::session::RequestSession;
+use crate::models::{NewWebauthnCredential, TotpSecret, WebauthnCredential};
+use crate::rate_limiter::LimitedAction;
+use crate::schema::{totp_secrets, webauthn_credentials};
+use crate::util::{totp, webauthn};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use diesel::dsl::{exists, select};
+use serde_json as json;
+
+/// Session key holding the challenge of the current WebAuthn ceremony.
+const WEBAUTHN_CHALLENGE_KEY: &str = "webauthn_challenge";
+
+/// Maximum number of WebAuthn credentials per user.
+const MAX_WEBAUTHN_CREDENTIALS_PER_USER: i64 = 10;
+
+fn decode_base64(value: &str, field: &str) -> AppResult<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| bad_request(&format!("{field} must be base64url-encoded")))
+}
+
+/// Handles the `GET /me/second_factor` route.
+pub async fn show(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+
+        let totp = TotpSecret::find(conn, user.id)?;
+        let credentials: Vec<WebauthnCredential> = WebauthnCredential::belonging_to(user)
+            .select(WebauthnCredential::as_select())
+            .order(webauthn_credentials::id)
+            .load(conn)?;
+
+        Ok(Json(json!({
+            "totp_enabled": totp.is_some_and(|totp| totp.is_enabled()),
+            "webauthn_credentials": credentials,
+            "verified": req.session().has_recent_second_factor(),
+        })))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/totp` route.
+///
+/// Generates a new secret, which the user adds to their authenticator app.
+/// The secret is only used as a second factor once the enrollment is
+/// confirmed with a valid code.
+pub async fn begin_totp(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie()
+            .require_second_factor()
+            .check(&req, conn)?;
+        let user = auth.user();
+
+        if TotpSecret::find(conn, user.id)?.is_some_and(|totp| totp.is_enabled()) {
+            return Err(bad_request(
+                "an authenticator app is already enrolled, remove it first",
+            ));
+        }
+
+        let totp = TotpSecret::begin_enrollment(conn, user.id)?;
+        let domain_name = &app.config.domain_name;
+
+        Ok(Json(json!({
+            "secret": totp::encode_secret(&totp.secret),
+            "uri": totp::provisioning_uri(&totp.secret, domain_name, &user.gh_login),
+        })))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/totp/confirm` route.
+pub async fn confirm_totp(app: AppState, req: BytesRequest) -> AppResult<Response> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct ConfirmRequest {
+            code: String,
+        }
+
+        let body: ConfirmRequest =
+            json::from_slice(req.body()).map_err(|_| bad_request("invalid json request"))?;
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+
+        app.rate_limiter
+            .check_rate_limit(user.id, LimitedAction::SecondFactor, conn)?;
+
+        let totp = match TotpSecret::find(conn, user.id)? {
+            Some(totp) if !totp.is_enabled() => totp,
+            _ => return Err(bad_request("no pending authenticator app enrollment found")),
+        };
+
+        if !totp.verify_code(conn, &body.code)? {
+            return Err(bad_request("invalid code"));
+        }
+
+        totp.enable(conn)?;
+        req.session().record_second_factor();
+
+        ok_true()
+    })
+    .await
+}
+
+/// Handles the `DELETE /me/totp` route.
+pub async fn remove_totp(app: AppState, req: Parts) -> AppResult<Response> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie()
+            .require_second_factor()
+            .check(&req, conn)?;
+
+        diesel::delete(totp_secrets::table.find(auth.user_id())).execute(conn)?;
+
+        ok_true()
+    })
+    .await
+}
+
+/// Handles the `GET /api/private/second_factor/challenge` route.
+///
+/// Returns a new challenge for the `navigator.credentials.create()` and
+/// `navigator.credentials.get()` calls of the frontend, and the IDs of the
+/// credentials that the user already enrolled.
+pub async fn challenge(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+
+        let credential_ids: Vec<String> = WebauthnCredential::belonging_to(auth.user())
+            .select(webauthn_credentials::credential_id)
+            .load(conn)?;
+
+        let challenge = webauthn::generate_challenge();
+        req.session()
+            .insert(WEBAUTHN_CHALLENGE_KEY.to_string(), challenge.clone());
+
+        Ok(Json(json!({
+            "challenge": challenge,
+            "rp_id": app.config.domain_name,
+            "credential_ids": credential_ids,
+        })))
+    })
+    .await
+}
+
+/// Handles the `PUT /me/webauthn_credentials` route.
+///
+/// All binary fields are base64url-encoded, and the `public_key` is the
+/// DER-encoded key returned by `AuthenticatorAttestationResponse.getPublicKey()`.
+pub async fn new_webauthn_credential(app: AppState, req: BytesRequest) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct NewCredentialRequest {
+            webauthn_credential: NewCredentialParams,
+        }
+
+        #[derive(Deserialize)]
+        struct NewCredentialParams {
+            name: String,
+            credential_id: String,
+            client_data_json: String,
+            public_key: String,
+        }
+
+        let new: NewCredentialRequest = json::from_slice(req.body())
+            .map_err(|e| bad_request(&format!("invalid new webauthn credential request: {e:?}")))?;
+        let params = new.webauthn_credential;
+
+        let name = params.name.trim();
+        if name.is_empty() {
+            return Err(bad_request("name must have a value"));
+        }
+
+        let client_data_json = decode_base64(&params.client_data_json, "client_data_json")?;
+        let public_key = decode_base64(&params.public_key, "public_key")?;
+        decode_base64(&params.credential_id, "credential_id")?;
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie()
+            .require_second_factor()
+            .check(&req, conn)?;
+        let user = auth.user();
+
+        let challenge = req
+            .session()
+            .remove(WEBAUTHN_CHALLENGE_KEY)
+            .ok_or_else(|| bad_request("no pending webauthn challenge found"))?;
+
+        let public_key = webauthn::verify_registration(
+            &client_data_json,
+            &public_key,
+            &challenge,
+            &app.config.domain_name,
+        )
+        .map_err(|error| bad_request(&error))?;
+
+        let count: i64 = WebauthnCredential::belonging_to(user)
+            .count()
+            .get_result(conn)?;
+        if count >= MAX_WEBAUTHN_CREDENTIALS_PER_USER {
+            return Err(bad_request(&format!(
+                "maximum webauthn credentials per user is: {MAX_WEBAUTHN_CREDENTIALS_PER_USER}"
+            )));
+        }
+
+        let query = webauthn_credentials::table
+            .filter(webauthn_credentials::credential_id.eq(&params.credential_id));
+        if select(exists(query)).get_result(conn)? {
+            return Err(bad_request("this credential has already been registered"));
+        }
+
+        let credential = NewWebauthnCredential {
+            user_id: user.id,
+            name: name.to_string(),
+            credential_id: params.credential_id,
+            public_key,
+        }
+        .insert(conn)?;
+
+        Ok(Json(json!({ "webauthn_credential": credential })))
+    })
+    .await
+}
+
+/// Handles the `DELETE /me/webauthn_credentials/:id` route.
+pub async fn remove_webauthn_credential(
+    app: AppState,
+    Path(id): Path<i32>,
+    req: Parts,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie()
+            .require_second_factor()
+            .check(&req, conn)?;
+
+        diesel::delete(WebauthnCredential::belonging_to(auth.user()).find(id)).execute(conn)?;
+
+        Ok(Json(json!({})))
+    })
+    .await
+}
+
+/// Handles the `PUT /api/private/second_factor/verify` route.
+///
+/// Verifies either a code of the enrolled authenticator app, or the response
+/// of a `navigator.credentials.get()` call, and records the verification in
+/// the session.
+///
+/// ## Request Body Examples
+///
+/// ```json
+/// { "totp": "123456" }
+/// ```
+///
+/// ```json
+/// {
+///     "webauthn": {
+///         "credential_id": "...",
+///         "client_data_json": "...",
+///         "authenticator_data": "...",
+///         "signature": "..."
+///     }
+/// }
+/// ```
+pub async fn verify(app: AppState, req: BytesRequest) -> AppResult<Response> {
+    conduit_compat(move || {
+        #[derive(Deserialize)]
+        struct VerifyRequest {
+            totp: Option<String>,
+            webauthn: Option<WebauthnAssertion>,
+        }
+
+        #[derive(Deserialize)]
+        struct WebauthnAssertion {
+            credential_id: String,
+            client_data_json: String,
+            authenticator_data: String,
+            signature: String,
+        }
+
+        let body: VerifyRequest =
+            json::from_slice(req.body()).map_err(|_| bad_request("invalid json request"))?;
+
+        let conn = &mut *app.db_write()?;
+        let auth = AuthCheck::only_cookie().check(&req, conn)?;
+        let user = auth.user();
+
+        app.rate_limiter
+            .check_rate_limit(user.id, LimitedAction::SecondFactor, conn)?;
+
+        let verified = match (body.totp, body.webauthn) {
+            (Some(code), None) => match TotpSecret::find(conn, user.id)? {
+                Some(totp) if totp.is_enabled() => totp.verify_code(conn, &code)?,
+                _ => false,
+            },
+            (None, Some(assertion)) => {
+                let challenge = req
+                    .session()
+                    .remove(WEBAUTHN_CHALLENGE_KEY)
+                    .ok_or_else(|| bad_request("no pending webauthn challenge found"))?;
+
+                let credential = WebauthnCredential::find(conn, user.id, &assertion.credential_id)?
+                    .ok_or_else(|| bad_request("unknown webauthn credential"))?;
+
+                let result = webauthn::verify_assertion(
+                    &credential.public_key,
+                    &decode_base64(&assertion.client_data_json, "client_data_json")?,
+                    &decode_base64(&assertion.authenticator_data, "authenticator_data")?,
+                    &decode_base64(&assertion.signature, "signature")?,
+                    &challenge,
+                    &app.config.domain_name,
+                );
+
+                match result {
+                    Ok(sign_count) => credential.record_use(conn, sign_count)?,
+                    Err(error) => {
+                        debug!(credential_id = %credential.id, "Invalid webauthn assertion: {error}");
+                        false
+                    }
+                }
+            }
+            _ => return Err(bad_request("either `totp` or `webauthn` must be provided")),
+        };
+
+        if !verified {
+            return Err(bad_request("the second factor could not be verified"));
+        }
+
+        req.session().record_second_factor();
+
+        ok_true()
+    })
+    .await
+}
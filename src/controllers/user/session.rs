@@ -106,6 +106,7 @@ pub async fn authorize(
 
         // Log in by setting a cookie and the middleware authentication
         session.insert("user_id".to_string(), user.id.to_string());
+        session.clear_second_factor();
 
         Ok(req)
     })
@@ -147,6 +148,7 @@ fn save_user_to_database(
 /// Handles the `DELETE /api/private/session` route.
 pub async fn logout(session: SessionExtension) -> Json<bool> {
     session.remove("user_id");
+    session.clear_second_factor();
     Json(true)
 }
 
@@ -7,7 +7,7 @@
 
 use crate::controllers::frontend_prelude::*;
 
-use crate::models::{Crate, User, Version, VersionOwnerAction};
+use crate::models::{Crate, User, Version, VersionAdvisory, VersionOwnerAction};
 use crate::schema::*;
 use crate::views::EncodableVersion;
 
@@ -40,9 +40,12 @@ pub async fn index(app: AppState, req: Parts) -> AppResult<Json<Value>> {
         let versions = versions_and_publishers
             .into_iter()
             .zip(VersionOwnerAction::for_versions(conn, &versions)?)
-            .map(|((version, crate_name, published_by), actions)| {
-                EncodableVersion::from(version, &crate_name, published_by, actions)
-            })
+            .zip(VersionAdvisory::for_versions(conn, &versions)?)
+            .map(
+                |(((version, crate_name, published_by), actions), advisories)| {
+                    EncodableVersion::from(version, &crate_name, published_by, actions, advisories)
+                },
+            )
             .collect::<Vec<_>>();
 
         Ok(Json(json!({ "versions": versions })))
@@ -67,8 +70,15 @@ pub async fn show_by_id(state: AppState, Path(id): Path<i32>) -> AppResult<Json<
             ))
             .first(conn)?;
         let audit_actions = VersionOwnerAction::by_version(conn, &version)?;
+        let advisories = VersionAdvisory::by_version(conn, &version)?;
 
-        let version = EncodableVersion::from(version, &krate.name, published_by, audit_actions);
+        let version = EncodableVersion::from(
+            version,
+            &krate.name,
+            published_by,
+            audit_actions,
+            advisories,
+        );
         Ok(Json(json!({ "version": version })))
     })
     .await
@@ -8,6 +8,7 @@ use crate::db::PoolError;
 use crate::middleware::log_request::RequestLogExt;
 use crate::models::{Crate, VersionDownload};
 use crate::schema::*;
+use crate::util::errors::{internal, not_found};
 use crate::views::EncodableVersionDownload;
 use chrono::{Duration, NaiveDate, Utc};
 use tracing::Instrument;
@@ -29,7 +30,26 @@ pub async fn download(
         .instrument(info_span!("cache.read", ?cache_key))
         .await;
 
-    let (crate_name, version) = if let Some(version_id) = cache_result {
+    let (crate_name, version) = if let Some(mirror) = &app.mirror {
+        // A mirror has no database records of the crates of the upstream
+        // registry, so downloads are not counted. Instead, the crate file is
+        // fetched from the upstream registry if it is not stored yet.
+        let is_available = Crate::valid_name(&crate_name)
+            && mirror
+                .cache_crate_file(&app.storage, &crate_name, &version)
+                .await
+                .map_err(|error| {
+                    internal(format!(
+                        "Failed to fetch crate file of {crate_name} {version}: {error:#}"
+                    ))
+                })?;
+
+        if !is_available {
+            return Err(not_found());
+        }
+
+        (crate_name, version)
+    } else if let Some(version_id) = cache_result {
         app.instance_metrics.version_id_cache_hits.inc();
 
         // The increment does not happen instantly, but it's deferred to be executed in a batch
@@ -131,6 +151,18 @@ pub async fn download(
         .await?
     };
 
+    // The crate files of a private registry must not be publicly accessible,
+    // so they are served directly instead of redirecting to the CDN.
+    if app.config.private_registry {
+        let bytes = match app.storage.download_crate_file(&crate_name, &version).await {
+            Ok(bytes) => bytes,
+            Err(object_store::Error::NotFound { .. }) => return Err(not_found()),
+            Err(error) => return Err(internal(format!("Failed to read crate file: {error}"))),
+        };
+
+        return Ok(([(header::CONTENT_TYPE, "application/gzip")], bytes).into_response());
+    }
+
     let redirect_url = app.storage.crate_location(&crate_name, &version);
     if wants_json {
         Ok(Json(json!({ "url": redirect_url })).into_response())
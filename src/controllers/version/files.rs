@@ -0,0 +1,92 @@
+//! Endpoints for browsing the files inside of published crate files
+
+use crate::controllers::frontend_prelude::*;
+
+use crate::models::VersionFileListing;
+use crate::util::errors::{internal, not_found};
+use crates_io_tarball::{read_file, FileKind, TarballFile};
+
+use super::version_and_crate;
+
+/// Handles the `GET /crates/:crate_id/:version/files` route.
+///
+/// The file listing is created by a background job after the crate file has
+/// been uploaded, so this returns a 404 response until the job has finished.
+pub async fn list(
+    app: AppState,
+    Path((crate_name, version)): Path<(String, String)>,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        if semver::Version::parse(&version).is_err() {
+            return Err(cargo_err(&format_args!("invalid semver: {version}")));
+        }
+
+        let conn = &mut *app.db_read()?;
+        let (version, _) = version_and_crate(conn, &crate_name, &version)?;
+
+        let Some(listing) = VersionFileListing::find(conn, version.id)? else {
+            return Err(not_found());
+        };
+
+        Ok(Json(json!({ "files": listing.files })))
+    })
+    .await
+}
+
+/// Handles the `GET /crates/:crate_id/:version/files/*path` route.
+///
+/// Extracts a single file out of the stored crate file and returns its
+/// contents as `text/plain` if it is valid UTF-8, or as
+/// `application/octet-stream` otherwise.
+pub async fn show(
+    app: AppState,
+    Path((crate_name, version, path)): Path<(String, String, String)>,
+) -> AppResult<Response> {
+    let (crate_name, version, path) = conduit_compat({
+        let app = app.clone();
+        move || {
+            if semver::Version::parse(&version).is_err() {
+                return Err(cargo_err(&format_args!("invalid semver: {version}")));
+            }
+
+            let conn = &mut *app.db_read()?;
+            let (version, krate) = version_and_crate(conn, &crate_name, &version)?;
+
+            // Avoid downloading the crate file if we already know that it
+            // does not contain the requested file.
+            if let Some(listing) = VersionFileListing::find(conn, version.id)? {
+                let files: Vec<TarballFile> = serde_json::from_value(listing.files)?;
+                let is_file = |file: &TarballFile| file.path == path && file.kind == FileKind::File;
+                if !files.iter().any(is_file) {
+                    return Err(not_found());
+                }
+            }
+
+            Ok((krate.name, version.num, path))
+        }
+    })
+    .await?;
+
+    let tarball = app
+        .storage
+        .download_crate_file(&crate_name, &version)
+        .await
+        .map_err(|error| match error {
+            object_store::Error::NotFound { .. } => not_found(),
+            error => internal(format!("failed to download crate file: {error}")),
+        })?;
+
+    let max_unpack_size = app.config.max_unpack_size;
+    let contents = conduit_compat(move || {
+        let pkg_name = format!("{crate_name}-{version}");
+        read_file(&pkg_name, &*tarball, max_unpack_size, &path)?.ok_or_else(not_found)
+    })
+    .await?;
+
+    let content_type = match std::str::from_utf8(&contents) {
+        Ok(_) => "text/plain; charset=utf-8",
+        Err(_) => "application/octet-stream",
+    };
+
+    Ok(([(header::CONTENT_TYPE, content_type)], contents).into_response())
+}
@@ -6,7 +6,7 @@
 
 use crate::controllers::frontend_prelude::*;
 
-use crate::models::VersionOwnerAction;
+use crate::models::{VersionAdvisory, VersionOwnerAction};
 use crate::views::{EncodableDependency, EncodableVersion};
 
 use super::version_and_crate;
@@ -68,8 +68,10 @@ pub async fn show(
         let (version, krate) = version_and_crate(conn, &crate_name, &version)?;
         let published_by = version.published_by(conn);
         let actions = VersionOwnerAction::by_version(conn, &version)?;
+        let advisories = VersionAdvisory::by_version(conn, &version)?;
 
-        let version = EncodableVersion::from(version, &krate.name, published_by, actions);
+        let version =
+            EncodableVersion::from(version, &krate.name, published_by, actions, advisories);
         Ok(Json(json!({ "version": version })))
     })
     .await
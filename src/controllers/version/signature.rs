@@ -0,0 +1,51 @@
+//! Endpoint for fetching the detached signature of a crate file
+
+use crate::controllers::frontend_prelude::*;
+
+use crate::models::{SigningKey, User, VersionSignature};
+use crate::schema::{signing_keys, users};
+use crate::util::errors::not_found;
+use crate::views::{EncodablePublicUser, EncodableVersionSignature};
+use base64::{engine::general_purpose, Engine};
+
+use super::version_and_crate;
+
+/// Handles the `GET /crates/:crate_id/:version/signature` route.
+///
+/// Returns the base64-encoded signature of the crate file together with the
+/// public key that it was verified against at publish time, so that clients
+/// can verify the downloaded crate file themselves.
+pub async fn show(
+    app: AppState,
+    Path((crate_name, version)): Path<(String, String)>,
+) -> AppResult<Json<Value>> {
+    conduit_compat(move || {
+        if semver::Version::parse(&version).is_err() {
+            return Err(cargo_err(&format_args!("invalid semver: {version}")));
+        }
+
+        let conn = &mut *app.db_read()?;
+        let (version, krate) = version_and_crate(conn, &crate_name, &version)?;
+
+        let Some(signature) = VersionSignature::find(conn, version.id)? else {
+            return Err(not_found());
+        };
+
+        let (key, user): (SigningKey, User) = signing_keys::table
+            .find(signature.signing_key_id)
+            .inner_join(users::table)
+            .select((SigningKey::as_select(), users::all_columns))
+            .first(conn)?;
+
+        let signature = EncodableVersionSignature {
+            signature: general_purpose::STANDARD.encode(&signature.signature),
+            url: app.storage.signature_location(&krate.name, &version.num),
+            key,
+            signed_by: EncodablePublicUser::from(user),
+            created_at: signature.created_at,
+        };
+
+        Ok(Json(json!({ "signature": signature })))
+    })
+    .await
+}
@@ -5,16 +5,25 @@ use crate::auth::AuthCheck;
 use crate::controllers::cargo_prelude::*;
 use crate::models::token::EndpointScope;
 use crate::models::Rights;
-use crate::models::{insert_version_owner_action, VersionAction};
+use crate::models::{insert_version_owner_action, VersionAction, WebhookEvent, YankReason};
 use crate::rate_limiter::LimitedAction;
 use crate::schema::versions;
+use crate::util::asymmetric_token::Mutation;
 use crate::worker::jobs;
+use crate::worker::swirl::BackgroundJob;
+
+/// Maximum number of characters of the message of a yank reason.
+const MAX_YANK_MESSAGE_LENGTH: usize = 500;
 
 /// Handles the `DELETE /crates/:crate_id/:version/yank` route.
 /// This does not delete a crate version, it makes the crate
 /// version accessible only to crates that already have a
 /// `Cargo.lock` containing this version.
 ///
+/// The request body may optionally contain a JSON encoded reason for
+/// yanking the version, e.g. `{"category": "security", "message": "..."}`.
+/// `cargo yank` does not send a request body.
+///
 /// Notes:
 /// Crate deletion is not implemented to avoid breaking builds,
 /// and the goal of yanking a crate is to prevent crates
@@ -22,9 +31,14 @@ use crate::worker::jobs;
 pub async fn yank(
     app: AppState,
     Path((crate_name, version)): Path<(String, String)>,
-    req: Parts,
+    req: BytesRequest,
 ) -> AppResult<Response> {
-    conduit_compat(move || modify_yank(&crate_name, &version, &app, &req, true)).await
+    conduit_compat(move || {
+        let (req, body) = req.0.into_parts();
+        let reason = parse_yank_reason(&body)?;
+        modify_yank(&crate_name, &version, &app, &req, true, reason.as_ref())
+    })
+    .await
 }
 
 /// Handles the `PUT /crates/:crate_id/:version/unyank` route.
@@ -33,7 +47,33 @@ pub async fn unyank(
     Path((crate_name, version)): Path<(String, String)>,
     req: Parts,
 ) -> AppResult<Response> {
-    conduit_compat(move || modify_yank(&crate_name, &version, &app, &req, false)).await
+    conduit_compat(move || modify_yank(&crate_name, &version, &app, &req, false, None)).await
+}
+
+/// Parses the optional yank reason from the request body of the yank
+/// endpoint.
+fn parse_yank_reason(body: &[u8]) -> AppResult<Option<YankReason>> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+
+    let mut reason: YankReason = serde_json::from_slice(body)
+        .map_err(|e| cargo_err(&format_args!("invalid yank reason: {e}")))?;
+
+    reason.message = reason
+        .message
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty());
+
+    if let Some(message) = &reason.message {
+        if message.chars().count() > MAX_YANK_MESSAGE_LENGTH {
+            return Err(cargo_err(&format_args!(
+                "yank message must not be longer than {MAX_YANK_MESSAGE_LENGTH} characters"
+            )));
+        }
+    }
+
+    Ok(Some(reason))
 }
 
 /// Changes `yanked` flag on a crate version record
@@ -43,6 +83,7 @@ fn modify_yank(
     state: &AppState,
     req: &Parts,
     yanked: bool,
+    reason: Option<&YankReason>,
 ) -> AppResult<Response> {
     // FIXME: Should reject bad requests before authentication, but can't due to
     // lifetime issues with `req`.
@@ -53,9 +94,19 @@ fn modify_yank(
 
     let conn = &mut *state.db_write()?;
 
+    let name = crate_name.to_string();
+    let vers = version.to_string();
+    let mutation = if yanked {
+        Mutation::Yank { name, vers }
+    } else {
+        Mutation::Unyank { name, vers }
+    };
+
     let auth = AuthCheck::default()
         .with_endpoint_scope(EndpointScope::Yank)
         .for_crate(crate_name)
+        .with_mutation(mutation)
+        .require_second_factor()
         .check(req, conn)?;
 
     state
@@ -71,24 +122,41 @@ fn modify_yank(
         return Err(cargo_err("must already be an owner to yank or unyank"));
     }
 
-    if version.yanked == yanked {
+    if version.yanked == yanked && reason.is_none() {
         // The crate is already in the state requested, nothing to do
         return ok_true();
     }
 
-    diesel::update(&version)
-        .set(versions::yanked.eq(yanked))
-        .execute(conn)?;
+    // Yanking an already yanked version with a reason only updates the
+    // reason that is shown in the API and the index.
+    if version.yanked != yanked {
+        diesel::update(&version)
+            .set(versions::yanked.eq(yanked))
+            .execute(conn)?;
+    }
 
-    let action = if yanked {
-        VersionAction::Yank
+    let (action, event) = if yanked {
+        (VersionAction::Yank, WebhookEvent::Yank)
     } else {
-        VersionAction::Unyank
+        (VersionAction::Unyank, WebhookEvent::Unyank)
     };
 
-    insert_version_owner_action(conn, version.id, user.id, api_token_id, action)?;
+    let action =
+        insert_version_owner_action(conn, version.id, user.id, api_token_id, action, reason)?;
 
     jobs::enqueue_sync_to_index(&krate.name, conn)?;
+    jobs::SendVersionNotifications::new(action.id).enqueue(conn)?;
+
+    // Webhooks are only notified about actual changes of the `yanked` flag,
+    // not about updates of the yank reason.
+    if version.yanked != yanked {
+        let details = json!({
+            "version": version.num,
+            "actor": user.gh_login,
+            "reason": reason,
+        });
+        jobs::enqueue_webhooks(conn, krate.id, event, details)?;
+    }
 
     ok_true()
 }
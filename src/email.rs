@@ -5,6 +5,7 @@ use crate::util::errors::{server_error, AppResult};
 
 use crate::config;
 use crate::Env;
+use chrono::NaiveDateTime;
 use lettre::message::header::ContentType;
 use lettre::transport::file::FileTransport;
 use lettre::transport::smtp::authentication::{Credentials, Mechanism};
@@ -118,6 +119,57 @@ Source type: {source}\n",
         self.send(email, subject, &body)
     }
 
+    /// Attempts to send a notification about a published, yanked or unyanked
+    /// version of a crate that the recipient owns.
+    ///
+    /// `action` is the past tense of the action, e.g. `published`, and
+    /// `token_name` is the name of the API token that was used, if any.
+    pub fn send_version_notification(
+        &self,
+        email: &str,
+        crate_name: &str,
+        version: &str,
+        action: &str,
+        actor: &str,
+        token_name: Option<&str>,
+    ) -> AppResult<()> {
+        let subject = format!("Crate {crate_name} v{version} has been {action}");
+        let authentication = match token_name {
+            Some(token_name) => format!("using the API token \"{token_name}\""),
+            None => "from the crates.io website".to_string(),
+        };
+        let body = format!(
+            "Version {version} of the crate {crate_name} has been {action} by {actor} {authentication}.\n
+If you did not expect this, please review your API tokens at https://{domain}/settings/tokens
+and contact help@crates.io immediately.\n
+You are receiving this email because you are an owner of {crate_name}. You can disable
+these notifications at https://{domain}/me.",
+            domain = crate::config::domain_name()
+        );
+
+        self.send(email, &subject, &body)
+    }
+
+    /// Attempts to send a reminder that an API token is about to expire.
+    pub fn send_token_expiry_notification(
+        &self,
+        email: &str,
+        token_name: &str,
+        expired_at: NaiveDateTime,
+    ) -> AppResult<()> {
+        let subject = format!("Your API token \"{token_name}\" is about to expire");
+        let expired_at = expired_at.format("%Y-%m-%d %H:%M UTC");
+        let body = format!(
+            "Your crates.io API token \"{token_name}\" will expire on {expired_at}.\n
+After that, it can not be used for `cargo publish` or other commands anymore.
+You can create a new token at https://{domain}/settings/tokens/new and update
+your release pipelines to use it before the old token expires.",
+            domain = crate::config::domain_name()
+        );
+
+        self.send(email, &subject, &body)
+    }
+
     /// This is supposed to be used only during tests, to retrieve the messages stored in the
     /// "memory" backend. It's not cfg'd away because our integration tests need to access this.
     pub fn mails_in_memory(&self) -> Option<Vec<StoredEmail>> {
@@ -1,5 +1,6 @@
 //! This module implements functionality for interacting with GitHub.
 
+use jsonwebtoken::jwk::JwkSet;
 use oauth2::AccessToken;
 use reqwest::{self, header};
 
@@ -34,6 +35,7 @@ pub trait GitHubClient: Send + Sync {
         auth: &AccessToken,
     ) -> AppResult<GitHubOrgMembership>;
     fn public_keys(&self, username: &str, password: &str) -> AppResult<Vec<GitHubPublicKey>>;
+    fn actions_oidc_keys(&self) -> AppResult<JwkSet>;
 }
 
 #[derive(Debug)]
@@ -149,6 +151,22 @@ impl GitHubClient for RealGitHubClient {
             Err(e) => Err(e),
         }
     }
+
+    /// Returns the set of public keys that can be used to verify the OIDC
+    /// tokens issued to GitHub Actions workflows
+    fn actions_oidc_keys(&self) -> AppResult<JwkSet> {
+        let url = "https://token.actions.githubusercontent.com/.well-known/jwks";
+        info!("GITHUB HTTP: {url}");
+
+        self.client()
+            .get(url)
+            .header(header::USER_AGENT, "crates.io (https://crates.io)")
+            .send()?
+            .error_for_status()
+            .map_err(|e| handle_error_response(&e))?
+            .json()
+            .map_err(Into::into)
+    }
 }
 
 fn handle_error_response(error: &reqwest::Error) -> BoxedAppError {
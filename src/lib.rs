@@ -49,6 +49,7 @@ pub mod headers;
 mod licenses;
 pub mod metrics;
 pub mod middleware;
+pub mod mirror;
 pub mod models;
 pub mod rate_limiter;
 mod real_ip;
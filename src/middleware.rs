@@ -5,7 +5,9 @@ mod common_headers;
 mod debug;
 mod ember_html;
 pub mod log_request;
+mod mirror;
 pub mod normalize_path;
+mod private_registry;
 pub mod real_ip;
 mod require_user_agent;
 pub mod session;
@@ -39,6 +41,8 @@ pub fn apply_axum_middleware(state: AppState, router: Router<(), TimeoutBody<Bod
         info!("BalanceCapacity middleware not enabled. DB_PRIMARY_POOL_SIZE is too low.");
     }
 
+    let serve_storage = config.storage.served_path().is_some() && !config.private_registry;
+
     let middleware = tower::ServiceBuilder::new()
         .layer(CompressionLayer::new().quality(CompressionLevel::Fastest))
         .layer(RequestBodyTimeoutLayer::new(Duration::from_secs(30)))
@@ -74,12 +78,17 @@ pub fn apply_axum_middleware(state: AppState, router: Router<(), TimeoutBody<Bod
             state.clone(),
             block_traffic::block_routes,
         ))
+        .layer(conditional_layer(config.mirror.is_some(), || {
+            from_fn_with_state(state.clone(), mirror::reject_changes)
+        }))
         .layer(from_fn_with_state(
             state.clone(),
             common_headers::add_common_headers,
         ))
-        .layer(conditional_layer(env == Env::Development, || {
-            from_fn(static_or_continue::serve_local_uploads)
+        // Files of private registries are only served by the download endpoint,
+        // which requires authentication.
+        .layer(conditional_layer(serve_storage, || {
+            from_fn_with_state(state.clone(), static_or_continue::serve_storage)
         }))
         .layer(conditional_layer(config.serve_dist, || {
             from_fn(static_or_continue::serve_dist)
@@ -88,6 +97,10 @@ pub fn apply_axum_middleware(state: AppState, router: Router<(), TimeoutBody<Bod
             from_fn_with_state(state.clone(), ember_html::serve_html)
         }))
         .layer(AddExtensionLayer::new(state.clone()))
+        // Authentication requires the `AppState` extension of the request
+        .layer(conditional_layer(config.private_registry, || {
+            from_fn_with_state(state.clone(), private_registry::require_authentication)
+        }))
         // This is currently the final middleware to run. If a middleware layer requires a database
         // connection, it should be run after this middleware so that the potential pool usage can be
         // tracked here.
@@ -0,0 +1,27 @@
+//! Middleware that rejects all changes if the server acts as a read-only
+//! mirror of an upstream registry.
+
+use crate::app::AppState;
+use crate::util::errors::bad_request;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use http::Method;
+
+pub async fn reject_changes<B>(
+    state: AppState,
+    req: http::Request<B>,
+    next: Next<B>,
+) -> axum::response::Response {
+    let method = req.method();
+    let is_read = method == Method::GET || method == Method::HEAD || method == Method::OPTIONS;
+
+    if let Some(config) = state.config.mirror.as_ref().filter(|_| !is_read) {
+        let detail = format!(
+            "this registry is a read-only mirror of {}",
+            config.upstream_index
+        );
+        return bad_request(&detail).into_response();
+    }
+
+    next.run(req).await
+}
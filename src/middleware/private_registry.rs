@@ -0,0 +1,62 @@
+//! Middleware that requires authentication for all read requests if the
+//! server acts as a private registry.
+//!
+//! Cargo sends the token of the registry with every request if the
+//! `config.json` file of the index contains `"auth-required": true`. Requests
+//! that change anything are not affected by this middleware, since their
+//! handlers perform stricter checks on their own.
+
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::conduit_axum::conduit_compat;
+use crate::models::token::EndpointScope;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::{header, Method, Request, StatusCode};
+
+/// Paths that are accessible without authentication, which are needed to
+/// log in on the website.
+const PUBLIC_PATHS: &[&str] = &["/api/private/session/", "/api/v1/site_metadata"];
+
+pub async fn require_authentication<B>(
+    state: AppState,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let path = req.uri().path();
+    let is_read = req.method() == Method::GET || req.method() == Method::HEAD;
+    let is_protected = path.starts_with("/api/") || path.starts_with("/index/");
+    let is_public = PUBLIC_PATHS.iter().any(|public| path.starts_with(public));
+
+    if !is_read || !is_protected || is_public {
+        return next.run(req).await;
+    }
+
+    let is_index = path.starts_with("/index/");
+    let (parts, body) = req.into_parts();
+
+    let app = state.clone();
+    let result = conduit_compat(move || {
+        let conn = &mut *app.db_read_prefer_primary()?;
+        AuthCheck::default()
+            .with_endpoint_scope(EndpointScope::Read)
+            .check(&parts, conn)?;
+
+        Ok(parts)
+    })
+    .await;
+
+    match result {
+        Ok(parts) => next.run(Request::from_parts(parts, body)).await,
+        // Cargo expects a `401 Unauthorized` response for index requests
+        // without a valid token.
+        // see <https://doc.rust-lang.org/cargo/reference/registry-web-api.html#authentication>
+        Err(_) if is_index => {
+            let domain = &state.config.domain_name;
+            let challenge = format!(r#"Cargo login_url="https://{domain}/settings/tokens""#);
+            let headers = [(header::WWW_AUTHENTICATE, challenge)];
+            (StatusCode::UNAUTHORIZED, headers).into_response()
+        }
+        Err(error) => error.into_response(),
+    }
+}
@@ -4,6 +4,7 @@ use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use axum_extra::extract::SignedCookieJar;
 use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
 use cookie::time::Duration;
 use cookie::{Cookie, SameSite};
 use http::Request;
@@ -15,6 +16,14 @@ use std::sync::Arc;
 static COOKIE_NAME: &str = "cargo_session";
 static MAX_AGE_DAYS: i64 = 90;
 
+/// Session key holding the Unix timestamp at which the user last verified a
+/// second factor.
+static SECOND_FACTOR_KEY: &str = "second_factor_verified_at";
+
+/// Number of minutes after a second factor verification during which
+/// sensitive actions are allowed without verifying the second factor again.
+pub const SECOND_FACTOR_MAX_AGE_MINUTES: i64 = 15;
+
 #[derive(Clone, FromRequestParts)]
 #[from_request(via(Extension))]
 pub struct SessionExtension(Arc<RwLock<Session>>);
@@ -40,6 +49,30 @@ impl SessionExtension {
         session.dirty = true;
         session.data.remove(key)
     }
+
+    /// Records that the user just verified their second factor.
+    pub fn record_second_factor(&self) {
+        let verified_at = Utc::now().timestamp().to_string();
+        self.insert(SECOND_FACTOR_KEY.to_string(), verified_at);
+    }
+
+    pub fn clear_second_factor(&self) {
+        self.remove(SECOND_FACTOR_KEY);
+    }
+
+    /// Returns `true` if the user verified their second factor within the
+    /// last [`SECOND_FACTOR_MAX_AGE_MINUTES`].
+    pub fn has_recent_second_factor(&self) -> bool {
+        let verified_at = self
+            .get(SECOND_FACTOR_KEY)
+            .and_then(|v| v.parse::<i64>().ok());
+        let Some(verified_at) = verified_at else {
+            return false;
+        };
+
+        let age = Utc::now().timestamp() - verified_at;
+        (0..=SECOND_FACTOR_MAX_AGE_MINUTES * 60).contains(&age)
+    }
 }
 
 impl Deref for SessionExtension {
@@ -1,6 +1,7 @@
 //! This module implements middleware to serve static files from the
 //! specified directory.
 
+use crate::app::AppState;
 use axum::middleware::Next;
 use axum::response::Response;
 use http::{Method, Request, StatusCode};
@@ -8,8 +9,13 @@ use std::path::Path;
 use tower::ServiceExt;
 use tower_http::services::ServeDir;
 
-pub async fn serve_local_uploads<B>(request: Request<B>, next: Next<B>) -> Response {
-    serve("local_uploads", request, next).await
+/// Serves the files of the file system storage backend, if there is no CDN
+/// in front of it.
+pub async fn serve_storage<B>(state: AppState, request: Request<B>, next: Next<B>) -> Response {
+    match state.config.storage.served_path() {
+        Some(path) => serve(path, request, next).await,
+        None => next.run(request).await,
+    }
 }
 
 pub async fn serve_dist<B>(request: Request<B>, next: Next<B>) -> Response {
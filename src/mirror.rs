@@ -0,0 +1,266 @@
+//! Pull-through mirror of an upstream registry.
+//!
+//! In mirror mode the server does not accept any changes. Instead, the index
+//! files and crate files of the upstream registry are fetched when they are
+//! requested for the first time and are stored in the regular [`Storage`]
+//! backend, which then serves all subsequent requests. This allows build
+//! environments without internet access to use a local copy of the crates
+//! they need.
+
+use crate::config::MirrorConfig;
+use crate::storage::Storage;
+use anyhow::{anyhow, Context};
+use crates_io_index::Repository;
+use http::StatusCode;
+use hyper::body::Bytes;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Markers of the `dl` template in the `config.json` file of a registry.
+///
+/// see <https://doc.rust-lang.org/cargo/reference/registry-index.html#index-configuration>
+const DL_MARKERS: [&str; 5] = [
+    "{crate}",
+    "{version}",
+    "{prefix}",
+    "{lowerprefix}",
+    "{sha256-checksum}",
+];
+
+pub struct Mirror {
+    upstream_index: String,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct IndexConfig {
+    dl: String,
+}
+
+impl Mirror {
+    pub fn new(config: &MirrorConfig, domain_name: &str) -> Self {
+        let client = Client::builder()
+            .user_agent(format!("{domain_name} (pull-through mirror)"))
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Couldn't build client");
+
+        Self {
+            upstream_index: config.upstream_index.clone(),
+            client,
+        }
+    }
+
+    /// Returns the index file of a crate, fetching it from the upstream
+    /// registry if it is not stored yet.
+    ///
+    /// Returns `None` if the crate does not exist in the upstream registry.
+    #[instrument(skip(self, storage))]
+    pub async fn index_file(&self, storage: &Storage, name: &str) -> anyhow::Result<Option<Bytes>> {
+        match storage.download_index_file(name).await {
+            Ok(content) => Ok(Some(content)),
+            Err(object_store::Error::NotFound { .. }) => self.fetch_index_file(storage, name).await,
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Makes sure that the crate file of a version is stored, fetching it
+    /// from the upstream registry if necessary.
+    ///
+    /// The fetched crate file is verified against the checksum in the index
+    /// file of the crate. Returns `false` if the version does not exist in
+    /// the upstream registry.
+    #[instrument(skip(self, storage))]
+    pub async fn cache_crate_file(
+        &self,
+        storage: &Storage,
+        name: &str,
+        version: &str,
+    ) -> anyhow::Result<bool> {
+        if storage.has_crate_file(name, version).await? {
+            return Ok(true);
+        }
+
+        let mut checksum = match self.index_file(storage, name).await? {
+            Some(content) => find_checksum(&content, version)?,
+            None => return Ok(false),
+        };
+
+        // The stored index file might have been fetched before the version
+        // was published, so the index file is fetched again in that case.
+        if checksum.is_none() {
+            checksum = match self.fetch_index_file(storage, name).await? {
+                Some(content) => find_checksum(&content, version)?,
+                None => None,
+            };
+        }
+
+        let Some(checksum) = checksum else {
+            return Ok(false);
+        };
+
+        let url = self.download_url(name, version, &checksum).await?;
+        let bytes = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        let actual = hex::encode(Sha256::digest(&bytes));
+        if actual != checksum {
+            return Err(anyhow!(
+                "Checksum mismatch of the {name} {version} crate file from {url}: \
+                 expected {checksum}, got {actual}"
+            ));
+        }
+
+        storage.upload_crate_file(name, version, bytes).await?;
+
+        Ok(true)
+    }
+
+    /// Fetches the index file of a crate from the upstream registry and
+    /// stores it, replacing the stored index file, if any.
+    async fn fetch_index_file(
+        &self,
+        storage: &Storage,
+        name: &str,
+    ) -> anyhow::Result<Option<Bytes>> {
+        let path = Repository::relative_index_file_for_url(name);
+        let url = format!("{}{path}", self.upstream_index);
+        let response = self.client.get(&url).send().await?;
+
+        // see <https://doc.rust-lang.org/cargo/reference/registry-index.html#sparse-protocol>
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND
+            || status == StatusCode::GONE
+            || status == StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS
+        {
+            return Ok(None);
+        }
+
+        let content = response.error_for_status()?.text().await?;
+        storage.sync_index(name, Some(content.clone())).await?;
+
+        Ok(Some(content.into()))
+    }
+
+    async fn download_url(
+        &self,
+        name: &str,
+        version: &str,
+        checksum: &str,
+    ) -> anyhow::Result<String> {
+        let url = format!("{}config.json", self.upstream_index);
+        let config: IndexConfig = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to read the index configuration of the upstream registry")?;
+
+        Ok(format_download_url(&config.dl, name, version, checksum))
+    }
+}
+
+/// Returns the checksum of a version from the content of an index file.
+fn find_checksum(content: &[u8], version: &str) -> anyhow::Result<Option<String>> {
+    for line in content.split(|&b| b == b'\n') {
+        if line.is_empty() {
+            continue;
+        }
+
+        let krate: crates_io_index::Crate =
+            serde_json::from_slice(line).context("Failed to parse index file")?;
+
+        if krate.vers == version {
+            return Ok(Some(krate.cksum));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fills in the `dl` template of a registry, as cargo does.
+///
+/// see <https://doc.rust-lang.org/cargo/reference/registry-index.html#index-configuration>
+fn format_download_url(template: &str, name: &str, version: &str, checksum: &str) -> String {
+    if !DL_MARKERS.iter().any(|marker| template.contains(marker)) {
+        let template = template.trim_end_matches('/');
+        return format!("{template}/{name}/{version}/download");
+    }
+
+    let prefix = match name.len() {
+        1 => "1".to_string(),
+        2 => "2".to_string(),
+        3 => format!("3/{}", &name[..1]),
+        _ => format!("{}/{}", &name[..2], &name[2..4]),
+    };
+
+    template
+        .replace("{crate}", name)
+        .replace("{version}", version)
+        .replace("{prefix}", &prefix)
+        .replace("{lowerprefix}", &prefix.to_lowercase())
+        .replace("{sha256-checksum}", checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_download_url() {
+        assert_eq!(
+            format_download_url("https://crates.io/api/v1/crates", "foo", "1.0.0", "abc"),
+            "https://crates.io/api/v1/crates/foo/1.0.0/download"
+        );
+        assert_eq!(
+            format_download_url(
+                "https://static.crates.io/crates/{crate}/{crate}-{version}.crate",
+                "foo",
+                "1.0.0",
+                "abc"
+            ),
+            "https://static.crates.io/crates/foo/foo-1.0.0.crate"
+        );
+        assert_eq!(
+            format_download_url(
+                "https://example.com/{prefix}/{lowerprefix}/{crate}?sha={sha256-checksum}",
+                "Serde",
+                "1.0.0",
+                "abc"
+            ),
+            "https://example.com/Se/rd/se/rd/Serde?sha=abc"
+        );
+        assert_eq!(
+            format_download_url("https://example.com/{prefix}/{crate}", "a", "1.0.0", "abc"),
+            "https://example.com/1/a"
+        );
+    }
+
+    #[test]
+    fn test_find_checksum() {
+        let content = concat!(
+            r#"{"name":"foo","vers":"1.0.0","deps":[],"cksum":"aaa","features":{},"yanked":false}"#,
+            "\n",
+            r#"{"name":"foo","vers":"1.1.0","deps":[],"cksum":"bbb","features":{},"yanked":true}"#,
+            "\n",
+        );
+
+        let checksum = find_checksum(content.as_bytes(), "1.1.0").unwrap();
+        assert_eq!(checksum.as_deref(), Some("bbb"));
+
+        let checksum = find_checksum(content.as_bytes(), "2.0.0").unwrap();
+        assert_eq!(checksum, None);
+
+        assert!(find_checksum(b"invalid", "1.0.0").is_err());
+    }
+}
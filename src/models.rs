@@ -1,33 +1,60 @@
-pub use self::action::{insert_version_owner_action, VersionAction, VersionOwnerAction};
+pub use self::action::{
+    insert_crate_audit_action, insert_token_audit_actions, insert_version_owner_action,
+    CrateAction, CrateAuditAction, VersionAction, VersionOwnerAction, YankCategory, YankReason,
+};
+pub use self::advisory::{Advisory, NewAdvisory, VersionAdvisory};
+pub use self::asymmetric_key::{AsymmetricKey, NewAsymmetricKey};
 pub use self::category::{Category, CrateCategory, NewCategory};
+pub use self::crate_name_prefix::{CrateNamePrefix, NewCrateNamePrefix};
 pub use self::crate_owner_invitation::{CrateOwnerInvitation, NewCrateOwnerInvitationOutcome};
 pub use self::dependency::{Dependency, DependencyKind, ReverseDependency};
 pub use self::download::VersionDownload;
 pub use self::email::{Email, NewEmail};
+pub use self::feed_token::FeedToken;
+pub use self::file_listing::VersionFileListing;
 pub use self::follow::Follow;
 pub use self::keyword::{CrateKeyword, Keyword};
-pub use self::krate::{Crate, CrateVersions, NewCrate, RecentCrateDownloads};
+pub use self::krate::{Crate, CrateVersions, DeprecationState, NewCrate, RecentCrateDownloads};
 pub use self::owner::{CrateOwner, Owner, OwnerKind};
+pub use self::publish::{NewPublish, Publish, PublishStatus};
 pub use self::rights::Rights;
+pub use self::signing_key::{NewSigningKey, SigningKey, VersionSignature};
+pub use self::storage_issue::{NewStorageIssue, StorageIssue, StorageIssueKind};
 pub use self::team::{NewTeam, Team};
-pub use self::token::{ApiToken, CreatedApiToken};
+pub use self::token::{ApiToken, ApiTokenUsage, CreatedApiToken, NewApiTokenUsage};
+pub use self::totp_secret::TotpSecret;
+pub use self::trusted_publisher::{NewTrustedPublisher, TrustedPublisher};
 pub use self::user::{NewUser, User};
 pub use self::version::{NewVersion, TopVersions, Version};
+pub use self::webauthn_credential::{NewWebauthnCredential, WebauthnCredential};
+pub use self::webhook::{NewWebhook, Webhook, WebhookDelivery, WebhookEvent};
 
 pub mod helpers;
 
 mod action;
+mod advisory;
+mod asymmetric_key;
 pub mod category;
+mod crate_name_prefix;
 mod crate_owner_invitation;
 pub mod dependency;
 mod download;
 mod email;
+mod feed_token;
+mod file_listing;
 mod follow;
 mod keyword;
 pub mod krate;
 mod owner;
+mod publish;
 mod rights;
+mod signing_key;
+mod storage_issue;
 mod team;
 pub mod token;
+mod totp_secret;
+mod trusted_publisher;
 pub mod user;
 pub mod version;
+mod webauthn_credential;
+mod webhook;
@@ -1,8 +1,10 @@
-use crate::models::{ApiToken, User, Version};
+use crate::models::{ApiToken, OwnerKind, User, Version};
 use crate::schema::*;
 use crate::sql::pg_enum;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
 
 pg_enum! {
     pub enum VersionAction {
@@ -30,7 +32,45 @@ impl From<VersionAction> for String {
     }
 }
 
-#[derive(Debug, Clone, Copy, Queryable, Identifiable, Associations)]
+pg_enum! {
+    pub enum YankCategory {
+        Other = 0,
+        Security = 1,
+        BrokenBuild = 2,
+        Mistake = 3,
+    }
+}
+
+impl From<YankCategory> for &'static str {
+    fn from(category: YankCategory) -> Self {
+        match category {
+            YankCategory::Other => "other",
+            YankCategory::Security => "security",
+            YankCategory::BrokenBuild => "broken_build",
+            YankCategory::Mistake => "mistake",
+        }
+    }
+}
+
+/// The optional reason that can be given when yanking a version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct YankReason {
+    pub category: YankCategory,
+    pub message: Option<String>,
+}
+
+impl From<YankReason> for crates_io_index::YankReason {
+    fn from(reason: YankReason) -> Self {
+        let category: &'static str = reason.category.into();
+
+        Self {
+            category: category.into(),
+            message: reason.message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Associations)]
 #[diesel(
     table_name = version_owner_actions,
     check_for_backend(diesel::pg::Pg),
@@ -45,9 +85,47 @@ pub struct VersionOwnerAction {
     pub api_token_id: Option<i32>,
     pub action: VersionAction,
     pub time: NaiveDateTime,
+    pub yank_category: Option<YankCategory>,
+    pub yank_message: Option<String>,
 }
 
 impl VersionOwnerAction {
+    /// Returns the reason that was given for a `Yank` action, if any.
+    pub fn yank_reason(&self) -> Option<YankReason> {
+        match (self.action, self.yank_category) {
+            (VersionAction::Yank, Some(category)) => Some(YankReason {
+                category,
+                message: self.yank_message.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the reasons of the most recent `Yank` actions of the given
+    /// versions, keyed by version id.
+    ///
+    /// Versions without a yank reason are not part of the returned map.
+    pub fn latest_yank_reasons(
+        conn: &mut PgConnection,
+        version_ids: &[i32],
+    ) -> QueryResult<HashMap<i32, YankReason>> {
+        let actions: Vec<Self> = version_owner_actions::table
+            .filter(version_owner_actions::version_id.eq_any(version_ids))
+            .filter(version_owner_actions::action.eq(VersionAction::Yank))
+            .order(version_owner_actions::id)
+            .load(conn)?;
+
+        let mut reasons = HashMap::new();
+        for action in actions {
+            match action.yank_reason() {
+                Some(reason) => reasons.insert(action.version_id, reason),
+                None => reasons.remove(&action.version_id),
+            };
+        }
+
+        Ok(reasons)
+    }
+
     pub fn all(conn: &mut PgConnection) -> QueryResult<Vec<Self>> {
         version_owner_actions::table.load(conn)
     }
@@ -83,8 +161,11 @@ pub fn insert_version_owner_action(
     user_id_: i32,
     api_token_id_: Option<i32>,
     action_: VersionAction,
+    yank_reason: Option<&YankReason>,
 ) -> QueryResult<VersionOwnerAction> {
-    use version_owner_actions::dsl::{action, api_token_id, user_id, version_id};
+    use version_owner_actions::dsl::{
+        action, api_token_id, user_id, version_id, yank_category, yank_message,
+    };
 
     diesel::insert_into(version_owner_actions::table)
         .values((
@@ -92,6 +173,152 @@ pub fn insert_version_owner_action(
             user_id.eq(user_id_),
             api_token_id.eq(api_token_id_),
             action.eq(action_),
+            yank_category.eq(yank_reason.map(|reason| reason.category)),
+            yank_message.eq(yank_reason.and_then(|reason| reason.message.as_deref())),
         ))
         .get_result(conn)
 }
+
+pg_enum! {
+    pub enum CrateAction {
+        InviteOwner = 0,
+        AcceptInvitation = 1,
+        DeclineInvitation = 2,
+        AddTeam = 3,
+        RemoveOwner = 4,
+        CreateToken = 5,
+        RevokeToken = 6,
+        AddTrustedPublisher = 7,
+        RemoveTrustedPublisher = 8,
+        AdminTransfer = 9,
+        AdminDeleteVersion = 10,
+        AdminDeleteCrate = 11,
+        Deprecate = 12,
+        Undeprecate = 13,
+        AddWebhook = 14,
+        RemoveWebhook = 15,
+        ExpireToken = 16,
+    }
+}
+
+impl From<CrateAction> for &'static str {
+    fn from(action: CrateAction) -> Self {
+        match action {
+            CrateAction::InviteOwner => "invite_owner",
+            CrateAction::AcceptInvitation => "accept_invitation",
+            CrateAction::DeclineInvitation => "decline_invitation",
+            CrateAction::AddTeam => "add_team",
+            CrateAction::RemoveOwner => "remove_owner",
+            CrateAction::CreateToken => "create_token",
+            CrateAction::RevokeToken => "revoke_token",
+            CrateAction::AddTrustedPublisher => "add_trusted_publisher",
+            CrateAction::RemoveTrustedPublisher => "remove_trusted_publisher",
+            CrateAction::AdminTransfer => "admin_transfer",
+            CrateAction::AdminDeleteVersion => "admin_delete_version",
+            CrateAction::AdminDeleteCrate => "admin_delete_crate",
+            CrateAction::Deprecate => "deprecate",
+            CrateAction::Undeprecate => "undeprecate",
+            CrateAction::AddWebhook => "add_webhook",
+            CrateAction::RemoveWebhook => "remove_webhook",
+            CrateAction::ExpireToken => "expire_token",
+        }
+    }
+}
+
+impl From<CrateAction> for String {
+    fn from(action: CrateAction) -> Self {
+        let string: &'static str = action.into();
+
+        string.into()
+    }
+}
+
+/// An entry of the crate level audit log.
+///
+/// The entries reference the crate by name instead of by id, so that the
+/// `AdminDeleteCrate` entries (and everything before them) are still
+/// available after the crate has been deleted.
+#[derive(Debug, Clone, Queryable, Identifiable, Selectable)]
+#[diesel(table_name = crate_audit_actions, check_for_backend(diesel::pg::Pg))]
+pub struct CrateAuditAction {
+    pub id: i32,
+    pub crate_name: String,
+    pub user_id: Option<i32>,
+    pub api_token_id: Option<i32>,
+    pub action: CrateAction,
+    pub details: Value,
+    pub time: NaiveDateTime,
+}
+
+impl CrateAuditAction {
+    /// Returns the audit log of the crate with the given name, newest entries
+    /// first, together with the user that performed each action.
+    pub fn by_crate_name(
+        conn: &mut PgConnection,
+        crate_name: &str,
+    ) -> QueryResult<Vec<(Self, Option<User>)>> {
+        crate_audit_actions::table
+            .filter(crate_audit_actions::crate_name.eq(crate_name))
+            .left_join(users::table)
+            .select((Self::as_select(), users::all_columns.nullable()))
+            .order(crate_audit_actions::id.desc())
+            .load(conn)
+    }
+}
+
+pub fn insert_crate_audit_action(
+    conn: &mut PgConnection,
+    crate_name: &str,
+    user_id: Option<i32>,
+    api_token_id: Option<i32>,
+    action: CrateAction,
+    details: Value,
+) -> QueryResult<()> {
+    diesel::insert_into(crate_audit_actions::table)
+        .values((
+            crate_audit_actions::crate_name.eq(crate_name),
+            crate_audit_actions::user_id.eq(user_id),
+            crate_audit_actions::api_token_id.eq(api_token_id),
+            crate_audit_actions::action.eq(action),
+            crate_audit_actions::details.eq(details),
+        ))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Records the creation or revocation of an API token in the audit log of
+/// every crate that the token can be used for.
+///
+/// These are all crates that are directly owned by the user of the token
+/// and that match the crate scopes of the token, if it has any.
+pub fn insert_token_audit_actions(
+    conn: &mut PgConnection,
+    token: &ApiToken,
+    api_token_id: Option<i32>,
+    action: CrateAction,
+) -> QueryResult<()> {
+    let crate_names: Vec<String> = crate_owners::table
+        .inner_join(crates::table)
+        .filter(crate_owners::owner_id.eq(token.user_id))
+        .filter(crate_owners::owner_kind.eq(OwnerKind::User))
+        .filter(crate_owners::deleted.eq(false))
+        .select(crates::name)
+        .load(conn)?;
+
+    let details = serde_json::json!({ "token_id": token.id, "token_name": token.name });
+
+    for crate_name in crate_names {
+        let in_scope = token.crate_scopes.as_ref().map_or(true, |scopes| {
+            scopes.iter().any(|scope| scope.matches(&crate_name))
+        });
+
+        if in_scope {
+            let user_id = Some(token.user_id);
+            let details = details.clone();
+            insert_crate_audit_action(conn, &crate_name, user_id, api_token_id, action, details)?;
+        }
+    }
+
+    Ok(())
+}
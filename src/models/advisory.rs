@@ -0,0 +1,161 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use semver::VersionReq;
+
+use crate::models::{Crate, Version};
+use crate::schema::{advisories, version_advisories};
+
+/// The model representing a row in the `advisories` database table.
+///
+/// Advisories are imported from a RustSec-format advisory database by the
+/// `ImportAdvisories` background job.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = advisories,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Crate),
+)]
+pub struct Advisory {
+    pub id: String,
+    pub crate_id: i32,
+    pub title: String,
+    pub description: String,
+    pub date: NaiveDate,
+    pub url: Option<String>,
+    pub aliases: Vec<String>,
+    pub informational: Option<String>,
+    pub patched: Vec<String>,
+    pub unaffected: Vec<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Advisory {
+    /// Checks whether the given version is affected by this advisory, which
+    /// is the case if neither the `patched` nor the `unaffected` version
+    /// requirements match it.
+    ///
+    /// Requirements that can not be parsed are ignored.
+    pub fn affects(&self, version: &semver::Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .filter_map(|req| VersionReq::parse(req).ok())
+            .any(|req| req.matches(version))
+    }
+}
+
+#[derive(Insertable, AsChangeset, Debug)]
+#[diesel(table_name = advisories, check_for_backend(diesel::pg::Pg))]
+pub struct NewAdvisory<'a> {
+    pub id: &'a str,
+    pub crate_id: i32,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub date: NaiveDate,
+    pub url: Option<&'a str>,
+    pub aliases: &'a [String],
+    pub informational: Option<&'a str>,
+    pub patched: &'a [String],
+    pub unaffected: &'a [String],
+}
+
+impl NewAdvisory<'_> {
+    /// Inserts the advisory, or updates it if an advisory with the same id
+    /// already exists.
+    pub fn upsert(&self, conn: &mut PgConnection) -> QueryResult<Advisory> {
+        diesel::insert_into(advisories::table)
+            .values(self)
+            .on_conflict(advisories::id)
+            .do_update()
+            .set((self, advisories::updated_at.eq(diesel::dsl::now)))
+            .returning(Advisory::as_returning())
+            .get_result(conn)
+    }
+}
+
+/// The model representing a row in the `version_advisories` database table.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Insertable, Associations)]
+#[diesel(
+    table_name = version_advisories,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(version_id, advisory_id),
+    belongs_to(Version),
+    belongs_to(Advisory),
+)]
+pub struct VersionAdvisory {
+    pub version_id: i32,
+    pub advisory_id: String,
+}
+
+impl VersionAdvisory {
+    /// Returns the ids of the advisories affecting the given version.
+    pub fn by_version(conn: &mut PgConnection, version: &Version) -> QueryResult<Vec<String>> {
+        VersionAdvisory::belonging_to(version)
+            .select(version_advisories::advisory_id)
+            .order(version_advisories::advisory_id)
+            .load(conn)
+    }
+
+    /// Returns the ids of the advisories affecting each of the given
+    /// versions, in the same order as the versions.
+    pub fn for_versions(
+        conn: &mut PgConnection,
+        versions: &[Version],
+    ) -> QueryResult<Vec<Vec<String>>> {
+        let advisories = VersionAdvisory::belonging_to(versions)
+            .select(VersionAdvisory::as_select())
+            .order(version_advisories::advisory_id)
+            .load(conn)?
+            .grouped_by(versions)
+            .into_iter()
+            .map(|advisories| advisories.into_iter().map(|a| a.advisory_id).collect())
+            .collect();
+
+        Ok(advisories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(patched: &[&str], unaffected: &[&str]) -> Advisory {
+        Advisory {
+            id: "RUSTSEC-2023-0001".into(),
+            crate_id: 1,
+            title: String::new(),
+            description: String::new(),
+            date: NaiveDate::default(),
+            url: None,
+            aliases: vec![],
+            informational: None,
+            patched: patched.iter().map(|s| s.to_string()).collect(),
+            unaffected: unaffected.iter().map(|s| s.to_string()).collect(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn affects() {
+        let version = |v: &str| semver::Version::parse(v).unwrap();
+
+        let a = advisory(&[">= 1.2.3, < 2.0.0", ">= 2.0.1"], &["< 1.0.0"]);
+        assert!(!a.affects(&version("0.9.0")));
+        assert!(a.affects(&version("1.0.0")));
+        assert!(a.affects(&version("1.2.2")));
+        assert!(!a.affects(&version("1.2.3")));
+        assert!(a.affects(&version("2.0.0")));
+        assert!(!a.affects(&version("2.0.1")));
+
+        // Advisories without any patched versions affect all versions
+        let a = advisory(&[], &[]);
+        assert!(a.affects(&version("0.1.0")));
+        assert!(a.affects(&version("99.0.0")));
+
+        // Invalid requirements are ignored
+        let a = advisory(&["not a requirement", ">= 1.0.0"], &[]);
+        assert!(a.affects(&version("0.1.0")));
+        assert!(!a.affects(&version("1.0.0")));
+    }
+}
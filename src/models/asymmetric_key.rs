@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::now;
+use diesel::prelude::*;
+
+use crate::models::User;
+use crate::schema::asymmetric_keys;
+use crate::util::asymmetric_token::PublicKey;
+
+/// The model representing a row in the `asymmetric_keys` database table.
+///
+/// Users can register ECDSA P-384 public keys and then authenticate cargo
+/// requests with PASETO tokens that are signed by the `cargo:paseto`
+/// credential provider, instead of sending an API token.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations, Serialize)]
+#[diesel(
+    table_name = asymmetric_keys,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User),
+)]
+pub struct AsymmetricKey {
+    pub id: i32,
+    #[serde(skip)]
+    pub user_id: i32,
+    pub name: String,
+    pub public_key: String,
+    pub key_id: String,
+    #[serde(with = "crate::util::rfc3339")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::util::rfc3339::option")]
+    pub last_used_at: Option<NaiveDateTime>,
+    #[serde(with = "crate::util::rfc3339::option")]
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl AsymmetricKey {
+    /// Finds the non-revoked key with the given PASERK ID.
+    pub fn find_active(conn: &mut PgConnection, key_id: &str) -> QueryResult<Option<Self>> {
+        asymmetric_keys::table
+            .filter(asymmetric_keys::key_id.eq(key_id))
+            .filter(asymmetric_keys::revoked_at.is_null())
+            .select(AsymmetricKey::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Records that the key was used to authenticate a request.
+    pub fn update_last_used_at(&self, conn: &mut PgConnection) {
+        // If the database is in read only mode, we can't update last_used_at.
+        // Try updating in a new transaction, and ignore the error if that fails.
+        let _ = conn.transaction(|conn| {
+            diesel::update(self)
+                .set(asymmetric_keys::last_used_at.eq(now.nullable()))
+                .execute(conn)
+        });
+    }
+
+    pub fn public_key(&self) -> Option<PublicKey> {
+        PublicKey::from_paserk(&self.public_key)
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = asymmetric_keys, check_for_backend(diesel::pg::Pg))]
+pub struct NewAsymmetricKey {
+    pub user_id: i32,
+    pub name: String,
+    pub public_key: String,
+    pub key_id: String,
+}
+
+impl NewAsymmetricKey {
+    /// Parses the given public key in its PASERK `k3.public.` encoding and
+    /// returns the key that can be inserted into the database, or `None` if
+    /// the public key is invalid.
+    pub fn parse(user_id: i32, name: String, public_key: &str) -> Option<Self> {
+        let public_key = PublicKey::from_paserk(public_key.trim())?;
+
+        Some(Self {
+            user_id,
+            name,
+            public_key: public_key.to_paserk(),
+            key_id: public_key.key_id(),
+        })
+    }
+
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<AsymmetricKey> {
+        diesel::insert_into(asymmetric_keys::table)
+            .values(self)
+            .returning(AsymmetricKey::as_returning())
+            .get_result(conn)
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::models::{Crate, User};
+use crate::schema::{crate_name_prefix_owners, crate_name_prefixes, users};
+use crate::sql::canon_crate_name;
+
+/// The model representing a row in the `crate_name_prefixes` database table.
+///
+/// A claimed prefix reserves the crate name `<prefix>` and all crate names
+/// starting with `<prefix>-` (or `<prefix>_`) for its owners. Crates that
+/// already existed when the prefix was claimed keep their current owners.
+///
+/// Since crate names can not contain `::`, the hyphen is the only supported
+/// namespace separator.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable)]
+#[diesel(table_name = crate_name_prefixes, check_for_backend(diesel::pg::Pg))]
+pub struct CrateNamePrefix {
+    pub id: i32,
+    pub prefix: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl CrateNamePrefix {
+    /// Checks whether the given string can be claimed as a prefix, which is
+    /// the case if it is a valid crate name that does not end with a
+    /// separator.
+    pub fn valid_prefix(prefix: &str) -> bool {
+        Crate::valid_name(prefix) && !prefix.ends_with(['-', '_'])
+    }
+
+    /// Finds the claimed prefix with the given name, treating `-` and `_` as
+    /// interchangeable.
+    pub fn find_by_prefix(conn: &mut PgConnection, prefix: &str) -> QueryResult<Self> {
+        crate_name_prefixes::table
+            .filter(canon_crate_name(crate_name_prefixes::prefix).eq(canon_crate_name(prefix)))
+            .select(CrateNamePrefix::as_select())
+            .first(conn)
+    }
+
+    /// Finds the most specific claimed prefix that covers the given crate
+    /// name, if there is any.
+    ///
+    /// For example, if both `foo` and `foo-bar` are claimed, the crate
+    /// `foo-bar-baz` is covered by `foo-bar`.
+    pub fn find_for_crate_name(conn: &mut PgConnection, name: &str) -> QueryResult<Option<Self>> {
+        let candidates = candidate_prefixes(name);
+
+        let prefixes = crate_name_prefixes::table
+            .filter(canon_crate_name(crate_name_prefixes::prefix).eq_any(&candidates))
+            .select(CrateNamePrefix::as_select())
+            .load(conn)?;
+
+        Ok(prefixes
+            .into_iter()
+            .max_by_key(|prefix| prefix.prefix.len()))
+    }
+
+    /// Returns the users that own this prefix, ordered by login.
+    pub fn owners(&self, conn: &mut PgConnection) -> QueryResult<Vec<User>> {
+        crate_name_prefix_owners::table
+            .inner_join(users::table)
+            .filter(crate_name_prefix_owners::prefix_id.eq(self.id))
+            .select(users::all_columns)
+            .order(users::gh_login)
+            .load(conn)
+    }
+
+    /// Checks whether the given user is one of the owners of this prefix.
+    pub fn is_owned_by(&self, conn: &mut PgConnection, user_id: i32) -> QueryResult<bool> {
+        let query = crate_name_prefix_owners::table
+            .filter(crate_name_prefix_owners::prefix_id.eq(self.id))
+            .filter(crate_name_prefix_owners::user_id.eq(user_id));
+
+        diesel::select(diesel::dsl::exists(query)).get_result(conn)
+    }
+
+    /// Adds the given user as an owner of this prefix. Adding an existing
+    /// owner again is a no-op.
+    pub fn add_owner(&self, conn: &mut PgConnection, user_id: i32) -> QueryResult<()> {
+        diesel::insert_into(crate_name_prefix_owners::table)
+            .values((
+                crate_name_prefix_owners::prefix_id.eq(self.id),
+                crate_name_prefix_owners::user_id.eq(user_id),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    /// Removes the given user from the owners of this prefix.
+    pub fn remove_owner(&self, conn: &mut PgConnection, user_id: i32) -> QueryResult<()> {
+        diesel::delete(crate_name_prefix_owners::table)
+            .filter(crate_name_prefix_owners::prefix_id.eq(self.id))
+            .filter(crate_name_prefix_owners::user_id.eq(user_id))
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate_name_prefixes, check_for_backend(diesel::pg::Pg))]
+pub struct NewCrateNamePrefix<'a> {
+    pub prefix: &'a str,
+}
+
+impl NewCrateNamePrefix<'_> {
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<CrateNamePrefix> {
+        diesel::insert_into(crate_name_prefixes::table)
+            .values(self)
+            .returning(CrateNamePrefix::as_returning())
+            .get_result(conn)
+    }
+}
+
+/// Returns the canonical forms (see `canon_crate_name()`) of all prefixes
+/// that could cover the given crate name, i.e. the name itself and
+/// everything in front of each of its separators.
+fn candidate_prefixes(name: &str) -> Vec<String> {
+    let name = name.to_lowercase().replace('-', "_");
+
+    name.match_indices('_')
+        .map(|(index, _)| &name[..index])
+        .chain([name.as_str()])
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_prefix() {
+        assert!(CrateNamePrefix::valid_prefix("mycompany"));
+        assert!(CrateNamePrefix::valid_prefix("my-company"));
+        assert!(!CrateNamePrefix::valid_prefix("mycompany-"));
+        assert!(!CrateNamePrefix::valid_prefix("mycompany_"));
+        assert!(!CrateNamePrefix::valid_prefix("mycompany::"));
+        assert!(!CrateNamePrefix::valid_prefix(""));
+    }
+
+    #[test]
+    fn candidates() {
+        assert_eq!(candidate_prefixes("foo"), ["foo"]);
+        assert_eq!(
+            candidate_prefixes("Foo_bar-baz"),
+            ["foo", "foo_bar", "foo_bar_baz"]
+        );
+        assert_eq!(candidate_prefixes("foo--bar"), ["foo", "foo_", "foo__bar"]);
+    }
+}
@@ -2,9 +2,10 @@ use chrono::{NaiveDateTime, Utc};
 use diesel::prelude::*;
 
 use crate::config;
-use crate::models::{CrateOwner, OwnerKind};
-use crate::schema::{crate_owner_invitations, crate_owners, crates};
+use crate::models::{insert_crate_audit_action, CrateAction, CrateOwner, OwnerKind, WebhookEvent};
+use crate::schema::{crate_owner_invitations, crate_owners, crates, users};
 use crate::util::errors::{AppResult, OwnershipInvitationExpired};
+use crate::worker::jobs;
 
 #[derive(Debug)]
 pub enum NewCrateOwnerInvitationOutcome {
@@ -118,6 +119,16 @@ impl CrateOwnerInvitation {
 
             diesel::delete(&self).execute(conn)?;
 
+            self.insert_audit_action(conn, CrateAction::AcceptInvitation)?;
+
+            let login: String = users::table
+                .find(self.invited_user_id)
+                .select(users::gh_login)
+                .first(conn)?;
+
+            let details = serde_json::json!({ "action": "added", "owner": login, "actor": login });
+            jobs::enqueue_webhooks(conn, self.crate_id, WebhookEvent::OwnerChange, details)?;
+
             Ok(())
         })
     }
@@ -128,6 +139,25 @@ impl CrateOwnerInvitation {
         // database.
 
         diesel::delete(&self).execute(conn)?;
+        self.insert_audit_action(conn, CrateAction::DeclineInvitation)?;
+        Ok(())
+    }
+
+    fn insert_audit_action(&self, conn: &mut PgConnection, action: CrateAction) -> AppResult<()> {
+        let crate_name: String = crates::table
+            .find(self.crate_id)
+            .select(crates::name)
+            .first(conn)?;
+
+        let invited_by: String = users::table
+            .find(self.invited_by_user_id)
+            .select(users::gh_login)
+            .first(conn)?;
+
+        let details = serde_json::json!({ "invited_by": invited_by });
+        let user_id = Some(self.invited_user_id);
+        insert_crate_audit_action(conn, &crate_name, user_id, None, action, details)?;
+
         Ok(())
     }
 
@@ -0,0 +1,69 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::models::User;
+use crate::schema::{feed_tokens, users};
+use crate::util::token::{HashedToken, PlainToken};
+
+/// The model representing a row in the `feed_tokens` database table.
+///
+/// Feed readers can not log in, so the feed of new releases of the crates
+/// that a user follows is authenticated by a token in the feed URL instead.
+/// Every user has at most one feed token.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations, Serialize)]
+#[diesel(
+    table_name = feed_tokens,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(user_id),
+    belongs_to(User),
+)]
+pub struct FeedToken {
+    #[serde(skip)]
+    pub user_id: i32,
+    #[serde(with = "crate::util::rfc3339")]
+    pub created_at: NaiveDateTime,
+}
+
+impl FeedToken {
+    /// Creates a new feed token for the user, replacing the previous one, if
+    /// any. The returned plaintext token is not stored anywhere.
+    pub fn regenerate(conn: &mut PgConnection, user_id: i32) -> QueryResult<(Self, PlainToken)> {
+        let plaintext = PlainToken::generate();
+
+        let token = diesel::insert_into(feed_tokens::table)
+            .values((
+                feed_tokens::user_id.eq(user_id),
+                feed_tokens::token.eq(plaintext.hashed()),
+            ))
+            .on_conflict(feed_tokens::user_id)
+            .do_update()
+            .set((
+                feed_tokens::token.eq(plaintext.hashed()),
+                feed_tokens::created_at.eq(diesel::dsl::now),
+            ))
+            .returning(FeedToken::as_returning())
+            .get_result(conn)?;
+
+        Ok((token, plaintext))
+    }
+
+    /// Deletes the feed token of the user, returning whether there was one.
+    pub fn revoke(conn: &mut PgConnection, user_id: i32) -> QueryResult<bool> {
+        let deleted = diesel::delete(feed_tokens::table.find(user_id)).execute(conn)?;
+        Ok(deleted > 0)
+    }
+
+    /// Finds the user that the plaintext feed token belongs to.
+    pub fn find_user(conn: &mut PgConnection, plaintext: &str) -> QueryResult<Option<User>> {
+        let Some(token) = HashedToken::parse(plaintext) else {
+            return Ok(None);
+        };
+
+        feed_tokens::table
+            .inner_join(users::table)
+            .filter(feed_tokens::token.eq(token))
+            .select(users::all_columns)
+            .first(conn)
+            .optional()
+    }
+}
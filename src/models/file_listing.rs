@@ -0,0 +1,52 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::models::Version;
+use crate::schema::version_file_listings;
+
+/// The model representing a row in the `version_file_listings` database
+/// table.
+///
+/// The listings are created by the `list_crate_files` background job after
+/// the crate file of a new version has been uploaded.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = version_file_listings,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(version_id),
+    belongs_to(Version),
+)]
+pub struct VersionFileListing {
+    pub version_id: i32,
+    pub files: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+impl VersionFileListing {
+    pub fn find(conn: &mut PgConnection, version_id: i32) -> QueryResult<Option<Self>> {
+        version_file_listings::table
+            .find(version_id)
+            .select(VersionFileListing::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Inserts the file listing of a version, replacing any existing listing.
+    pub fn upsert(
+        conn: &mut PgConnection,
+        version_id: i32,
+        files: &serde_json::Value,
+    ) -> QueryResult<()> {
+        diesel::insert_into(version_file_listings::table)
+            .values((
+                version_file_listings::version_id.eq(version_id),
+                version_file_listings::files.eq(files),
+            ))
+            .on_conflict(version_file_listings::version_id)
+            .do_update()
+            .set(version_file_listings::files.eq(files))
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
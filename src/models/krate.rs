@@ -11,13 +11,13 @@ use crate::controllers::helpers::pagination::*;
 use crate::models::version::TopVersions;
 use crate::models::{
     CrateOwner, CrateOwnerInvitation, Dependency, NewCrateOwnerInvitationOutcome, Owner, OwnerKind,
-    ReverseDependency, User, Version,
+    ReverseDependency, User, Version, VersionOwnerAction,
 };
 use crate::util::errors::{cargo_err, AppResult};
 
 use crate::models::helpers::with_count::*;
 use crate::schema::*;
-use crate::sql::canon_crate_name;
+use crate::sql::{canon_crate_name, pg_enum};
 
 #[derive(Debug, Queryable, Identifiable, Associations, Clone, Copy)]
 #[diesel(
@@ -31,6 +31,13 @@ pub struct RecentCrateDownloads {
     pub downloads: i32,
 }
 
+pg_enum! {
+    pub enum DeprecationState {
+        Deprecated = 0,
+        Unmaintained = 1,
+    }
+}
+
 #[derive(Debug, Clone, Queryable, Identifiable, AsChangeset, QueryableByName, Selectable)]
 #[diesel(table_name = crates, check_for_backend(diesel::pg::Pg))]
 pub struct Crate {
@@ -45,6 +52,9 @@ pub struct Crate {
     pub repository: Option<String>,
     pub max_upload_size: Option<i32>,
     pub max_features: Option<i16>,
+    pub deprecation: Option<DeprecationState>,
+    pub deprecation_message: Option<String>,
+    pub successor: Option<String>,
 }
 
 /// We literally never want to select `textsearchable_index_col`
@@ -61,6 +71,9 @@ type AllColumns = (
     crates::repository,
     crates::max_upload_size,
     crates::max_features,
+    crates::deprecation,
+    crates::deprecation_message,
+    crates::successor,
 );
 
 pub const ALL_COLUMNS: AllColumns = (
@@ -75,6 +88,9 @@ pub const ALL_COLUMNS: AllColumns = (
     crates::repository,
     crates::max_upload_size,
     crates::max_features,
+    crates::deprecation,
+    crates::deprecation_message,
+    crates::successor,
 );
 
 pub const MAX_NAME_LENGTH: usize = 64;
@@ -391,6 +407,13 @@ impl Crate {
 
         let deps = deps.grouped_by(&versions);
 
+        let yanked_version_ids = versions
+            .iter()
+            .filter(|v| v.yanked)
+            .map(|v| v.id)
+            .collect::<Vec<_>>();
+        let mut yank_reasons = VersionOwnerAction::latest_yank_reasons(conn, &yanked_version_ids)?;
+
         versions
             .into_iter()
             .zip(deps)
@@ -438,6 +461,8 @@ impl Crate {
                     (Some(features2), Some(2))
                 };
 
+                let yank_reason = yank_reasons.remove(&version.id).map(Into::into);
+
                 let krate = crates_io_index::Crate {
                     name: self.name.clone(),
                     vers: version.num.to_string(),
@@ -447,6 +472,7 @@ impl Crate {
                     features,
                     links: version.links,
                     rust_version: version.rust_version,
+                    yank_reason,
                     features2,
                     v,
                 };
@@ -0,0 +1,110 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::models::{ApiToken, User, Version};
+use crate::schema::publishes;
+use crate::sql::pg_enum;
+
+pg_enum! {
+    pub enum PublishStatus {
+        Queued = 0,
+        Processing = 1,
+        Succeeded = 2,
+        Failed = 3,
+    }
+}
+
+/// The model representing a row in the `publishes` database table.
+///
+/// The uploaded tarball is intentionally not part of this struct, since it
+/// can be rather large and is only needed by the `ProcessPublish` background
+/// job. Use [Publish::tarball] to load it.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = publishes,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Version),
+    belongs_to(User),
+    belongs_to(ApiToken),
+)]
+pub struct Publish {
+    pub id: i32,
+    pub version_id: i32,
+    pub user_id: i32,
+    pub api_token_id: Option<i32>,
+    pub status: PublishStatus,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Publish {
+    pub fn find(conn: &mut PgConnection, id: i32) -> QueryResult<Self> {
+        publishes::table
+            .find(id)
+            .select(Self::as_select())
+            .first(conn)
+    }
+
+    /// Loads the uploaded tarball of this publish, unless it has already been
+    /// cleared by [Publish::mark_succeeded].
+    pub fn tarball(&self, conn: &mut PgConnection) -> QueryResult<Option<Vec<u8>>> {
+        publishes::table
+            .find(self.id)
+            .select(publishes::tarball)
+            .first(conn)
+    }
+
+    pub fn mark_processing(&self, conn: &mut PgConnection) -> QueryResult<()> {
+        diesel::update(self)
+            .set((
+                publishes::status.eq(PublishStatus::Processing),
+                publishes::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Marks the publish as succeeded and clears the stored tarball, since it
+    /// is now available from the storage backend.
+    pub fn mark_succeeded(&self, conn: &mut PgConnection) -> QueryResult<()> {
+        diesel::update(self)
+            .set((
+                publishes::status.eq(PublishStatus::Succeeded),
+                publishes::error.eq(None::<String>),
+                publishes::tarball.eq(None::<Vec<u8>>),
+                publishes::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn mark_failed(&self, error: &str, conn: &mut PgConnection) -> QueryResult<()> {
+        diesel::update(self)
+            .set((
+                publishes::status.eq(PublishStatus::Failed),
+                publishes::error.eq(error),
+                publishes::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = publishes, check_for_backend(diesel::pg::Pg))]
+pub struct NewPublish<'a> {
+    pub version_id: i32,
+    pub user_id: i32,
+    pub api_token_id: Option<i32>,
+    pub tarball: &'a [u8],
+}
+
+impl NewPublish<'_> {
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<Publish> {
+        diesel::insert_into(publishes::table)
+            .values(self)
+            .returning(Publish::as_returning())
+            .get_result(conn)
+    }
+}
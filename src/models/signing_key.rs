@@ -0,0 +1,196 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use hex::ToHex;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::EncodePublicKey;
+use p256::PublicKey;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+use crate::models::{User, Version};
+use crate::schema::{signing_keys, version_signatures};
+
+/// The model representing a row in the `signing_keys` database table.
+///
+/// Users can register ECDSA P-256 public keys and then attach a detached
+/// signature of the crate file when publishing a new version. The signature
+/// is verified against the uploaded crate file before the version is
+/// accepted.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations, Serialize)]
+#[diesel(
+    table_name = signing_keys,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User),
+)]
+pub struct SigningKey {
+    pub id: i32,
+    #[serde(skip)]
+    pub user_id: i32,
+    pub name: String,
+    pub public_key: String,
+    pub fingerprint: String,
+    #[serde(with = "crate::util::rfc3339")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::util::rfc3339::option")]
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl SigningKey {
+    /// Finds the non-revoked key of the given user with the given
+    /// fingerprint.
+    pub fn find_active(
+        conn: &mut PgConnection,
+        user_id: i32,
+        fingerprint: &str,
+    ) -> QueryResult<Option<Self>> {
+        signing_keys::table
+            .filter(signing_keys::user_id.eq(user_id))
+            .filter(signing_keys::fingerprint.eq(fingerprint.to_lowercase()))
+            .filter(signing_keys::revoked_at.is_null())
+            .select(SigningKey::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Checks whether `signature` is a valid DER-encoded signature of `data`
+    /// for this key.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        let Ok(public_key) = PublicKey::from_str(&self.public_key) else {
+            return false;
+        };
+
+        let Ok(signature) = Signature::from_der(signature) else {
+            return false;
+        };
+
+        VerifyingKey::from(public_key)
+            .verify(data, &signature)
+            .is_ok()
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = signing_keys, check_for_backend(diesel::pg::Pg))]
+pub struct NewSigningKey {
+    pub user_id: i32,
+    pub name: String,
+    pub public_key: String,
+    pub fingerprint: String,
+}
+
+impl NewSigningKey {
+    /// Parses the given PEM-encoded public key and returns the key that can
+    /// be inserted into the database, or `None` if the public key is invalid.
+    ///
+    /// The public key is stored in its normalized PEM encoding.
+    pub fn parse(user_id: i32, name: String, public_key: &str) -> Option<Self> {
+        let public_key = PublicKey::from_str(public_key.trim()).ok()?;
+        let der = public_key.to_public_key_der().ok()?;
+
+        Some(Self {
+            user_id,
+            name,
+            public_key: public_key.to_string(),
+            fingerprint: Sha256::digest(der.as_bytes()).encode_hex(),
+        })
+    }
+
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<SigningKey> {
+        diesel::insert_into(signing_keys::table)
+            .values(self)
+            .returning(SigningKey::as_returning())
+            .get_result(conn)
+    }
+}
+
+/// The model representing a row in the `version_signatures` database table.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Insertable, Associations)]
+#[diesel(
+    table_name = version_signatures,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(version_id),
+    belongs_to(Version),
+    belongs_to(SigningKey),
+)]
+pub struct VersionSignature {
+    pub version_id: i32,
+    pub signing_key_id: i32,
+    pub signature: Vec<u8>,
+    pub created_at: NaiveDateTime,
+}
+
+impl VersionSignature {
+    pub fn find(conn: &mut PgConnection, version_id: i32) -> QueryResult<Option<Self>> {
+        version_signatures::table
+            .find(version_id)
+            .select(VersionSignature::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn insert(
+        conn: &mut PgConnection,
+        version_id: i32,
+        signing_key_id: i32,
+        signature: &[u8],
+    ) -> QueryResult<()> {
+        diesel::insert_into(version_signatures::table)
+            .values((
+                version_signatures::version_id.eq(version_id),
+                version_signatures::signing_key_id.eq(signing_key_id),
+                version_signatures::signature.eq(signature),
+            ))
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer;
+    use p256::pkcs8::LineEnding;
+
+    fn signing_key(byte: u8) -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_bytes(&[byte; 32].into()).unwrap()
+    }
+
+    fn public_key_pem(signing_key: &p256::ecdsa::SigningKey) -> String {
+        PublicKey::from(signing_key.verifying_key())
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_and_verify() {
+        let private_key = signing_key(1);
+        let pem = public_key_pem(&private_key);
+
+        assert!(NewSigningKey::parse(1, "test".into(), "not a key").is_none());
+
+        let new_key = NewSigningKey::parse(1, "test".into(), &format!("\n{pem}\n")).unwrap();
+        assert_eq!(new_key.public_key, pem);
+        assert_eq!(new_key.fingerprint.len(), 64);
+
+        let key = SigningKey {
+            id: 1,
+            user_id: 1,
+            name: new_key.name,
+            public_key: new_key.public_key,
+            fingerprint: new_key.fingerprint,
+            created_at: NaiveDateTime::default(),
+            revoked_at: None,
+        };
+
+        let signature: Signature = private_key.sign(b"crate file");
+        let signature = signature.to_der();
+        assert!(key.verify(b"crate file", signature.as_bytes()));
+        assert!(!key.verify(b"other file", signature.as_bytes()));
+        assert!(!key.verify(b"crate file", b"not a signature"));
+
+        let signature: Signature = signing_key(2).sign(b"crate file");
+        assert!(!key.verify(b"crate file", signature.to_der().as_bytes()));
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::schema::storage_issues;
+use crate::sql::pg_enum;
+
+pg_enum! {
+    pub enum StorageIssueKind {
+        Missing = 0,
+        ChecksumMismatch = 1,
+        Orphaned = 2,
+    }
+}
+
+/// The model representing a row in the `storage_issues` database table.
+///
+/// The issues are found by the `verify_storage` background job, which
+/// compares the stored crate files with the checksums in the `versions`
+/// table.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable)]
+#[diesel(table_name = storage_issues, check_for_backend(diesel::pg::Pg))]
+pub struct StorageIssue {
+    pub id: i32,
+    pub kind: StorageIssueKind,
+    pub crate_name: String,
+    pub version: String,
+    pub version_id: Option<i32>,
+    pub expected_checksum: Option<String>,
+    pub actual_checksum: Option<String>,
+    pub detected_at: NaiveDateTime,
+}
+
+impl StorageIssue {
+    pub fn all(conn: &mut PgConnection) -> QueryResult<Vec<Self>> {
+        storage_issues::table
+            .select(StorageIssue::as_select())
+            .order(storage_issues::id)
+            .load(conn)
+    }
+
+    pub fn count(conn: &mut PgConnection) -> QueryResult<i64> {
+        storage_issues::table.count().get_result(conn)
+    }
+
+    /// Replaces the issues of the previous verification run with the
+    /// issues of the current one.
+    pub fn replace_all(conn: &mut PgConnection, issues: &[NewStorageIssue]) -> QueryResult<()> {
+        conn.transaction(|conn| {
+            diesel::delete(storage_issues::table).execute(conn)?;
+            diesel::insert_into(storage_issues::table)
+                .values(issues)
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Insertable)]
+#[diesel(table_name = storage_issues, check_for_backend(diesel::pg::Pg))]
+pub struct NewStorageIssue {
+    pub kind: StorageIssueKind,
+    pub crate_name: String,
+    pub version: String,
+    pub version_id: Option<i32>,
+    pub expected_checksum: Option<String>,
+    pub actual_checksum: Option<String>,
+}
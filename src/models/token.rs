@@ -1,9 +1,11 @@
 mod scopes;
+mod usage;
 
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
 pub use self::scopes::{CrateScope, EndpointScope};
+pub use self::usage::{ApiTokenUsage, NewApiTokenUsage, MAX_USAGES_PER_TOKEN};
 use crate::models::User;
 use crate::schema::api_tokens;
 use crate::util::errors::{AppResult, InsecurelyGeneratedTokenRevoked};
@@ -13,6 +13,31 @@ pub enum EndpointScope {
     PublishUpdate,
     Yank,
     ChangeOwners,
+    /// Read access to the index, the crate files and the API of a private
+    /// registry, without any permission to change them.
+    Read,
+    /// Read access to the private data of the user account, like the
+    /// `/me` and `/me/updates` endpoints and crate owner invitations.
+    ReadPrivate,
+    Follow,
+    ManageInvitations,
+    /// Listing and revoking the API tokens of the user account. New tokens
+    /// can only be created with a cookie session.
+    ManageTokens,
+}
+
+impl EndpointScope {
+    /// Returns `true` for the scopes of endpoints that deal with the user
+    /// account instead of a specific crate, and that tokens with crate scopes
+    /// can therefore never be used for.
+    pub fn is_account_scope(&self) -> bool {
+        matches!(
+            self,
+            EndpointScope::ReadPrivate
+                | EndpointScope::ManageInvitations
+                | EndpointScope::ManageTokens
+        )
+    }
 }
 
 impl From<&EndpointScope> for &[u8] {
@@ -22,6 +47,11 @@ impl From<&EndpointScope> for &[u8] {
             EndpointScope::PublishUpdate => b"publish-update",
             EndpointScope::Yank => b"yank",
             EndpointScope::ChangeOwners => b"change-owners",
+            EndpointScope::Read => b"read",
+            EndpointScope::ReadPrivate => b"read-private",
+            EndpointScope::Follow => b"follow",
+            EndpointScope::ManageInvitations => b"manage-invitations",
+            EndpointScope::ManageTokens => b"manage-tokens",
         }
     }
 }
@@ -42,6 +72,11 @@ impl TryFrom<&[u8]> for EndpointScope {
             b"publish-update" => Ok(EndpointScope::PublishUpdate),
             b"yank" => Ok(EndpointScope::Yank),
             b"change-owners" => Ok(EndpointScope::ChangeOwners),
+            b"read" => Ok(EndpointScope::Read),
+            b"read-private" => Ok(EndpointScope::ReadPrivate),
+            b"follow" => Ok(EndpointScope::Follow),
+            b"manage-invitations" => Ok(EndpointScope::ManageInvitations),
+            b"manage-tokens" => Ok(EndpointScope::ManageTokens),
             _ => Err("Unrecognized enum variant".to_string()),
         }
     }
@@ -137,6 +172,11 @@ mod tests {
         assert(EndpointScope::PublishNew, "\"publish-new\"");
         assert(EndpointScope::PublishUpdate, "\"publish-update\"");
         assert(EndpointScope::Yank, "\"yank\"");
+        assert(EndpointScope::Read, "\"read\"");
+        assert(EndpointScope::ReadPrivate, "\"read-private\"");
+        assert(EndpointScope::Follow, "\"follow\"");
+        assert(EndpointScope::ManageInvitations, "\"manage-invitations\"");
+        assert(EndpointScope::ManageTokens, "\"manage-tokens\"");
     }
 
     #[test]
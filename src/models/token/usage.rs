@@ -0,0 +1,84 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::BigInt;
+
+use crate::models::ApiToken;
+use crate::schema::api_token_usages;
+
+/// Maximum number of uses that are kept for every token.
+pub const MAX_USAGES_PER_TOKEN: i64 = 100;
+
+/// Number of days after which uses of a token are pruned.
+pub const USAGE_RETENTION_DAYS: i64 = 90;
+
+/// The model representing a row in the `api_token_usages` database table.
+#[derive(Debug, Identifiable, Queryable, Selectable, Associations, Serialize)]
+#[diesel(
+    table_name = api_token_usages,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(ApiToken),
+)]
+pub struct ApiTokenUsage {
+    #[serde(skip)]
+    pub id: i64,
+    #[serde(skip)]
+    pub api_token_id: i32,
+    #[serde(with = "crate::util::rfc3339")]
+    pub used_at: NaiveDateTime,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+impl ApiTokenUsage {
+    /// Deletes the uses that are older than [`USAGE_RETENTION_DAYS`], and all
+    /// but the [`MAX_USAGES_PER_TOKEN`] most recent uses of every token.
+    pub fn prune(conn: &mut PgConnection) -> QueryResult<usize> {
+        sql_query(
+            "DELETE FROM api_token_usages \
+             WHERE used_at < now() - make_interval(days => $1::int) \
+             OR id IN ( \
+                 SELECT id FROM ( \
+                     SELECT id, row_number() OVER ( \
+                         PARTITION BY api_token_id ORDER BY used_at DESC, id DESC \
+                     ) AS rank \
+                     FROM api_token_usages \
+                 ) AS ranked \
+                 WHERE rank > $2 \
+             )",
+        )
+        .bind::<BigInt, _>(USAGE_RETENTION_DAYS)
+        .bind::<BigInt, _>(MAX_USAGES_PER_TOKEN)
+        .execute(conn)
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = api_token_usages, check_for_backend(diesel::pg::Pg))]
+pub struct NewApiTokenUsage<'a> {
+    pub api_token_id: i32,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<&'a str>,
+    pub endpoint: Option<String>,
+}
+
+impl NewApiTokenUsage<'_> {
+    /// Records the use of a token. Failures are ignored, since they must not
+    /// prevent the request from being handled, e.g. if the database is in
+    /// read only mode.
+    pub fn insert(&self, conn: &mut PgConnection) {
+        // Try inserting in a new transaction, so that a failure doesn't abort
+        // a surrounding transaction.
+        let result = conn.transaction(|conn| {
+            diesel::insert_into(api_token_usages::table)
+                .values(self)
+                .execute(conn)
+        });
+
+        if let Err(error) = result {
+            let api_token_id = self.api_token_id;
+            debug!(%api_token_id, "Failed to record token usage: {error}");
+        }
+    }
+}
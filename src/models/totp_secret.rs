@@ -0,0 +1,93 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::now;
+use diesel::prelude::*;
+
+use crate::models::User;
+use crate::schema::totp_secrets;
+use crate::util::totp;
+
+/// The model representing a row in the `totp_secrets` database table.
+///
+/// Every user can enroll one TOTP authenticator app as a second factor. The
+/// secret is only used as a second factor once the enrollment has been
+/// confirmed with a valid code.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = totp_secrets,
+    primary_key(user_id),
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User),
+)]
+pub struct TotpSecret {
+    pub user_id: i32,
+    pub secret: Vec<u8>,
+    pub created_at: NaiveDateTime,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+}
+
+impl TotpSecret {
+    pub fn find(conn: &mut PgConnection, user_id: i32) -> QueryResult<Option<Self>> {
+        totp_secrets::table
+            .find(user_id)
+            .select(TotpSecret::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Generates a new secret for the user, which replaces the previous
+    /// secret once the enrollment is confirmed.
+    pub fn begin_enrollment(conn: &mut PgConnection, user_id: i32) -> QueryResult<Self> {
+        let secret = totp::generate_secret();
+
+        diesel::insert_into(totp_secrets::table)
+            .values((
+                totp_secrets::user_id.eq(user_id),
+                totp_secrets::secret.eq(&secret),
+            ))
+            .on_conflict(totp_secrets::user_id)
+            .do_update()
+            .set((
+                totp_secrets::secret.eq(&secret),
+                totp_secrets::created_at.eq(now),
+                totp_secrets::enabled_at.eq(None::<NaiveDateTime>),
+                totp_secrets::last_used_step.eq(None::<i64>),
+            ))
+            .returning(TotpSecret::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Verifies the code and records its time step, so that the code can't
+    /// be used again. Returns `false` if the code is invalid.
+    pub fn verify_code(&self, conn: &mut PgConnection, code: &str) -> QueryResult<bool> {
+        let current_step = totp::time_step(Utc::now());
+        let Some(step) = totp::verify(&self.secret, code, current_step, self.last_used_step) else {
+            return Ok(false);
+        };
+
+        // Filtering on the previous time step makes sure that concurrent
+        // requests can't use the same code twice.
+        let updated = diesel::update(self)
+            .filter(
+                totp_secrets::last_used_step
+                    .is_null()
+                    .or(totp_secrets::last_used_step.lt(step)),
+            )
+            .set(totp_secrets::last_used_step.eq(step))
+            .execute(conn)?;
+
+        Ok(updated == 1)
+    }
+
+    pub fn enable(&self, conn: &mut PgConnection) -> QueryResult<()> {
+        diesel::update(self)
+            .set(totp_secrets::enabled_at.eq(now.nullable()))
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::models::{Crate, User};
+use crate::schema::trusted_publishers;
+
+/// The model representing a row in the `trusted_publishers` database table.
+///
+/// A trusted publisher allows a specific GitHub Actions workflow to exchange
+/// its OIDC token for a short-lived API token that can publish new versions
+/// of the crate, without having to store a long-lived API token in the
+/// repository secrets.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = trusted_publishers,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Crate),
+    belongs_to(User, foreign_key = created_by),
+)]
+pub struct TrustedPublisher {
+    pub id: i32,
+    pub crate_id: i32,
+    pub repository: String,
+    pub workflow_filename: String,
+    pub environment: Option<String>,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+}
+
+impl TrustedPublisher {
+    /// Checks whether the given claims of a GitHub Actions OIDC token match
+    /// this trusted publisher configuration.
+    ///
+    /// The repository and environment names are compared case-insensitively,
+    /// since GitHub treats them that way too.
+    pub fn matches(&self, repository: &str, workflow_filename: &str, env: Option<&str>) -> bool {
+        if !self.repository.eq_ignore_ascii_case(repository) {
+            return false;
+        }
+
+        if self.workflow_filename != workflow_filename {
+            return false;
+        }
+
+        match (&self.environment, env) {
+            (None, _) => true,
+            (Some(expected), Some(env)) => expected.eq_ignore_ascii_case(env),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = trusted_publishers, check_for_backend(diesel::pg::Pg))]
+pub struct NewTrustedPublisher<'a> {
+    pub crate_id: i32,
+    pub repository: &'a str,
+    pub workflow_filename: &'a str,
+    pub environment: Option<&'a str>,
+    pub created_by: i32,
+}
+
+impl NewTrustedPublisher<'_> {
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<TrustedPublisher> {
+        diesel::insert_into(trusted_publishers::table)
+            .values(self)
+            .returning(TrustedPublisher::as_returning())
+            .get_result(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publisher(environment: Option<&str>) -> TrustedPublisher {
+        TrustedPublisher {
+            id: 1,
+            crate_id: 1,
+            repository: "rust-lang/foo".into(),
+            workflow_filename: "release.yml".into(),
+            environment: environment.map(Into::into),
+            created_by: 1,
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let tp = publisher(None);
+        assert!(tp.matches("rust-lang/foo", "release.yml", None));
+        assert!(tp.matches("Rust-Lang/Foo", "release.yml", Some("prod")));
+        assert!(!tp.matches("rust-lang/bar", "release.yml", None));
+        assert!(!tp.matches("rust-lang/foo", "ci.yml", None));
+
+        let tp = publisher(Some("release"));
+        assert!(tp.matches("rust-lang/foo", "release.yml", Some("release")));
+        assert!(tp.matches("rust-lang/foo", "release.yml", Some("Release")));
+        assert!(!tp.matches("rust-lang/foo", "release.yml", Some("staging")));
+        assert!(!tp.matches("rust-lang/foo", "release.yml", None));
+    }
+}
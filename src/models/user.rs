@@ -7,7 +7,7 @@ use crate::email::Emails;
 use crate::util::errors::AppResult;
 
 use crate::models::{ApiToken, Crate, CrateOwner, Email, NewEmail, Owner, OwnerKind, Rights};
-use crate::schema::{crate_owners, emails, users};
+use crate::schema::{crate_owners, emails, totp_secrets, users, webauthn_credentials};
 
 /// The model representing a row in the `users` database table.
 #[derive(Clone, Debug, PartialEq, Eq, Queryable, Identifiable, AsChangeset)]
@@ -159,6 +159,20 @@ impl User {
         Ok(best)
     }
 
+    /// Returns `true` if the user enrolled a TOTP authenticator app or a
+    /// WebAuthn credential as a second factor.
+    pub fn has_second_factor(&self, conn: &mut PgConnection) -> QueryResult<bool> {
+        use diesel::dsl::{exists, select};
+
+        let totp = totp_secrets::table
+            .filter(totp_secrets::user_id.eq(self.id))
+            .filter(totp_secrets::enabled_at.is_not_null());
+        let webauthn =
+            webauthn_credentials::table.filter(webauthn_credentials::user_id.eq(self.id));
+
+        select(exists(totp).or(exists(webauthn))).get_result(conn)
+    }
+
     /// Queries the database for the verified emails
     /// belonging to a given user
     pub fn verified_email(&self, conn: &mut PgConnection) -> QueryResult<Option<String>> {
@@ -0,0 +1,83 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::now;
+use diesel::prelude::*;
+
+use crate::models::User;
+use crate::schema::webauthn_credentials;
+
+/// The model representing a row in the `webauthn_credentials` database table.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations, Serialize)]
+#[diesel(
+    table_name = webauthn_credentials,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User),
+)]
+pub struct WebauthnCredential {
+    pub id: i32,
+    #[serde(skip)]
+    pub user_id: i32,
+    pub name: String,
+    pub credential_id: String,
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+    #[serde(skip)]
+    pub sign_count: i64,
+    #[serde(with = "crate::util::rfc3339")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::util::rfc3339::option")]
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+impl WebauthnCredential {
+    pub fn find(
+        conn: &mut PgConnection,
+        user_id: i32,
+        credential_id: &str,
+    ) -> QueryResult<Option<Self>> {
+        webauthn_credentials::table
+            .filter(webauthn_credentials::user_id.eq(user_id))
+            .filter(webauthn_credentials::credential_id.eq(credential_id))
+            .select(WebauthnCredential::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Records a successful assertion with the given signature counter.
+    ///
+    /// Returns `false` if the counter did not increase, which indicates that
+    /// the authenticator was cloned. Authenticators that don't implement a
+    /// counter always report zero.
+    pub fn record_use(&self, conn: &mut PgConnection, sign_count: u32) -> QueryResult<bool> {
+        let sign_count = i64::from(sign_count);
+        if sign_count != 0 && sign_count <= self.sign_count {
+            return Ok(false);
+        }
+
+        diesel::update(self)
+            .set((
+                webauthn_credentials::sign_count.eq(sign_count),
+                webauthn_credentials::last_used_at.eq(now.nullable()),
+            ))
+            .execute(conn)?;
+
+        Ok(true)
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = webauthn_credentials, check_for_backend(diesel::pg::Pg))]
+pub struct NewWebauthnCredential {
+    pub user_id: i32,
+    pub name: String,
+    pub credential_id: String,
+    pub public_key: Vec<u8>,
+}
+
+impl NewWebauthnCredential {
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<WebauthnCredential> {
+        diesel::insert_into(webauthn_credentials::table)
+            .values(self)
+            .returning(WebauthnCredential::as_returning())
+            .get_result(conn)
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::NaiveDateTime;
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use rand::distributions::{Alphanumeric, DistString};
+use rand::rngs::OsRng;
+use std::io::Write;
+
+use crate::models::{Crate, User};
+use crate::schema::{webhook_deliveries, webhooks};
+
+/// Length of the randomly generated secrets that are used to sign the
+/// webhook payloads.
+const SECRET_LENGTH: usize = 32;
+
+/// The crate events that a webhook can subscribe to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AsExpression, Serialize, Deserialize)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEvent {
+    /// A new version of the crate was published.
+    Publish,
+    /// A version of the crate was yanked.
+    Yank,
+    /// A version of the crate was unyanked.
+    Unyank,
+    /// An owner was added to or removed from the crate.
+    OwnerChange,
+    /// A crate started to depend on the crate.
+    NewDependent,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::Publish => "publish",
+            WebhookEvent::Yank => "yank",
+            WebhookEvent::Unyank => "unyank",
+            WebhookEvent::OwnerChange => "owner-change",
+            WebhookEvent::NewDependent => "new-dependent",
+        }
+    }
+}
+
+impl From<&WebhookEvent> for &[u8] {
+    fn from(event: &WebhookEvent) -> Self {
+        event.as_str().as_bytes()
+    }
+}
+
+impl ToSql<Text, Pg> for WebhookEvent {
+    fn to_sql(&self, out: &mut Output<'_, '_, Pg>) -> serialize::Result {
+        out.write_all(self.into())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl TryFrom<&[u8]> for WebhookEvent {
+    type Error = String;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        match bytes {
+            b"publish" => Ok(WebhookEvent::Publish),
+            b"yank" => Ok(WebhookEvent::Yank),
+            b"unyank" => Ok(WebhookEvent::Unyank),
+            b"owner-change" => Ok(WebhookEvent::OwnerChange),
+            b"new-dependent" => Ok(WebhookEvent::NewDependent),
+            _ => Err("Unrecognized enum variant".to_string()),
+        }
+    }
+}
+
+impl FromSql<Text, Pg> for WebhookEvent {
+    fn from_sql(bytes: diesel::pg::PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Pg>>::from_sql(bytes)?;
+        Ok(WebhookEvent::try_from(value.as_bytes())?)
+    }
+}
+
+/// The model representing a row in the `webhooks` database table.
+///
+/// A webhook is an HTTPS endpoint that gets a signed `POST` request for
+/// every event of the crate that it is subscribed to.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = webhooks,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Crate),
+    belongs_to(User, foreign_key = created_by),
+)]
+pub struct Webhook {
+    pub id: i32,
+    pub crate_id: i32,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = webhooks, check_for_backend(diesel::pg::Pg))]
+pub struct NewWebhook<'a> {
+    pub crate_id: i32,
+    pub url: &'a str,
+    pub secret: String,
+    pub events: &'a [WebhookEvent],
+    pub created_by: i32,
+}
+
+impl<'a> NewWebhook<'a> {
+    /// Creates a new webhook with a randomly generated secret.
+    pub fn new(crate_id: i32, url: &'a str, events: &'a [WebhookEvent], created_by: i32) -> Self {
+        let secret = Alphanumeric.sample_string(&mut OsRng, SECRET_LENGTH);
+
+        Self {
+            crate_id,
+            url,
+            secret,
+            events,
+            created_by,
+        }
+    }
+
+    pub fn insert(&self, conn: &mut PgConnection) -> QueryResult<Webhook> {
+        diesel::insert_into(webhooks::table)
+            .values(self)
+            .returning(Webhook::as_returning())
+            .get_result(conn)
+    }
+}
+
+/// The model representing a row in the `webhook_deliveries` database table.
+///
+/// Every event that a webhook is subscribed to results in one delivery,
+/// which keeps track of the attempts to send the event to the webhook.
+#[derive(Clone, Debug, Identifiable, Queryable, Selectable, Associations)]
+#[diesel(
+    table_name = webhook_deliveries,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Webhook),
+)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i32,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_attempt_at: Option<NaiveDateTime>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub failed_at: Option<NaiveDateTime>,
+}
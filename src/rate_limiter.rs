@@ -14,6 +14,7 @@ pg_enum! {
         PublishNew = 0,
         PublishUpdate = 1,
         YankUnyank = 2,
+        SecondFactor = 3,
     }
 }
 
@@ -23,6 +24,7 @@ impl LimitedAction {
             LimitedAction::PublishNew => 10 * 60, // 10 minutes
             LimitedAction::PublishUpdate => 60,   // 1 minute
             LimitedAction::YankUnyank => 60,      // 1 minute
+            LimitedAction::SecondFactor => 60,    // 1 minute
         }
     }
 
@@ -31,6 +33,7 @@ impl LimitedAction {
             LimitedAction::PublishNew => 5,
             LimitedAction::PublishUpdate => 30,
             LimitedAction::YankUnyank => 100,
+            LimitedAction::SecondFactor => 10,
         }
     }
 
@@ -39,6 +42,7 @@ impl LimitedAction {
             LimitedAction::PublishNew => "PUBLISH_NEW",
             LimitedAction::PublishUpdate => "PUBLISH_UPDATE",
             LimitedAction::YankUnyank => "YANK_UNYANK",
+            LimitedAction::SecondFactor => "SECOND_FACTOR",
         }
     }
 
@@ -53,6 +57,9 @@ impl LimitedAction {
             LimitedAction::YankUnyank => {
                 "You have yanked or unyanked too many versions in a short period of time"
             }
+            LimitedAction::SecondFactor => {
+                "You have tried to verify your second factor too many times in a short period of time"
+            }
         }
     }
 }
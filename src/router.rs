@@ -22,6 +22,7 @@ pub fn build_axum_router(state: AppState) -> Router<(), TimeoutBody<Body>> {
             "/api/v1/crates/new",
             put(krate::publish::publish).layer(DefaultBodyLimit::max(MAX_PUBLISH_CONTENT_LENGTH)),
         )
+        .route("/api/v1/publishes/:publish_id", get(krate::publish::status))
         .route(
             "/api/v1/crates/:crate_id/owners",
             get(krate::owners::owners)
@@ -68,6 +69,18 @@ pub fn build_axum_router(state: AppState) -> Router<(), TimeoutBody<Body>> {
             "/api/v1/crates/:crate_id/:version/authors",
             get(version::metadata::authors),
         )
+        .route(
+            "/api/v1/crates/:crate_id/:version/signature",
+            get(version::signature::show),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/:version/files",
+            get(version::files::list),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/:version/files/*path",
+            get(version::files::show),
+        )
         .route(
             "/api/v1/crates/:crate_id/downloads",
             get(krate::downloads::downloads),
@@ -111,7 +124,87 @@ pub fn build_axum_router(state: AppState) -> Router<(), TimeoutBody<Body>> {
         .route("/api/v1/me/updates", get(user::me::updates))
         .route("/api/v1/me/tokens", get(token::list).put(token::new))
         .route("/api/v1/me/tokens/:id", delete(token::revoke))
+        .route("/api/v1/me/tokens/:id/usage", get(token::usage))
+        .route(
+            "/api/v1/me/signing_keys",
+            get(signing_key::list).put(signing_key::new),
+        )
+        .route("/api/v1/me/signing_keys/:id", delete(signing_key::revoke))
+        .route(
+            "/api/v1/me/asymmetric_keys",
+            get(asymmetric_key::list).put(asymmetric_key::new),
+        )
+        .route(
+            "/api/v1/me/asymmetric_keys/:id",
+            delete(asymmetric_key::revoke),
+        )
+        .route("/api/v1/me/second_factor", get(user::second_factor::show))
+        .route(
+            "/api/v1/me/totp",
+            put(user::second_factor::begin_totp).delete(user::second_factor::remove_totp),
+        )
+        .route(
+            "/api/v1/me/totp/confirm",
+            put(user::second_factor::confirm_totp),
+        )
+        .route(
+            "/api/v1/me/webauthn_credentials",
+            put(user::second_factor::new_webauthn_credential),
+        )
+        .route(
+            "/api/v1/me/webauthn_credentials/:id",
+            delete(user::second_factor::remove_webauthn_credential),
+        )
         .route("/api/v1/tokens/current", delete(token::revoke_current))
+        .route(
+            "/api/v1/crates/:crate_id/advisories",
+            get(krate::advisories::list),
+        )
+        .route("/api/v1/crates/:crate_id/audit", get(krate::audit::list))
+        .route(
+            "/api/v1/crates/:crate_id/deprecation",
+            put(krate::deprecation::deprecate).delete(krate::deprecation::undeprecate),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/trusted_publishers",
+            get(trusted_publishing::list).put(trusted_publishing::create),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/trusted_publishers/:id",
+            delete(trusted_publishing::delete),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/webhooks",
+            get(webhook::list).put(webhook::create),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/webhooks/:id",
+            delete(webhook::delete),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/webhooks/:id/deliveries",
+            get(webhook::deliveries),
+        )
+        .route(
+            "/api/v1/crates/:crate_id/releases.atom",
+            get(feed::crate_releases),
+        )
+        .route("/api/v1/feeds/crates.atom", get(feed::new_crates))
+        .route("/api/v1/feeds/updates.atom", get(feed::updates))
+        .route(
+            "/api/v1/me/feed_token",
+            put(feed::create_token).delete(feed::revoke_token),
+        )
+        .route(
+            "/api/v1/prefixes/:prefix/owners",
+            get(prefix::owners)
+                .put(prefix::add_owners)
+                .delete(prefix::remove_owners),
+        )
+        .route(
+            "/api/v1/trusted_publishing/tokens",
+            put(trusted_publishing::exchange),
+        )
         .route(
             "/api/v1/me/crate_owner_invitations",
             get(crate_owner_invitation::list),
@@ -148,6 +241,14 @@ pub fn build_axum_router(state: AppState) -> Router<(), TimeoutBody<Body>> {
             get(user::session::authorize),
         )
         .route("/api/private/session", delete(user::session::logout))
+        .route(
+            "/api/private/second_factor/challenge",
+            get(user::second_factor::challenge),
+        )
+        .route(
+            "/api/private/second_factor/verify",
+            put(user::second_factor::verify),
+        )
         // Metrics
         .route("/api/private/metrics/:kind", get(metrics::prometheus))
         // Crate ownership invitations management in the frontend
@@ -172,6 +273,15 @@ pub fn build_axum_router(state: AppState) -> Router<(), TimeoutBody<Body>> {
         );
     }
 
+    // Serve the sparse index if the server acts as a mirror of an upstream
+    // registry or as a private registry. Otherwise, the index is served
+    // from the storage backend.
+    if state.config.mirror.is_some() || state.config.private_registry {
+        router = router
+            .route("/index/config.json", get(index::index_config))
+            .route("/index/*path", get(index::index_file));
+    }
+
     router
         .fallback(|method: Method| async move {
             match method {
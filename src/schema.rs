@@ -24,6 +24,84 @@ pub mod sql_types {
     pub use diesel_full_text_search::Tsvector;
 }
 
+diesel::table! {
+    /// Security advisories imported from a RustSec-format advisory database.
+    advisories (id) {
+        /// Identifier of the advisory, e.g. `RUSTSEC-2023-0001`.
+        id -> Varchar,
+        /// The `crate_id` column of the `advisories` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        crate_id -> Int4,
+        /// The `title` column of the `advisories` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `description` column of the `advisories` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// Date on which the advisory was reported.
+        date -> Date,
+        /// The `url` column of the `advisories` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        url -> Nullable<Varchar>,
+        /// Other identifiers of the advisory, e.g. CVE or GHSA identifiers.
+        aliases -> Array<Text>,
+        /// Kind of informational advisory, e.g. `unmaintained` or `unsound`. `NULL` for vulnerabilities.
+        informational -> Nullable<Varchar>,
+        /// Version requirements matching the versions that contain a fix.
+        patched -> Array<Text>,
+        /// Version requirements matching the versions that were never affected.
+        unaffected -> Array<Text>,
+        /// Time at which the advisory was last imported.
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Recent uses of API tokens, to help users find out where and how a possibly leaked token was used. Old rows are pruned by the daily database maintenance job.
+    api_token_usages (id) {
+        /// The `id` column of the `api_token_usages` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `api_token_id` column of the `api_token_usages` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        api_token_id -> Int4,
+        /// The `used_at` column of the `api_token_usages` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        used_at -> Timestamp,
+        /// IP address of the client, as determined by the `real_ip` middleware.
+        ip_address -> Nullable<Varchar>,
+        /// The `user_agent` column of the `api_token_usages` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_agent -> Nullable<Varchar>,
+        /// HTTP method and matched route of the request, e.g. `PUT /api/v1/crates/new`.
+        endpoint -> Nullable<Varchar>,
+    }
+}
+
 diesel::table! {
     /// Representation of the `api_tokens` table.
     ///
@@ -81,6 +159,50 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         expired_at -> Nullable<Timestamp>,
+        /// Time at which the owner of the token was reminded that the token is about to expire.
+        expiry_notification_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// Public keys that users registered to authenticate cargo requests with signed PASETO tokens instead of API tokens.
+    asymmetric_keys (id) {
+        /// The `id` column of the `asymmetric_keys` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `user_id` column of the `asymmetric_keys` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `name` column of the `asymmetric_keys` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// ECDSA P-384 public key in its PASERK `k3.public.` encoding.
+        public_key -> Text,
+        /// PASERK `k3.pid.` ID of the public key, which cargo sends in the footer of every token.
+        key_id -> Varchar,
+        /// The `created_at` column of the `asymmetric_keys` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `last_used_at` column of the `asymmetric_keys` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_used_at -> Nullable<Timestamp>,
+        /// Revoked keys can not be used to authenticate requests anymore.
+        revoked_at -> Nullable<Timestamp>,
     }
 }
 
@@ -207,6 +329,78 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Audit log of owner, token and crate settings changes. Version level actions are recorded in `version_owner_actions` instead.
+    crate_audit_actions (id) {
+        /// The `id` column of the `crate_audit_actions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// Name of the affected crate. This intentionally is not a foreign key to the `crates` table, so that the audit log survives the deletion of the crate.
+        crate_name -> Varchar,
+        /// User that performed the action, or `NULL` if it was performed by the crates.io team via `crates-admin`.
+        user_id -> Nullable<Int4>,
+        /// API token that was used to perform the action, if any.
+        api_token_id -> Nullable<Int4>,
+        /// `0` = invite_owner, `1` = accept_invitation, `2` = decline_invitation, `3` = add_team, `4` = remove_owner, `5` = create_token, `6` = revoke_token, `7` = add_trusted_publisher, `8` = remove_trusted_publisher, `9` = admin_transfer, `10` = admin_delete_version, `11` = admin_delete_crate, `12` = deprecate, `13` = undeprecate, `14` = add_webhook, `15` = remove_webhook
+        action -> Int4,
+        /// Action specific details, like the login of the invited owner or the name of the created API token.
+        details -> Jsonb,
+        /// The `time` column of the `crate_audit_actions` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Users that are allowed to publish new crates under a claimed crate name prefix, and to manage the owners of the prefix.
+    crate_name_prefix_owners (prefix_id, user_id) {
+        /// The `prefix_id` column of the `crate_name_prefix_owners` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        prefix_id -> Int4,
+        /// The `user_id` column of the `crate_name_prefix_owners` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `created_at` column of the `crate_name_prefix_owners` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Crate name prefixes that were claimed by an organization. New crates named `<prefix>` or `<prefix>-*` can only be published by the owners of the prefix.
+    crate_name_prefixes (id) {
+        /// The `id` column of the `crate_name_prefixes` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The claimed prefix, without the trailing separator. Compared using `canon_crate_name()`, so `-` and `_` are interchangeable.
+        prefix -> Varchar,
+        /// The `created_at` column of the `crate_name_prefixes` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `crate_owner_invitations` table.
     ///
@@ -385,6 +579,12 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         max_features -> Nullable<Int2>,
+        /// Deprecation state of the crate as set by its owners: `NULL` = not deprecated, `0` = deprecated, `1` = unmaintained
+        deprecation -> Nullable<Int4>,
+        /// Optional free-form message explaining why the crate is deprecated.
+        deprecation_message -> Nullable<Text>,
+        /// Optional name of the crate that replaces this deprecated crate.
+        successor -> Nullable<Varchar>,
     }
 }
 
@@ -540,6 +740,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Tokens that authenticate the feed of new releases of the crates that a user follows. Feed readers can not log in, so the token is part of the feed URL.
+    feed_tokens (user_id) {
+        /// The `user_id` column of the `feed_tokens` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// SHA256 hash of the token. The plaintext token is only shown to the user once, when it is created.
+        token -> Bytea,
+        /// The `created_at` column of the `feed_tokens` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `follows` table.
     ///
@@ -670,6 +890,54 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Uploads accepted by the `PUT /api/v1/crates/new` endpoint, which are finished by the `process_publish` background job.
+    publishes (id) {
+        /// The `id` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `version_id` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        version_id -> Int4,
+        /// The `user_id` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `api_token_id` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        api_token_id -> Nullable<Int4>,
+        /// `0` = queued, `1` = processing, `2` = succeeded, `3` = failed
+        status -> Int4,
+        /// Error message of the last failed processing attempt.
+        error -> Nullable<Text>,
+        /// Uploaded `.crate` file. This is cleared once the file has been uploaded to the storage backend.
+        tarball -> Nullable<Bytea>,
+        /// The `created_at` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `publishes` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `readme_renderings` table.
     ///
@@ -722,6 +990,82 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Public keys that users registered to sign the crate files they publish.
+    signing_keys (id) {
+        /// The `id` column of the `signing_keys` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `user_id` column of the `signing_keys` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `name` column of the `signing_keys` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// ECDSA P-256 public key in PEM format.
+        public_key -> Text,
+        /// Hex-encoded SHA-256 digest of the DER-encoded public key, used to reference the key when publishing.
+        fingerprint -> Varchar,
+        /// The `created_at` column of the `signing_keys` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// Revoked keys can not be used for new signatures anymore, but existing signatures stay verifiable.
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// Problems with the crate files in the storage backend that were found by the last run of the `verify_storage` background job. The table is replaced on every run.
+    storage_issues (id) {
+        /// The `id` column of the `storage_issues` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// `0` = missing, `1` = checksum_mismatch, `2` = orphaned
+        kind -> Int4,
+        /// The `crate_name` column of the `storage_issues` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        crate_name -> Varchar,
+        /// The `version` column of the `storage_issues` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        version -> Varchar,
+        /// Version that the crate file belongs to. `NULL` for orphaned crate files, which have no matching version.
+        version_id -> Nullable<Int4>,
+        /// SHA256 checksum of the version in the `versions` table.
+        #[max_length = 64]
+        expected_checksum -> Nullable<Bpchar>,
+        /// SHA256 checksum of the stored crate file.
+        #[max_length = 64]
+        actual_checksum -> Nullable<Bpchar>,
+        /// The `detected_at` column of the `storage_issues` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        detected_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `teams` table.
     ///
@@ -754,6 +1098,62 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Shared secrets of the TOTP authenticator apps that users enrolled as a second factor.
+    totp_secrets (user_id) {
+        /// The `user_id` column of the `totp_secrets` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// Shared secret that the one-time codes are derived from.
+        secret -> Bytea,
+        /// The `created_at` column of the `totp_secrets` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// Time at which the enrollment was confirmed with a valid code. Secrets without this timestamp are not used as a second factor yet.
+        enabled_at -> Nullable<Timestamp>,
+        /// Time step of the last accepted code, so that every code can only be used once.
+        last_used_step -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    /// GitHub Actions workflows that are allowed to exchange their OIDC tokens for short-lived, crate-scoped API tokens.
+    trusted_publishers (id) {
+        /// The `id` column of the `trusted_publishers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `crate_id` column of the `trusted_publishers` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        crate_id -> Int4,
+        /// GitHub repository in `owner/name` format. Compared case-insensitively.
+        repository -> Varchar,
+        /// Filename of the workflow in the `.github/workflows` directory of the repository, e.g. `release.yml`.
+        workflow_filename -> Varchar,
+        /// GitHub Actions environment the workflow has to run in. `NULL` allows any environment.
+        environment -> Nullable<Varchar>,
+        /// Owner that configured the trusted publisher. API tokens are minted on behalf of this user.
+        created_by -> Int4,
+        /// The `created_at` column of the `trusted_publishers` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `users` table.
     ///
@@ -810,6 +1210,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Versions that are affected by an advisory, i.e. that are matched by neither the `patched` nor the `unaffected` requirements of the advisory.
+    version_advisories (version_id, advisory_id) {
+        /// The `version_id` column of the `version_advisories` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        version_id -> Int4,
+        /// The `advisory_id` column of the `version_advisories` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        advisory_id -> Varchar,
+    }
+}
+
 diesel::table! {
     /// Representation of the `version_downloads` table.
     ///
@@ -848,6 +1266,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Listings of the files inside of the crate files of published versions.
+    version_file_listings (version_id) {
+        /// The `version_id` column of the `version_file_listings` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        version_id -> Int4,
+        /// JSON array of `{"path", "size", "type"}` objects, with paths relative to the root directory of the crate file.
+        files -> Jsonb,
+        /// The `created_at` column of the `version_file_listings` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `version_owner_actions` table.
     ///
@@ -889,6 +1327,36 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         time -> Timestamp,
+        /// Optional category of the reason for a `yank` action: `0` = other, `1` = security, `2` = broken_build, `3` = mistake
+        yank_category -> Nullable<Int4>,
+        /// Optional free-form message explaining why the version was yanked.
+        yank_message -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    /// Detached signatures of the crate files of published versions. The signature was verified against the uploaded crate file at publish time.
+    version_signatures (version_id) {
+        /// The `version_id` column of the `version_signatures` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        version_id -> Int4,
+        /// The `signing_key_id` column of the `version_signatures` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        signing_key_id -> Int4,
+        /// DER-encoded ECDSA P-256 (SHA-256) signature of the crate file.
+        signature -> Bytea,
+        /// The `created_at` column of the `version_signatures` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
     }
 }
 
@@ -1014,8 +1482,137 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// WebAuthn credentials, like security keys or passkeys, that users enrolled as a second factor.
+    webauthn_credentials (id) {
+        /// The `id` column of the `webauthn_credentials` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `user_id` column of the `webauthn_credentials` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Int4,
+        /// The `name` column of the `webauthn_credentials` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// Base64url-encoded ID that the authenticator assigned to the credential.
+        credential_id -> Varchar,
+        /// DER-encoded ECDSA P-256 public key of the credential.
+        public_key -> Bytea,
+        /// Signature counter reported by the authenticator, used to detect cloned authenticators.
+        sign_count -> Int8,
+        /// The `created_at` column of the `webauthn_credentials` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `last_used_at` column of the `webauthn_credentials` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// Delivery log of the events that were sent, or are still being sent, to the webhooks.
+    webhook_deliveries (id) {
+        /// The `id` column of the `webhook_deliveries` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `webhook_id` column of the `webhook_deliveries` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        webhook_id -> Int4,
+        /// The `event` column of the `webhook_deliveries` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        event -> Varchar,
+        /// JSON body that is sent to the webhook.
+        payload -> Jsonb,
+        /// Number of delivery attempts that have been made so far.
+        attempts -> Int4,
+        /// HTTP status code of the response to the last delivery attempt, if any response was received.
+        last_status -> Nullable<Int4>,
+        /// Error message of the last failed delivery attempt.
+        last_error -> Nullable<Varchar>,
+        /// The `created_at` column of the `webhook_deliveries` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `last_attempt_at` column of the `webhook_deliveries` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_attempt_at -> Nullable<Timestamp>,
+        /// Time of the successful delivery. `NULL` while the delivery is pending or has failed.
+        delivered_at -> Nullable<Timestamp>,
+        /// Time at which the delivery was given up on after too many failed attempts.
+        failed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// HTTPS endpoints that are notified about events of a crate.
+    webhooks (id) {
+        /// The `id` column of the `webhooks` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `crate_id` column of the `webhooks` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        crate_id -> Int4,
+        /// URL that the event payloads are `POST`ed to.
+        url -> Varchar,
+        /// Secret that is used to sign the event payloads with HMAC-SHA256. Only shown to the owner once, when the webhook is created.
+        secret -> Varchar,
+        /// Events that the webhook is subscribed to, e.g. `publish` or `owner-change`.
+        events -> Array<Text>,
+        /// Owner that registered the webhook.
+        created_by -> Int4,
+        /// The `created_at` column of the `webhooks` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(advisories -> crates (crate_id));
+diesel::joinable!(api_token_usages -> api_tokens (api_token_id));
 diesel::joinable!(api_tokens -> users (user_id));
+diesel::joinable!(asymmetric_keys -> users (user_id));
 diesel::joinable!(badges -> crates (crate_id));
+diesel::joinable!(crate_audit_actions -> api_tokens (api_token_id));
+diesel::joinable!(crate_audit_actions -> users (user_id));
+diesel::joinable!(crate_name_prefix_owners -> crate_name_prefixes (prefix_id));
+diesel::joinable!(crate_name_prefix_owners -> users (user_id));
 diesel::joinable!(crate_owner_invitations -> crates (crate_id));
 diesel::joinable!(crate_owners -> crates (crate_id));
 diesel::joinable!(crate_owners -> teams (owner_id));
@@ -1027,25 +1624,49 @@ diesel::joinable!(crates_keywords -> keywords (keyword_id));
 diesel::joinable!(dependencies -> crates (crate_id));
 diesel::joinable!(dependencies -> versions (version_id));
 diesel::joinable!(emails -> users (user_id));
+diesel::joinable!(feed_tokens -> users (user_id));
 diesel::joinable!(follows -> crates (crate_id));
 diesel::joinable!(follows -> users (user_id));
 diesel::joinable!(publish_limit_buckets -> users (user_id));
 diesel::joinable!(publish_rate_overrides -> users (user_id));
+diesel::joinable!(publishes -> api_tokens (api_token_id));
+diesel::joinable!(publishes -> users (user_id));
+diesel::joinable!(publishes -> versions (version_id));
 diesel::joinable!(readme_renderings -> versions (version_id));
 diesel::joinable!(recent_crate_downloads -> crates (crate_id));
+diesel::joinable!(signing_keys -> users (user_id));
+diesel::joinable!(storage_issues -> versions (version_id));
+diesel::joinable!(totp_secrets -> users (user_id));
+diesel::joinable!(trusted_publishers -> crates (crate_id));
+diesel::joinable!(trusted_publishers -> users (created_by));
+diesel::joinable!(version_advisories -> advisories (advisory_id));
+diesel::joinable!(version_advisories -> versions (version_id));
 diesel::joinable!(version_downloads -> versions (version_id));
+diesel::joinable!(version_file_listings -> versions (version_id));
 diesel::joinable!(version_owner_actions -> api_tokens (api_token_id));
 diesel::joinable!(version_owner_actions -> users (user_id));
 diesel::joinable!(version_owner_actions -> versions (version_id));
+diesel::joinable!(version_signatures -> signing_keys (signing_key_id));
+diesel::joinable!(version_signatures -> versions (version_id));
 diesel::joinable!(versions -> crates (crate_id));
 diesel::joinable!(versions -> users (published_by));
 diesel::joinable!(versions_published_by -> versions (version_id));
+diesel::joinable!(webauthn_credentials -> users (user_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> crates (crate_id));
+diesel::joinable!(webhooks -> users (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    advisories,
+    api_token_usages,
     api_tokens,
+    asymmetric_keys,
     background_jobs,
     badges,
     categories,
+    crate_audit_actions,
+    crate_name_prefix_owners,
+    crate_name_prefixes,
     crate_owner_invitations,
     crate_owners,
     crates,
@@ -1053,18 +1674,30 @@ diesel::allow_tables_to_appear_in_same_query!(
     crates_keywords,
     dependencies,
     emails,
+    feed_tokens,
     follows,
     keywords,
     metadata,
     publish_limit_buckets,
     publish_rate_overrides,
+    publishes,
     readme_renderings,
     recent_crate_downloads,
     reserved_crate_names,
+    signing_keys,
+    storage_issues,
     teams,
+    totp_secrets,
+    trusted_publishers,
     users,
+    version_advisories,
     version_downloads,
+    version_file_listings,
     version_owner_actions,
+    version_signatures,
     versions,
     versions_published_by,
+    webauthn_credentials,
+    webhook_deliveries,
+    webhooks,
 );
@@ -11,6 +11,7 @@ use object_store::path::Path;
 use object_store::prefix::PrefixStore;
 use object_store::{ClientOptions, ObjectStore, Result};
 use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -24,6 +25,7 @@ const CONTENT_TYPE_CRATE: &str = "application/gzip";
 const CONTENT_TYPE_DB_DUMP: &str = "application/gzip";
 const CONTENT_TYPE_INDEX: &str = "text/plain";
 const CONTENT_TYPE_README: &str = "text/html";
+const CONTENT_TYPE_SIGNATURE: &str = "application/octet-stream";
 const CACHE_CONTROL_IMMUTABLE: &str = "public,max-age=31536000,immutable";
 const CACHE_CONTROL_INDEX: &str = "public,max-age=600";
 const CACHE_CONTROL_README: &str = "public,max-age=604800";
@@ -40,7 +42,7 @@ pub struct StorageConfig {
 #[allow(clippy::large_enum_variant)]
 pub enum StorageBackend {
     S3 { default: S3Config, index: S3Config },
-    LocalFileSystem { path: PathBuf },
+    FileSystem { path: PathBuf },
     InMemory,
 }
 
@@ -60,6 +62,13 @@ impl StorageConfig {
         }
     }
 
+    pub fn file_system(path: PathBuf) -> Self {
+        Self {
+            backend: StorageBackend::FileSystem { path },
+            cdn_prefix: None,
+        }
+    }
+
     pub fn from_environment() -> Self {
         if let Ok(bucket) = dotenvy::var("S3_BUCKET") {
             let region = dotenvy::var("S3_REGION").ok();
@@ -93,17 +102,31 @@ impl StorageConfig {
             };
         }
 
-        let current_dir = std::env::current_dir()
-            .context("Failed to read the current directory")
-            .unwrap();
+        let cdn_prefix = dotenvy::var("STORAGE_CDN").ok();
 
-        let path = current_dir.join("local_uploads");
+        let path = match dotenvy::var("STORAGE_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => std::env::current_dir()
+                .context("Failed to read the current directory")
+                .unwrap()
+                .join("local_uploads"),
+        };
 
-        let backend = StorageBackend::LocalFileSystem { path };
+        let backend = StorageBackend::FileSystem { path };
 
         Self {
             backend,
-            cdn_prefix: None,
+            cdn_prefix,
+        }
+    }
+
+    /// Returns the root directory of the file system backend, if the files
+    /// in it have to be served by the server itself because there is no CDN
+    /// in front of it.
+    pub fn served_path(&self) -> Option<&StdPath> {
+        match &self.backend {
+            StorageBackend::FileSystem { path } if self.cdn_prefix.is_none() => Some(path),
+            _ => None,
         }
     }
 }
@@ -114,6 +137,7 @@ pub struct Storage {
     store: Box<dyn ObjectStore>,
     crate_upload_store: Box<dyn ObjectStore>,
     readme_upload_store: Box<dyn ObjectStore>,
+    signature_upload_store: Box<dyn ObjectStore>,
     db_dump_upload_store: Box<dyn ObjectStore>,
 
     index_store: Box<dyn ObjectStore>,
@@ -139,6 +163,9 @@ impl Storage {
                 let options = client_options(CONTENT_TYPE_README, CACHE_CONTROL_README);
                 let readme_upload_store = build_s3(default, options);
 
+                let options = client_options(CONTENT_TYPE_SIGNATURE, CACHE_CONTROL_IMMUTABLE);
+                let signature_upload_store = build_s3(default, options);
+
                 let options =
                     ClientOptions::default().with_default_content_type(CONTENT_TYPE_DB_DUMP);
                 let db_dump_upload_store = build_s3(default, options);
@@ -157,6 +184,7 @@ impl Storage {
                     store: Box::new(store),
                     crate_upload_store: Box::new(crate_upload_store),
                     readme_upload_store: Box::new(readme_upload_store),
+                    signature_upload_store: Box::new(signature_upload_store),
                     db_dump_upload_store: Box::new(db_dump_upload_store),
                     cdn_prefix,
                     index_store: Box::new(index_store),
@@ -164,7 +192,7 @@ impl Storage {
                 }
             }
 
-            StorageBackend::LocalFileSystem { path } => {
+            StorageBackend::FileSystem { path } => {
                 warn!(?path, "Using local file system for file storage");
 
                 let index_path = path.join("index");
@@ -188,6 +216,7 @@ impl Storage {
                     store: Box::new(store.clone()),
                     crate_upload_store: Box::new(store.clone()),
                     readme_upload_store: Box::new(store.clone()),
+                    signature_upload_store: Box::new(store.clone()),
                     db_dump_upload_store: Box::new(store),
                     cdn_prefix,
                     index_store: Box::new(index_store.clone()),
@@ -203,6 +232,7 @@ impl Storage {
                     store: Box::new(store.clone()),
                     crate_upload_store: Box::new(store.clone()),
                     readme_upload_store: Box::new(store.clone()),
+                    signature_upload_store: Box::new(store.clone()),
                     db_dump_upload_store: Box::new(store.clone()),
                     cdn_prefix,
                     index_store: Box::new(PrefixStore::new(store.clone(), "index")),
@@ -226,6 +256,14 @@ impl Storage {
         apply_cdn_prefix(&self.cdn_prefix, &readme_path(name, version)).replace('+', "%2B")
     }
 
+    /// Returns the URL of the detached signature of an uploaded crate's
+    /// version archive.
+    ///
+    /// The function doesn't check for the existence of the file.
+    pub fn signature_location(&self, name: &str, version: &str) -> String {
+        apply_cdn_prefix(&self.cdn_prefix, &signature_path(name, version)).replace('+', "%2B")
+    }
+
     #[instrument(skip(self))]
     pub async fn delete_all_crate_files(&self, name: &str) -> Result<()> {
         let prefix = format!("{PREFIX_CRATES}/{name}").into();
@@ -244,24 +282,120 @@ impl Storage {
         self.store.delete(&path).await
     }
 
+    #[instrument(skip(self))]
+    pub async fn delete_crate_signature(&self, name: &str, version: &str) -> Result<()> {
+        let path = signature_path(name, version);
+        self.store.delete(&path).await
+    }
+
     #[instrument(skip(self))]
     pub async fn delete_readme(&self, name: &str, version: &str) -> Result<()> {
         let path = readme_path(name, version);
         self.store.delete(&path).await
     }
 
+    /// Downloads the archive of an uploaded crate version.
+    #[instrument(skip(self))]
+    pub async fn download_crate_file(&self, name: &str, version: &str) -> Result<Bytes> {
+        let path = crate_file_path(name, version);
+        self.store.get(&path).await?.bytes().await
+    }
+
+    /// Checks whether the archive of a crate version has been uploaded.
+    #[instrument(skip(self))]
+    pub async fn has_crate_file(&self, name: &str, version: &str) -> Result<bool> {
+        let path = crate_file_path(name, version);
+        match self.store.head(&path).await {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::NotFound { .. }) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Computes the SHA256 checksum of the archive of an uploaded crate
+    /// version, without loading the whole archive into memory.
+    #[instrument(skip(self))]
+    pub async fn crate_file_checksum(&self, name: &str, version: &str) -> Result<String> {
+        let path = crate_file_path(name, version);
+        let mut stream = self.store.get(&path).await?.into_stream();
+
+        let mut hasher = Sha256::new();
+        while let Some(chunk) = stream.try_next().await? {
+            hasher.update(&chunk);
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Lists the names and versions of all uploaded crate archives.
+    ///
+    /// Files in the `crates` directory that don't follow the naming scheme
+    /// of crate archives, like the detached signatures, are skipped.
+    #[instrument(skip(self))]
+    pub async fn list_crate_files(&self) -> Result<Vec<(String, String)>> {
+        let prefix = PREFIX_CRATES.into();
+        let objects = self.store.list(Some(&prefix)).await?;
+        let objects = objects.try_collect::<Vec<_>>().await?;
+
+        let files = objects
+            .iter()
+            .filter_map(|meta| parse_crate_file_path(meta.location.as_ref()))
+            .collect();
+
+        Ok(files)
+    }
+
     #[instrument(skip(self, bytes))]
     pub async fn upload_crate_file(&self, name: &str, version: &str, bytes: Bytes) -> Result<()> {
         let path = crate_file_path(name, version);
         self.crate_upload_store.put(&path, bytes).await
     }
 
+    #[instrument(skip(self, bytes))]
+    pub async fn upload_crate_signature(
+        &self,
+        name: &str,
+        version: &str,
+        bytes: Bytes,
+    ) -> Result<()> {
+        let path = signature_path(name, version);
+        self.signature_upload_store.put(&path, bytes).await
+    }
+
     #[instrument(skip(self, bytes))]
     pub async fn upload_readme(&self, name: &str, version: &str, bytes: Bytes) -> Result<()> {
         let path = readme_path(name, version);
         self.readme_upload_store.put(&path, bytes).await
     }
 
+    /// Downloads the index file of a crate.
+    #[instrument(skip(self))]
+    pub async fn download_index_file(&self, name: &str) -> Result<Bytes> {
+        let path = crates_io_index::Repository::relative_index_file_for_url(name).into();
+        self.index_store.get(&path).await?.bytes().await
+    }
+
+    /// Lists the names of all crates with an index file.
+    #[instrument(skip(self))]
+    pub async fn list_index_files(&self) -> Result<Vec<String>> {
+        let objects = self.index_store.list(None).await?;
+        let objects = objects.try_collect::<Vec<_>>().await?;
+
+        let names = objects
+            .iter()
+            .filter_map(|meta| {
+                let path = meta.location.as_ref();
+                let name = path.rsplit('/').next()?;
+                let is_index_file =
+                    crates_io_index::Repository::relative_index_file_for_url(name) == path;
+
+                is_index_file.then(|| name.to_string())
+            })
+            .collect();
+
+        Ok(names)
+    }
+
     #[instrument(skip(self, content))]
     pub async fn sync_index(&self, name: &str, content: Option<String>) -> Result<()> {
         let path = crates_io_index::Repository::relative_index_file_for_url(name).into();
@@ -339,6 +473,23 @@ fn crate_file_path(name: &str, version: &str) -> Path {
     format!("{PREFIX_CRATES}/{name}/{name}-{version}.crate").into()
 }
 
+/// Extracts the name and version of a crate from the path of its archive,
+/// which is the reverse of [`crate_file_path`].
+fn parse_crate_file_path(path: &str) -> Option<(String, String)> {
+    let path = path.strip_prefix(PREFIX_CRATES)?.strip_prefix('/')?;
+    let (name, file_name) = path.split_once('/')?;
+    let version = file_name
+        .strip_prefix(name)?
+        .strip_prefix('-')?
+        .strip_suffix(".crate")?;
+
+    Some((name.to_string(), version.to_string()))
+}
+
+fn signature_path(name: &str, version: &str) -> Path {
+    format!("{PREFIX_CRATES}/{name}/{name}-{version}.crate.sig").into()
+}
+
 fn readme_path(name: &str, version: &str) -> Path {
     format!("{PREFIX_READMES}/{name}/{name}-{version}.html").into()
 }
@@ -416,6 +567,18 @@ mod tests {
         for (name, version, expected) in readme_tests {
             assert_eq!(storage.readme_location(name, version), expected);
         }
+
+        let signature_tests = vec![
+            ("foo", "1.2.3", "https://static.crates.io/crates/foo/foo-1.2.3.crate.sig"),
+            (
+                "some-long-crate-name",
+                "42.0.5-beta.1+foo",
+                "https://static.crates.io/crates/some-long-crate-name/some-long-crate-name-42.0.5-beta.1%2Bfoo.crate.sig",
+            ),
+        ];
+        for (name, version, expected) in signature_tests {
+            assert_eq!(storage.signature_location(name, version), expected);
+        }
     }
 
     #[test]
@@ -528,6 +691,35 @@ mod tests {
         assert_eq!(stored_files(&s.store).await, expected_files);
     }
 
+    #[tokio::test]
+    async fn download_crate_file() {
+        let s = Storage::from_config(&StorageConfig::in_memory());
+
+        let bytes = Bytes::from_static(b"crate file");
+        s.upload_crate_file("foo", "1.2.3", bytes.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(s.download_crate_file("foo", "1.2.3").await.unwrap(), bytes);
+        assert!(s.download_crate_file("foo", "2.0.0").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn upload_crate_signature() {
+        let s = Storage::from_config(&StorageConfig::in_memory());
+
+        let bytes = Bytes::from_static(b"signature");
+        s.upload_crate_signature("foo", "1.2.3", bytes.clone())
+            .await
+            .unwrap();
+
+        let expected_files = vec!["crates/foo/foo-1.2.3.crate.sig"];
+        assert_eq!(stored_files(&s.store).await, expected_files);
+
+        s.delete_crate_signature("foo", "1.2.3").await.unwrap();
+        assert!(stored_files(&s.store).await.is_empty());
+    }
+
     #[tokio::test]
     async fn upload_readme() {
         let s = Storage::from_config(&StorageConfig::in_memory());
@@ -566,6 +758,28 @@ mod tests {
         assert!(stored_files(&s.store).await.is_empty());
     }
 
+    #[tokio::test]
+    async fn has_crate_file() {
+        let s = prepare().await;
+
+        assert!(s.has_crate_file("foo", "1.2.3").await.unwrap());
+        assert!(!s.has_crate_file("foo", "2.0.0").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn download_index_file() {
+        let s = Storage::from_config(&StorageConfig::in_memory());
+
+        let error = s.download_index_file("foo").await.unwrap_err();
+        assert!(matches!(error, object_store::Error::NotFound { .. }));
+
+        let content = "foo".to_string();
+        s.sync_index("foo", Some(content)).await.unwrap();
+
+        let bytes = s.download_index_file("foo").await.unwrap();
+        assert_eq!(bytes, "foo");
+    }
+
     #[tokio::test]
     async fn upload_db_dump() {
         let s = Storage::from_config(&StorageConfig::in_memory());
@@ -579,4 +793,117 @@ mod tests {
         let expected_files = vec![target];
         assert_eq!(stored_files(&s.store).await, expected_files);
     }
+
+    #[tokio::test]
+    async fn file_system() {
+        let dir = tempfile::tempdir().unwrap();
+        let s = Storage::from_config(&StorageConfig::file_system(dir.path().into()));
+
+        let bytes = Bytes::from_static(b"foo");
+        s.upload_crate_file("foo", "1.2.3", bytes.clone())
+            .await
+            .unwrap();
+        s.upload_readme("foo", "1.2.3", bytes.clone())
+            .await
+            .unwrap();
+        s.sync_index("foo", Some("foo".to_string())).await.unwrap();
+
+        let file = NamedTempFile::new().unwrap();
+        s.upload_db_dump("db-dump.tar.gz", file.path())
+            .await
+            .unwrap();
+
+        for path in [
+            "crates/foo/foo-1.2.3.crate",
+            "readmes/foo/foo-1.2.3.html",
+            "index/3/f/foo",
+            "db-dump.tar.gz",
+        ] {
+            assert!(dir.path().join(path).is_file(), "{path} is missing");
+        }
+
+        let content = s.download_crate_file("foo", "1.2.3").await.unwrap();
+        assert_eq!(content, bytes);
+        let content = s.download_index_file("foo").await.unwrap();
+        assert_eq!(content, bytes);
+    }
+
+    #[test]
+    fn served_path() {
+        let mut config = StorageConfig::file_system("/srv/crates".into());
+        assert_eq!(config.served_path(), Some(StdPath::new("/srv/crates")));
+
+        config.cdn_prefix = Some("static.example.com".to_string());
+        assert_eq!(config.served_path(), None);
+
+        assert_eq!(StorageConfig::in_memory().served_path(), None);
+    }
+
+    #[tokio::test]
+    async fn crate_file_checksum() {
+        let s = Storage::from_config(&StorageConfig::in_memory());
+
+        let bytes = Bytes::from_static(b"foo");
+        s.upload_crate_file("foo", "1.2.3", bytes).await.unwrap();
+
+        let checksum = s.crate_file_checksum("foo", "1.2.3").await.unwrap();
+        let expected = "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae";
+        assert_eq!(checksum, expected);
+
+        assert!(s.crate_file_checksum("foo", "2.0.0").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn list_crate_files() {
+        let s = prepare().await;
+
+        let signature = Bytes::from_static(b"signature");
+        s.upload_crate_signature("foo", "1.0.0", signature)
+            .await
+            .unwrap();
+
+        let expected = vec![
+            ("bar".to_string(), "2.0.0".to_string()),
+            ("foo".to_string(), "1.0.0".to_string()),
+            ("foo".to_string(), "1.2.3".to_string()),
+        ];
+        assert_eq!(s.list_crate_files().await.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_crate_file_path() {
+        let parse = |path: &str| parse_crate_file_path(path);
+        let expected = |name: &str, version: &str| Some((name.to_string(), version.to_string()));
+
+        assert_eq!(
+            parse("crates/foo/foo-1.0.0.crate"),
+            expected("foo", "1.0.0")
+        );
+        assert_eq!(
+            parse("crates/foo-bar/foo-bar-1.0.0-beta.1.crate"),
+            expected("foo-bar", "1.0.0-beta.1")
+        );
+        assert_eq!(parse("crates/foo/foo-1.0.0.crate.sig"), None);
+        assert_eq!(parse("crates/foo/bar-1.0.0.crate"), None);
+        assert_eq!(parse("readmes/foo/foo-1.0.0.html"), None);
+        assert_eq!(parse("crates"), None);
+    }
+
+    #[tokio::test]
+    async fn list_index_files() {
+        let s = Storage::from_config(&StorageConfig::in_memory());
+
+        for name in ["a", "foo", "serde"] {
+            s.sync_index(name, Some(String::new())).await.unwrap();
+        }
+
+        let config = Bytes::from_static(b"{}");
+        s.index_upload_store
+            .put(&"config.json".into(), config)
+            .await
+            .unwrap();
+
+        let expected = vec!["a", "foo", "serde"];
+        assert_eq!(s.list_index_files().await.unwrap(), expected);
+    }
 }
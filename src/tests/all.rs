@@ -24,6 +24,7 @@ use std::{
 use diesel::prelude::*;
 
 mod account_lock;
+mod asymmetric_token;
 mod authentication;
 mod blocked_routes;
 mod builders;
@@ -32,15 +33,18 @@ mod dump_db;
 mod github_secret_scanning;
 mod krate;
 mod middleware;
+mod mirror;
 mod models;
 mod not_found_error;
 mod owners;
 mod pagination;
+mod private_registry;
 mod read_only_mode;
 mod routes;
 mod schema_details;
 mod server;
 mod server_binary;
+mod storage;
 mod team;
 mod token;
 mod unhealthy_database;
@@ -0,0 +1,168 @@
+use crate::builders::CrateBuilder;
+use crate::util::{MockRequestExt, RequestHelper, Response, TestApp};
+use chrono::{Duration, Utc};
+use crates_io::models::NewAsymmetricKey;
+use crates_io::schema::asymmetric_keys;
+use crates_io::util::asymmetric_token::{AsymmetricToken, Claims, PublicKey};
+use diesel::prelude::*;
+use http::{header, Method, StatusCode};
+use p384::ecdsa::SigningKey;
+
+const INDEX_URL: &str = "sparse+https://crates.io/index/";
+const MUST_LOGIN: &str = r#"{"errors":[{"detail":"must be logged in to perform that action"}]}"#;
+
+fn signing_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 48].into()).unwrap()
+}
+
+/// Registers the public key of `signing_key(seed)` for the given user.
+fn register_key(app: &TestApp, user_id: i32, seed: u8) -> i32 {
+    let public_key = PublicKey::from(&signing_key(seed));
+
+    app.db(|conn| {
+        NewAsymmetricKey::parse(user_id, "laptop".into(), &public_key.to_paserk())
+            .unwrap()
+            .insert(conn)
+            .unwrap()
+            .id
+    })
+}
+
+fn read_claims() -> Claims {
+    Claims {
+        iat: Utc::now().to_rfc3339(),
+        ..Default::default()
+    }
+}
+
+fn run(anon: &impl RequestHelper, method: Method, url: &str, token: &str) -> Response<()> {
+    let mut request = anon.request_builder(method, url);
+    request.header(header::AUTHORIZATION, token);
+    anon.run(request)
+}
+
+#[test]
+fn read_only_token() {
+    let (app, anon, user) = TestApp::init().with_user();
+    let user = user.as_model();
+    register_key(&app, user.id, 1);
+
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let token = AsymmetricToken::sign(&signing_key(1), &read_claims(), INDEX_URL);
+    let response = run(&anon, Method::GET, "/api/v1/crates?following=1", &token);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let last_used_at = app.db(|conn| {
+        asymmetric_keys::table
+            .select(asymmetric_keys::last_used_at)
+            .first::<Option<chrono::NaiveDateTime>>(conn)
+            .unwrap()
+    });
+    assert_some!(last_used_at);
+
+    // Read-only tokens are not accepted by endpoints that change anything
+    let response = run(&anon, Method::PUT, "/api/v1/crates/foo/follow", &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn invalid_tokens() {
+    let (app, anon, user) = TestApp::init().with_user();
+    let key_id = register_key(&app, user.as_model().id, 1);
+    let url = "/api/v1/crates?following=1";
+
+    // The key is not registered
+    let token = AsymmetricToken::sign(&signing_key(2), &read_claims(), INDEX_URL);
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.into_text(), MUST_LOGIN);
+
+    // The token was signed for another registry
+    let token = AsymmetricToken::sign(&signing_key(1), &read_claims(), "sparse+https://other/");
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The token was issued too long ago
+    let claims = Claims {
+        iat: (Utc::now() - Duration::hours(1)).to_rfc3339(),
+        ..Default::default()
+    };
+    let token = AsymmetricToken::sign(&signing_key(1), &claims, INDEX_URL);
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The token contains a challenge that this registry never issued
+    let claims = Claims {
+        challenge: Some("challenge".into()),
+        ..read_claims()
+    };
+    let token = AsymmetricToken::sign(&signing_key(1), &claims, INDEX_URL);
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The key was revoked
+    let token = AsymmetricToken::sign(&signing_key(1), &read_claims(), INDEX_URL);
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = user.delete::<()>(&format!("/api/v1/me/asymmetric_keys/{key_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = run(&anon, Method::GET, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn yank_with_token() {
+    let (app, anon, user) = TestApp::full().with_user();
+    let user = user.as_model();
+    register_key(&app, user.id, 1);
+
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let url = "/api/v1/crates/foo/1.0.0/yank";
+
+    // The token was signed for another version
+    let claims = Claims {
+        mutation: Some("yank".into()),
+        name: Some("foo".into()),
+        vers: Some("2.0.0".into()),
+        ..read_claims()
+    };
+    let token = AsymmetricToken::sign(&signing_key(1), &claims, INDEX_URL);
+    let response = run(&anon, Method::DELETE, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The token was signed for unyanking instead of yanking
+    let claims = Claims {
+        mutation: Some("unyank".into()),
+        name: Some("foo".into()),
+        vers: Some("1.0.0".into()),
+        ..read_claims()
+    };
+    let token = AsymmetricToken::sign(&signing_key(1), &claims, INDEX_URL);
+    let response = run(&anon, Method::DELETE, url, &token);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let claims = Claims {
+        mutation: Some("yank".into()),
+        ..claims
+    };
+    let token = AsymmetricToken::sign(&signing_key(1), &claims, INDEX_URL);
+    let response = run(&anon, Method::DELETE, url, &token);
+    assert_eq!(response.status(), StatusCode::OK);
+    app.run_pending_background_jobs();
+
+    let crates = app.crates_from_index_head("foo");
+    assert_eq!(crates.len(), 1);
+    assert_eq!(crates[0].yanked, Some(true));
+}
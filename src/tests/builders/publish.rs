@@ -1,7 +1,9 @@
+use base64::{engine::general_purpose, Engine};
 use bytes::{BufMut, Bytes, BytesMut};
 use cargo_manifest::{DependencyDetail, DepsSet, MaybeInherited};
 use crates_io::models::DependencyKind;
 use crates_io::views::krate_publish as u;
+use p256::ecdsa::signature::Signer;
 use std::collections::BTreeMap;
 
 use crates_io_tarball::TarballBuilder;
@@ -25,6 +27,7 @@ pub struct PublishBuilder {
     readme: Option<String>,
     version: semver::Version,
     features: BTreeMap<String, Vec<String>>,
+    signing_key: Option<(String, p256::ecdsa::SigningKey)>,
 }
 
 enum Manifest {
@@ -51,6 +54,7 @@ impl PublishBuilder {
             readme: None,
             version: semver::Version::parse(version).unwrap(),
             features: BTreeMap::new(),
+            signing_key: None,
         }
     }
 
@@ -138,12 +142,20 @@ impl PublishBuilder {
         self
     }
 
+    /// Sign the crate file with the given private key, and reference the
+    /// registered signing key with the given fingerprint in the request.
+    pub fn signed_with(mut self, fingerprint: &str, key: p256::ecdsa::SigningKey) -> Self {
+        self.signing_key = Some((fingerprint.to_string(), key));
+        self
+    }
+
     pub fn build(self) -> (String, Vec<u8>) {
-        let metadata = u::PublishMetadata {
+        let mut metadata = u::PublishMetadata {
             name: self.krate_name.clone(),
             vers: self.version.to_string(),
             readme: self.readme,
             readme_file: None,
+            signature: None,
         };
 
         let mut tarball_builder = TarballBuilder::new();
@@ -202,6 +214,15 @@ impl PublishBuilder {
         }
 
         let tarball = tarball_builder.build();
+
+        if let Some((fingerprint, key)) = self.signing_key {
+            let signature: p256::ecdsa::Signature = key.sign(&tarball);
+            metadata.signature = Some(u::PublishSignature {
+                key: fingerprint,
+                signature: general_purpose::STANDARD.encode(signature.to_der()),
+            });
+        }
+
         (serde_json::to_string(&metadata).unwrap(), tarball)
     }
 
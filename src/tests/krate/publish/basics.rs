@@ -15,6 +15,9 @@ fn new_krate() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let crates = app.crates_from_index_head("foo_new");
@@ -42,6 +45,9 @@ fn new_krate_with_token() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let expected_files = vec!["crates/foo_new/foo_new-1.0.0.crate", "index/fo/o_/foo_new"];
@@ -58,6 +64,9 @@ fn new_krate_weird_version() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let expected_files = vec![
@@ -81,6 +90,9 @@ fn new_krate_twice() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let crates = app.crates_from_index_head("foo_twice");
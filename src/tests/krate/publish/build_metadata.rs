@@ -11,6 +11,9 @@ fn version_with_build_metadata(v1: &str, v2: &str, expected_error: &str) {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let response = token.publish_crate(PublishBuilder::new("foo", v2));
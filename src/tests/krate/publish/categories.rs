@@ -20,6 +20,9 @@ fn good_categories() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 }
 
@@ -33,6 +36,9 @@ fn ignored_categories() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 }
 
@@ -1,5 +1,6 @@
-use crate::builders::{CrateBuilder, DependencyBuilder, PublishBuilder};
+use crate::builders::{CrateBuilder, DependencyBuilder, PublishBuilder, VersionBuilder};
 use crate::util::{RequestHelper, TestApp};
+use crates_io::models::DependencyKind;
 use http::StatusCode;
 use insta::assert_json_snapshot;
 
@@ -21,7 +22,9 @@ fn new_with_renamed_dependency() {
 
     app.db(|conn| {
         // Insert a crate directly into the database so that new-krate can depend on it
-        CrateBuilder::new("package-name", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("package-name", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("package-name").rename("my-name");
@@ -39,7 +42,9 @@ fn invalid_dependency_rename() {
 
     app.db(|conn| {
         // Insert a crate directly into the database so that new-krate can depend on it
-        CrateBuilder::new("package-name", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("package-name", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let response = token.publish_crate(
@@ -57,7 +62,9 @@ fn new_with_underscore_renamed_dependency() {
 
     app.db(|conn| {
         // Insert a crate directly into the database so that new-krate can depend on it
-        CrateBuilder::new("package-name", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("package-name", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("package-name").rename("_my-name");
@@ -80,7 +87,9 @@ fn new_krate_with_dependency() {
         // The name choice of `foo-dep` is important! It has the property of
         // name != canon_crate_name(name) and is a regression test for
         // https://github.com/rust-lang/crates.io/issues/651
-        CrateBuilder::new("foo-dep", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("foo-dep").version_req("1.0.0");
@@ -111,7 +120,9 @@ fn new_krate_with_broken_dependency_requirement() {
         // The name choice of `foo-dep` is important! It has the property of
         // name != canon_crate_name(name) and is a regression test for
         // https://github.com/rust-lang/crates.io/issues/651
-        CrateBuilder::new("foo-dep", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("foo-dep").version_req("broken");
@@ -128,7 +139,9 @@ fn reject_new_krate_with_non_exact_dependency() {
     let (app, _, user, token) = TestApp::full().with_token();
 
     app.db(|conn| {
-        CrateBuilder::new("foo-dep", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     // Use non-exact name for the dependency
@@ -147,7 +160,9 @@ fn new_crate_allow_empty_alternative_registry_dependency() {
     let (app, _, user, token) = TestApp::full().with_token();
 
     app.db(|conn| {
-        CrateBuilder::new("foo-dep", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("foo-dep").registry("");
@@ -210,8 +225,12 @@ fn new_krate_sorts_deps() {
 
     app.db(|conn| {
         // Insert crates directly into the database so that two-deps can depend on it
-        CrateBuilder::new("dep-a", user.as_model().id).expect_build(conn);
-        CrateBuilder::new("dep-b", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("dep-a", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
+        CrateBuilder::new("dep-b", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dep_a = DependencyBuilder::new("dep-a");
@@ -239,3 +258,86 @@ fn invalid_feature_name() {
     assert_json_snapshot!(response.into_json());
     assert!(app.stored_files().is_empty());
 }
+
+#[test]
+fn reject_new_krate_with_unsatisfiable_dependency() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    app.db(|conn| {
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .version("2.0.0-beta.1")
+            .expect_build(conn);
+    });
+
+    // Pre-release versions only match requirements that explicitly mention them
+    for version_req in ["1.1.0", "2.0.0"] {
+        let dependency = DependencyBuilder::new("foo-dep").version_req(version_req);
+        let crate_to_publish = PublishBuilder::new("new_dep", "1.0.0").dependency(dependency);
+        let response = token.publish_crate(crate_to_publish);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json_snapshot!(response.into_json());
+    }
+
+    let dependency = DependencyBuilder::new("foo-dep")
+        .version_req("2.0.0")
+        .kind(DependencyKind::Build);
+    let crate_to_publish = PublishBuilder::new("new_dep", "1.0.0").dependency(dependency);
+    let response = token.publish_crate(crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    assert!(app.stored_files().is_empty());
+
+    let dependency = DependencyBuilder::new("foo-dep").version_req("2.0.0-beta.1");
+    let crate_to_publish = PublishBuilder::new("new_dep", "1.0.0").dependency(dependency);
+    let json = token.publish_crate(crate_to_publish).good();
+    assert!(json.warnings.other.is_empty());
+}
+
+#[test]
+fn new_krate_with_unsatisfiable_dev_dependency() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    app.db(|conn| {
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let dependency = DependencyBuilder::new("foo-dep")
+        .version_req("2.0.0")
+        .kind(DependencyKind::Dev);
+    let crate_to_publish = PublishBuilder::new("new_dep", "1.0.0").dependency(dependency);
+    let json = token.publish_crate(crate_to_publish).good();
+    assert_eq!(
+        json.warnings.other,
+        ["no published version of dependency `foo-dep` matches the version requirement `^2.0.0`"]
+    );
+}
+
+#[test]
+fn new_krate_with_yanked_dependency() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    app.db(|conn| {
+        CrateBuilder::new("foo-dep", user.as_model().id)
+            .version(VersionBuilder::new("1.0.0").yanked(true))
+            .version(VersionBuilder::new("1.1.0").yanked(true))
+            .version("2.0.0")
+            .expect_build(conn);
+    });
+
+    let dependency = DependencyBuilder::new("foo-dep").version_req("1.0.0");
+    let crate_to_publish = PublishBuilder::new("new_dep", "1.0.0").dependency(dependency);
+    let json = token.publish_crate(crate_to_publish).good();
+    assert_eq!(
+        json.warnings.other,
+        ["all versions of dependency `foo-dep` that match the version requirement `^1.0.0` have been yanked"]
+    );
+
+    let dependency = DependencyBuilder::new("foo-dep").version_req(">= 1.0.0");
+    let crate_to_publish = PublishBuilder::new("new_dep", "1.0.1").dependency(dependency);
+    let json = token.publish_crate(crate_to_publish).good();
+    assert!(json.warnings.other.is_empty());
+}
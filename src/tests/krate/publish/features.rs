@@ -9,7 +9,9 @@ fn features_version_2() {
 
     app.db(|conn| {
         // Insert a crate directly into the database so that foo_new can depend on it
-        CrateBuilder::new("bar", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("bar", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
     });
 
     let dependency = DependencyBuilder::new("bar");
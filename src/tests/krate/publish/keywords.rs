@@ -15,6 +15,9 @@ fn good_keywords() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 }
 
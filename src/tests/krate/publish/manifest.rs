@@ -23,6 +23,9 @@ fn boolean_readme() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
 
     let response = token.get::<()>("/api/v1/crates/foo/1.0.0");
@@ -49,6 +49,9 @@ fn tarball_between_default_axum_limit_and_max_upload_size() {
     assert_json_snapshot!(response.into_json(), {
         ".crate.created_at" => "[datetime]",
         ".crate.updated_at" => "[datetime]",
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
     });
     assert_eq!(app.stored_files().len(), 2);
 }
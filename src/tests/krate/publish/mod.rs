@@ -11,8 +11,10 @@ mod inheritance;
 mod keywords;
 mod manifest;
 mod max_size;
+mod prefixes;
 mod rate_limit;
 mod readme;
+mod signatures;
 mod similar_names;
 mod tarball;
 mod timestamps;
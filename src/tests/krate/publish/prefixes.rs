@@ -0,0 +1,79 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::NewCrateNamePrefix;
+use http::StatusCode;
+use insta::assert_json_snapshot;
+
+#[test]
+fn new_krate_under_claimed_prefix() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    let other_user = app.db_new_user("other").db_new_token("other-token");
+
+    app.db(|conn| {
+        // Crates that existed before the prefix was claimed keep their owners
+        CrateBuilder::new("mycompany-legacy", other_user.as_model().user_id).expect_build(conn);
+
+        let prefix = NewCrateNamePrefix {
+            prefix: "mycompany",
+        }
+        .insert(conn)
+        .unwrap();
+        prefix.add_owner(conn, user.as_model().id).unwrap();
+    });
+
+    for name in ["mycompany", "mycompany-foo", "MyCompany_bar"] {
+        let response = other_user.publish_crate(PublishBuilder::new(name, "1.0.0"));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json_snapshot!(response.into_json());
+    }
+
+    // Crate names that merely start with the same letters are not affected
+    other_user
+        .publish_crate(PublishBuilder::new("mycompanyfoo", "1.0.0"))
+        .good();
+
+    other_user
+        .publish_crate(PublishBuilder::new("mycompany-legacy", "2.0.0"))
+        .good();
+
+    token
+        .publish_crate(PublishBuilder::new("mycompany-foo", "1.0.0"))
+        .good();
+
+    let crates = app.crates_from_index_head("mycompany-foo");
+    assert_eq!(crates.len(), 1);
+}
+
+#[test]
+fn most_specific_prefix_wins() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    let other_user = app.db_new_user("other").db_new_token("other-token");
+
+    app.db(|conn| {
+        let prefix = NewCrateNamePrefix {
+            prefix: "mycompany",
+        }
+        .insert(conn)
+        .unwrap();
+        prefix.add_owner(conn, user.as_model().id).unwrap();
+
+        let prefix = NewCrateNamePrefix {
+            prefix: "mycompany-team",
+        }
+        .insert(conn)
+        .unwrap();
+        prefix
+            .add_owner(conn, other_user.as_model().user_id)
+            .unwrap();
+    });
+
+    other_user
+        .publish_crate(PublishBuilder::new("mycompany-team-foo", "1.0.0"))
+        .good();
+
+    let response = token.publish_crate(PublishBuilder::new("mycompany-team-bar", "1.0.0"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+}
@@ -0,0 +1,123 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{NewSigningKey, SigningKey};
+use crates_io::schema::signing_keys;
+use diesel::prelude::*;
+use http::StatusCode;
+use insta::assert_json_snapshot;
+use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+fn private_key(seed: u8) -> p256::ecdsa::SigningKey {
+    p256::ecdsa::SigningKey::from_bytes(&[seed; 32].into()).unwrap()
+}
+
+fn register_key(conn: &mut PgConnection, user_id: i32, seed: u8) -> SigningKey {
+    let pem = p256::PublicKey::from(private_key(seed).verifying_key())
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+
+    NewSigningKey::parse(user_id, "test".into(), &pem)
+        .unwrap()
+        .insert(conn)
+        .unwrap()
+}
+
+#[test]
+fn new_krate_with_signature() {
+    let (app, anon, _, token) = TestApp::full().with_token();
+    let user_id = token.as_model().user_id;
+
+    let key = app.db(|conn| register_key(conn, user_id, 1));
+
+    let crate_to_publish =
+        PublishBuilder::new("foo", "1.0.0").signed_with(&key.fingerprint, private_key(1));
+    token.publish_crate(crate_to_publish).good();
+
+    let expected_files = vec![
+        "crates/foo/foo-1.0.0.crate",
+        "crates/foo/foo-1.0.0.crate.sig",
+        "index/3/f/foo",
+    ];
+    assert_eq!(app.stored_files(), expected_files);
+
+    let json = anon
+        .get::<()>("/api/v1/crates/foo/1.0.0/signature")
+        .into_json();
+    assert_eq!(json["signature"]["key"]["fingerprint"], key.fingerprint);
+    assert_eq!(json["signature"]["signed_by"]["login"], "foo");
+    assert_eq!(
+        json["signature"]["url"],
+        "https://static.crates.io/crates/foo/foo-1.0.0.crate.sig"
+    );
+}
+
+#[test]
+fn new_krate_with_invalid_signature() {
+    let (app, _, _, token) = TestApp::full().with_token();
+    let user_id = token.as_model().user_id;
+
+    let key = app.db(|conn| register_key(conn, user_id, 1));
+
+    let crate_to_publish =
+        PublishBuilder::new("foo", "1.0.0").signed_with(&key.fingerprint, private_key(2));
+    let response = token.publish_crate(crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    assert!(app.stored_files().is_empty());
+}
+
+#[test]
+fn new_krate_with_unknown_signing_key() {
+    let (app, _, _, token) = TestApp::full().with_token();
+
+    // Keys of other users can not be used
+    let other_user = app.db_new_user("other");
+    let other_user_id = other_user.as_model().id;
+    let key = app.db(|conn| register_key(conn, other_user_id, 1));
+
+    let crate_to_publish =
+        PublishBuilder::new("foo", "1.0.0").signed_with(&key.fingerprint, private_key(1));
+    let response = token.publish_crate(crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    assert!(app.stored_files().is_empty());
+}
+
+#[test]
+fn new_krate_with_revoked_signing_key() {
+    let (app, _, _, token) = TestApp::full().with_token();
+    let user_id = token.as_model().user_id;
+
+    let key = app.db(|conn| {
+        let key = register_key(conn, user_id, 1);
+        diesel::update(signing_keys::table.find(key.id))
+            .set(signing_keys::revoked_at.eq(diesel::dsl::now))
+            .execute(conn)
+            .unwrap();
+        key
+    });
+
+    let crate_to_publish =
+        PublishBuilder::new("foo", "1.0.0").signed_with(&key.fingerprint, private_key(1));
+    let response = token.publish_crate(crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    assert!(app.stored_files().is_empty());
+}
+
+#[test]
+fn unsigned_version_has_no_signature() {
+    let (app, anon, user) = TestApp::init().with_user();
+
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.0.0/signature");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
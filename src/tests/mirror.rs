@@ -0,0 +1,235 @@
+use crate::util::{MockAnonymousUser, RequestHelper, TestApp};
+use crates_io::config::MirrorConfig;
+use http::StatusCode;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const FOO_1_0_0: &[u8] = b"foo 1.0.0 crate file";
+const FOO_1_1_0: &[u8] = b"foo 1.1.0 crate file";
+
+/// A minimal HTTP server that acts as the upstream registry and records the
+/// paths of the requests it receives.
+struct Upstream {
+    url: String,
+    files: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Upstream {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let files = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let served_files = files.clone();
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split(' ').nth(1).unwrap().to_string();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                }
+
+                let file = served_files.lock().unwrap().get(&path).cloned();
+                received.lock().unwrap().push(path);
+
+                let (status, body) = match file {
+                    Some(body) => ("200 OK", body),
+                    None => ("404 Not Found", vec![]),
+                };
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+
+        let upstream = Self {
+            url,
+            files,
+            requests,
+        };
+
+        let dl = format!(
+            "{}/crates/{{crate}}/{{crate}}-{{version}}.crate",
+            upstream.url
+        );
+        upstream.add_file("/config.json", json!({ "dl": dl }).to_string());
+        upstream.add_file("/crates/foo/foo-1.0.0.crate", FOO_1_0_0);
+        upstream.add_file("/crates/foo/foo-1.1.0.crate", FOO_1_1_0);
+        upstream.add_file("/3/f/foo", index_file(&[("1.0.0", FOO_1_0_0)]));
+
+        upstream
+    }
+
+    fn add_file(&self, path: &str, content: impl Into<Vec<u8>>) {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), content.into());
+    }
+
+    fn take_requests(&self) -> Vec<String> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+fn index_file(versions: &[(&str, &[u8])]) -> String {
+    let mut content = String::new();
+    for (version, crate_file) in versions {
+        let cksum = hex::encode(Sha256::digest(crate_file));
+        let entry = json!({
+            "name": "foo",
+            "vers": version,
+            "deps": [],
+            "cksum": cksum,
+            "features": {},
+            "yanked": false,
+        });
+        content.push_str(&format!("{entry}\n"));
+    }
+    content
+}
+
+fn mirror_app(upstream: &Upstream) -> (TestApp, MockAnonymousUser) {
+    let upstream_index = format!("{}/", upstream.url);
+    TestApp::init()
+        .with_config(|config| config.mirror = Some(MirrorConfig { upstream_index }))
+        .empty()
+}
+
+#[test]
+fn index_files_are_fetched_from_upstream() {
+    let upstream = Upstream::start();
+    let (app, anon) = mirror_app(&upstream);
+
+    let json = anon.get::<()>("/index/config.json").into_json();
+    assert_eq!(json["dl"], "https://crates.io/api/v1/crates");
+    assert_eq!(json["api"], "https://crates.io");
+
+    let response = anon.get::<()>("/index/3/f/foo");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_text(), index_file(&[("1.0.0", FOO_1_0_0)]));
+    assert_eq!(upstream.take_requests(), ["/3/f/foo"]);
+    assert_eq!(app.stored_files(), ["index/3/f/foo"]);
+
+    // The stored index file is served without asking the upstream registry
+    let response = anon.get::<()>("/index/3/f/foo");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(upstream.take_requests().is_empty());
+
+    anon.get::<()>("/index/3/b/bar").assert_not_found();
+    assert_eq!(upstream.take_requests(), ["/3/b/bar"]);
+
+    // Paths that don't match the name of the crate are rejected
+    anon.get::<()>("/index/fo/o/foo").assert_not_found();
+    assert!(upstream.take_requests().is_empty());
+}
+
+#[test]
+fn crate_files_are_fetched_from_upstream() {
+    let upstream = Upstream::start();
+    let (app, anon) = mirror_app(&upstream);
+
+    anon.get::<()>("/api/v1/crates/foo/1.0.0/download")
+        .assert_redirect_ends_with("/crates/foo/foo-1.0.0.crate");
+    assert_eq!(
+        upstream.take_requests(),
+        ["/3/f/foo", "/config.json", "/crates/foo/foo-1.0.0.crate"]
+    );
+    assert_eq!(
+        app.stored_files(),
+        ["crates/foo/foo-1.0.0.crate", "index/3/f/foo"]
+    );
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let storage = &app.as_inner().storage;
+    let stored = rt.block_on(storage.download_crate_file("foo", "1.0.0"));
+    assert_eq!(stored.unwrap(), FOO_1_0_0);
+
+    // The stored crate file is served without asking the upstream registry
+    anon.get::<()>("/api/v1/crates/foo/1.0.0/download")
+        .assert_redirect_ends_with("/crates/foo/foo-1.0.0.crate");
+    assert!(upstream.take_requests().is_empty());
+}
+
+#[test]
+fn index_file_is_refreshed_for_new_versions() {
+    let upstream = Upstream::start();
+    let (_, anon) = mirror_app(&upstream);
+
+    assert_eq!(anon.get::<()>("/index/3/f/foo").status(), StatusCode::OK);
+    upstream.take_requests();
+
+    let versions = [("1.0.0", FOO_1_0_0), ("1.1.0", FOO_1_1_0)];
+    upstream.add_file("/3/f/foo", index_file(&versions));
+
+    anon.get::<()>("/api/v1/crates/foo/1.1.0/download")
+        .assert_redirect_ends_with("/crates/foo/foo-1.1.0.crate");
+    assert_eq!(
+        upstream.take_requests(),
+        ["/3/f/foo", "/config.json", "/crates/foo/foo-1.1.0.crate"]
+    );
+
+    let response = anon.get::<()>("/index/3/f/foo");
+    assert_eq!(response.into_text(), index_file(&versions));
+
+    anon.get::<()>("/api/v1/crates/foo/2.0.0/download")
+        .assert_not_found();
+    anon.get::<()>("/api/v1/crates/bar/1.0.0/download")
+        .assert_not_found();
+}
+
+#[test]
+fn crate_files_are_verified() {
+    let upstream = Upstream::start();
+    let (app, anon) = mirror_app(&upstream);
+
+    // The index file has the checksum of a different crate file
+    upstream.add_file(
+        "/3/f/foo",
+        index_file(&[("1.0.0", FOO_1_0_0), ("1.1.0", FOO_1_0_0)]),
+    );
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.1.0/download");
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(app.stored_files(), ["index/3/f/foo"]);
+}
+
+#[test]
+fn mirror_is_read_only() {
+    let upstream = Upstream::start();
+    let upstream_index = format!("{}/", upstream.url);
+    let (_, _, _, token) = TestApp::init()
+        .with_config(|config| config.mirror = Some(MirrorConfig { upstream_index }))
+        .with_token();
+
+    let response = token.put::<()>("/api/v1/crates/new", b"" as &[u8]);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let detail = format!("this registry is a read-only mirror of {}/", upstream.url);
+    let expected = json!({ "errors": [{ "detail": detail }] });
+    assert_eq!(response.into_text(), expected.to_string());
+
+    let response = token.get::<()>("/api/v1/crates");
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,140 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::config;
+use crates_io::models::token::EndpointScope;
+use http::{header, StatusCode};
+
+fn private_registry(config: &mut config::Server) {
+    config.private_registry = true;
+}
+
+#[test]
+fn index_config_requires_auth() {
+    let (_, anon, _, token) = TestApp::full().with_config(private_registry).with_token();
+
+    let response = anon.get::<()>("/index/config.json");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers()[header::WWW_AUTHENTICATE],
+        r#"Cargo login_url="https://crates.io/settings/tokens""#
+    );
+
+    let json = token.get::<()>("/index/config.json").into_json();
+    assert_eq!(
+        json,
+        json!({
+            "dl": "https://crates.io/api/v1/crates",
+            "api": "https://crates.io",
+            "auth-required": true,
+        })
+    );
+}
+
+#[test]
+fn index_files_require_auth() {
+    let (_, anon, _, token) = TestApp::full().with_config(private_registry).with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    let response = anon.get::<()>("/index/3/f/foo");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = token.get::<()>("/index/3/f/foo");
+    assert_eq!(response.status(), StatusCode::OK);
+    let content = response.into_text();
+    let entry: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+    assert_eq!(entry["name"], "foo");
+    assert_eq!(entry["vers"], "1.0.0");
+
+    token.get::<()>("/index/3/b/bar").assert_not_found();
+}
+
+#[test]
+fn downloads_require_auth_and_are_served_directly() {
+    let (app, anon, _, token) = TestApp::full().with_config(private_registry).with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    anon.get::<()>("/api/v1/crates/foo/1.0.0/download")
+        .assert_forbidden();
+
+    let response = token.get::<()>("/api/v1/crates/foo/1.0.0/download");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/gzip");
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let storage = &app.as_inner().storage;
+    let stored = rt.block_on(storage.download_crate_file("foo", "1.0.0"));
+    assert_eq!(response.into_bytes(), stored.unwrap());
+
+    token
+        .get::<()>("/api/v1/crates/foo/2.0.0/download")
+        .assert_not_found();
+}
+
+#[test]
+fn api_reads_require_auth() {
+    let (app, anon, user, token) = TestApp::full().with_config(private_registry).with_token();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+    });
+
+    anon.get::<()>("/api/v1/crates").assert_forbidden();
+    anon.get::<()>("/api/v1/crates/foo").assert_forbidden();
+
+    assert_eq!(user.get::<()>("/api/v1/crates").status(), StatusCode::OK);
+    assert_eq!(
+        token.get::<()>("/api/v1/crates/foo").status(),
+        StatusCode::OK
+    );
+
+    // The website needs these to log in
+    let response = anon.get::<()>("/api/v1/site_metadata");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn read_scope_allows_reads() {
+    let endpoint_scopes = Some(vec![EndpointScope::Read]);
+    let (_, _, user, token) = TestApp::full()
+        .with_config(private_registry)
+        .with_scoped_token(None, endpoint_scopes);
+
+    user.publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    assert_eq!(token.get::<()>("/index/3/f/foo").status(), StatusCode::OK);
+    let response = token.get::<()>("/api/v1/crates/foo/1.0.0/download");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(token.get::<()>("/api/v1/crates").status(), StatusCode::OK);
+
+    // ... but can't be used to publish
+    let crate_to_publish = PublishBuilder::new("bar", "1.0.0");
+    let response = token.publish_crate(crate_to_publish);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn other_scopes_do_not_allow_reads() {
+    let endpoint_scopes = Some(vec![EndpointScope::PublishNew]);
+    let (_, _, _, token) = TestApp::full()
+        .with_config(private_registry)
+        .with_scoped_token(None, endpoint_scopes);
+
+    let response = token.get::<()>("/index/config.json");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    token.get::<()>("/api/v1/crates").assert_forbidden();
+}
+
+#[test]
+fn public_registry_does_not_serve_the_index() {
+    let (_, anon) = TestApp::init().empty();
+    anon.get::<()>("/index/config.json").assert_not_found();
+}
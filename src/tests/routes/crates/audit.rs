@@ -0,0 +1,120 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{MockCookieUser, RequestHelper, TestApp};
+use crates_io::models::{insert_crate_audit_action, CrateAction};
+use http::StatusCode;
+
+const URL: &str = "/api/v1/crates/foo/audit";
+const OWNERS_URL: &str = "/api/v1/crates/foo/owners";
+
+#[test]
+fn audit_log_records_owner_changes() {
+    let (app, _, user) = TestApp::init().with_user();
+    let krate = app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let new_owner = app.db_new_user("new-owner");
+    let other_user = app.db_new_user("other-user");
+
+    let response = user.put::<()>(OWNERS_URL, r#"{"owners":["new-owner","other-user"]}"#);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let accept = |user: &MockCookieUser, accepted: bool| {
+        let body = json!({ "crate_owner_invite": { "crate_id": krate.id, "accepted": accepted } });
+        let url = format!("/api/v1/me/crate_owner_invitations/{}", krate.id);
+        let response = user.put::<()>(&url, body.to_string());
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+    accept(&new_owner, true);
+    accept(&other_user, false);
+
+    let response = user.delete_with_body::<()>(OWNERS_URL, r#"{"owners":["new-owner"]}"#);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = user.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".audit_actions[].id" => insta::any_id_redaction(),
+        ".audit_actions[].time" => "[datetime]",
+        ".audit_actions[].user.id" => insta::any_id_redaction(),
+    });
+}
+
+#[test]
+fn audit_log_records_token_changes() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("bar", user.as_model().id).expect_build(conn);
+    });
+
+    let body = json!({ "api_token": { "name": "unscoped" } });
+    let response = user.put::<()>("/api/v1/me/tokens", body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    let token_id = response.into_json()["api_token"]["id"].as_i64().unwrap();
+
+    let body = json!({ "api_token": { "name": "scoped", "crate_scopes": ["bar"] } });
+    let response = user.put::<()>("/api/v1/me/tokens", body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/api/v1/me/tokens/{token_id}");
+    assert_eq!(user.delete::<()>(&url).status(), StatusCode::OK);
+
+    // Revoking an already revoked token is not recorded again
+    assert_eq!(user.delete::<()>(&url).status(), StatusCode::OK);
+
+    let response = user.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".audit_actions[].id" => insta::any_id_redaction(),
+        ".audit_actions[].time" => "[datetime]",
+        ".audit_actions[].user.id" => insta::any_id_redaction(),
+        ".audit_actions[].details.token_id" => insta::any_id_redaction(),
+    });
+}
+
+#[test]
+fn audit_log_includes_admin_actions() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+
+        let details = json!({ "versions": ["1.0.0"] });
+        let action = CrateAction::AdminDeleteVersion;
+        insert_crate_audit_action(conn, "foo", None, None, action, details).unwrap();
+    });
+
+    let response = user.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".audit_actions[].id" => insta::any_id_redaction(),
+        ".audit_actions[].time" => "[datetime]",
+    });
+}
+
+#[test]
+fn audit_log_requires_authentication() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    anon.get::<()>(URL).assert_forbidden();
+}
+
+#[test]
+fn audit_log_requires_ownership() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let other = app.db_new_user("other");
+    let response = other.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "only owners have permission to read the audit log" }] })
+    );
+}
+
+#[test]
+fn audit_log_for_unknown_crate() {
+    let (_, _, user) = TestApp::init().with_user();
+    user.get::<()>(URL).assert_not_found();
+}
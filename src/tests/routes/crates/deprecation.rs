@@ -0,0 +1,174 @@
+use crate::builders::CrateBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::DeprecationState;
+use http::StatusCode;
+use insta::assert_snapshot;
+
+const URL: &str = "/api/v1/crates/foo/deprecation";
+
+#[test]
+fn deprecate_and_undeprecate() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-ng", user.as_model().id).expect_build(conn);
+    });
+
+    let json = anon.show_crate("foo");
+    assert_none!(json.krate.deprecation);
+
+    let body =
+        json!({ "state": "deprecated", "message": "  use foo-ng instead ", "successor": "Foo_NG" });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "ok": true }));
+
+    let json = anon.show_crate("foo");
+    let deprecation = assert_some!(json.krate.deprecation);
+    assert_eq!(deprecation.state, DeprecationState::Deprecated);
+    assert_some_eq!(deprecation.message, "use foo-ng instead");
+    assert_some_eq!(deprecation.successor, "foo-ng");
+
+    // Setting the deprecation again replaces the previous state
+    let body = json!({ "state": "unmaintained" });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = anon.show_crate("foo");
+    let deprecation = assert_some!(json.krate.deprecation);
+    assert_eq!(deprecation.state, DeprecationState::Unmaintained);
+    assert_none!(deprecation.message);
+    assert_none!(deprecation.successor);
+
+    let response = user.delete::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "ok": true }));
+
+    let json = anon.show_crate("foo");
+    assert_none!(json.krate.deprecation);
+
+    // Removing the deprecation of a crate that is not deprecated is a no-op
+    let response = user.delete::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let actions = user.get::<()>("/api/v1/crates/foo/audit").into_json();
+    let actions = actions["audit_actions"].as_array().unwrap();
+    let actions = actions
+        .iter()
+        .map(|action| action["action"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(actions, ["undeprecate", "deprecate", "deprecate"]);
+}
+
+#[test]
+fn search_excludes_deprecated_crates() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+        CrateBuilder::new("foo-ng", user.as_model().id).expect_build(conn);
+    });
+
+    let body = json!({ "state": "deprecated", "successor": "foo-ng" });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = anon.search("");
+    assert_eq!(json.meta.total, 2);
+    let deprecated = json
+        .crates
+        .iter()
+        .find(|krate| krate.name == "foo")
+        .unwrap();
+    let deprecation = assert_some!(deprecated.deprecation.as_ref());
+    assert_some_eq!(deprecation.successor.as_deref(), "foo-ng");
+
+    let json = anon.search("include_deprecated=yes");
+    assert_eq!(json.meta.total, 2);
+
+    let json = anon.search("include_deprecated=no");
+    assert_eq!(json.meta.total, 1);
+    assert_eq!(json.crates[0].name, "foo-ng");
+
+    let json = anon.search("q=foo&include_deprecated=no");
+    assert_eq!(json.meta.total, 1);
+    assert_eq!(json.crates[0].name, "foo-ng");
+}
+
+#[test]
+fn invalid_deprecation_requests() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+    });
+
+    let response = user.put::<()>(URL, r#"{"state":"obsolete"}"#);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text());
+
+    let body = json!({ "state": "deprecated", "successor": "bar" });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "successor crate `bar` does not exist" }] })
+    );
+
+    let body = json!({ "state": "deprecated", "successor": "foo" });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "a crate can not be its own successor" }] })
+    );
+
+    let body = json!({ "state": "deprecated", "message": "a".repeat(501) });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "deprecation message must not be longer than 500 characters" }] })
+    );
+}
+
+#[test]
+fn only_owners_can_deprecate() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+    });
+
+    let body = json!({ "state": "deprecated" }).to_string();
+
+    let response = anon.put::<()>(URL, body.clone());
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let other_user = app.db_new_user("other-user");
+    let response = other_user.put::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "only owners have permission to deprecate this crate" }] })
+    );
+
+    let response = other_user.delete::<()>(URL);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let json = anon.show_crate("foo");
+    assert_none!(json.krate.deprecation);
+}
+
+#[test]
+fn deprecate_with_token() {
+    let (app, anon, user, token) = TestApp::init().with_token();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id).expect_build(conn);
+    });
+
+    let body = json!({ "state": "unmaintained" });
+    let response = token.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = anon.show_crate("foo");
+    let deprecation = assert_some!(json.krate.deprecation);
+    assert_eq!(deprecation.state, DeprecationState::Unmaintained);
+}
@@ -1,3 +1,5 @@
+mod audit;
+mod deprecation;
 pub mod downloads;
 mod following;
 mod list;
@@ -5,4 +7,6 @@ mod new;
 pub mod owners;
 mod read;
 mod reverse_dependencies;
+mod trusted_publishers;
 pub mod versions;
+mod webhooks;
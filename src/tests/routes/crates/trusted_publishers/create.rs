@@ -0,0 +1,109 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{RequestHelper, TestApp};
+use http::StatusCode;
+use serde_json::Value;
+
+const URL: &str = "/api/v1/crates/foo/trusted_publishers";
+
+static NEW_PUBLISHER: &[u8] = br#"{ "trusted_publisher": { "repository": "rust-lang/foo", "workflow_filename": "release.yml" } }"#;
+
+#[test]
+fn create_trusted_publisher() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let response = user.put::<()>(URL, NEW_PUBLISHER);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".trusted_publisher.id" => insta::any_id_redaction(),
+        ".trusted_publisher.created_at" => "[datetime]",
+    });
+}
+
+#[test]
+fn create_trusted_publisher_with_environment() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let body = json!({
+        "trusted_publisher": {
+            "repository": "rust-lang/foo",
+            "workflow_filename": "release.yml",
+            "environment": "release",
+        }
+    });
+
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_json()["trusted_publisher"]["environment"],
+        "release"
+    );
+}
+
+#[test]
+fn create_trusted_publisher_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    anon.put::<()>(URL, NEW_PUBLISHER).assert_forbidden();
+}
+
+#[test]
+fn create_trusted_publisher_with_token() {
+    let (app, _, user, token) = TestApp::init().with_token();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    token.put::<()>(URL, NEW_PUBLISHER).assert_forbidden();
+}
+
+#[test]
+fn create_trusted_publisher_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let other = app.db_new_user("other");
+    let response = other.put::<()>(URL, NEW_PUBLISHER);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "only owners have permission to manage trusted publishers" }] })
+    );
+}
+
+#[test]
+fn create_trusted_publisher_for_unknown_crate() {
+    let (_, _, user) = TestApp::init().with_user();
+
+    let response = user.put::<()>(URL, NEW_PUBLISHER);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn create_trusted_publisher_with_invalid_fields() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let assert_error = |body: Value, expected: &str| {
+        let response = user.put::<()>(URL, body.to_string());
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.into_json(),
+            json!({ "errors": [{ "detail": expected }] })
+        );
+    };
+
+    assert_error(
+        json!({ "trusted_publisher": { "repository": "foo", "workflow_filename": "release.yml" } }),
+        "repository must be in `owner/name` format",
+    );
+    assert_error(
+        json!({ "trusted_publisher": { "repository": "rust-lang/foo", "workflow_filename": ".github/workflows/release.yml" } }),
+        "workflow_filename must be the name of a `.yml` or `.yaml` file",
+    );
+    assert_error(
+        json!({ "trusted_publisher": { "repository": "rust-lang/foo", "workflow_filename": "release.yml", "environment": " " } }),
+        "environment must not be empty",
+    );
+}
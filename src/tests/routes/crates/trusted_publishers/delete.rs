@@ -0,0 +1,76 @@
+use crate::builders::CrateBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{NewTrustedPublisher, TrustedPublisher};
+use crates_io::schema::trusted_publishers;
+use diesel::prelude::*;
+use http::StatusCode;
+
+fn create_publisher(app: &TestApp, crate_name: &str, user_id: i32) -> TrustedPublisher {
+    app.db(|conn| {
+        let krate = CrateBuilder::new(crate_name, user_id).expect_build(conn);
+
+        NewTrustedPublisher {
+            crate_id: krate.id,
+            repository: "rust-lang/foo",
+            workflow_filename: "release.yml",
+            environment: None,
+            created_by: user_id,
+        }
+        .insert(conn)
+        .unwrap()
+    })
+}
+
+fn count_publishers(app: &TestApp) -> i64 {
+    app.db(|conn| trusted_publishers::table.count().get_result(conn).unwrap())
+}
+
+#[test]
+fn delete_trusted_publisher() {
+    let (app, _, user) = TestApp::init().with_user();
+    let publisher = create_publisher(&app, "foo", user.as_model().id);
+
+    let url = format!("/api/v1/crates/foo/trusted_publishers/{}", publisher.id);
+    let response = user.delete::<()>(&url);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "ok": true }));
+
+    assert_eq!(count_publishers(&app), 0);
+}
+
+#[test]
+fn delete_trusted_publisher_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    let publisher = create_publisher(&app, "foo", user.as_model().id);
+
+    let url = format!("/api/v1/crates/foo/trusted_publishers/{}", publisher.id);
+    anon.delete::<()>(&url).assert_forbidden();
+
+    assert_eq!(count_publishers(&app), 1);
+}
+
+#[test]
+fn delete_trusted_publisher_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    let publisher = create_publisher(&app, "foo", user.as_model().id);
+
+    let other = app.db_new_user("other");
+    let url = format!("/api/v1/crates/foo/trusted_publishers/{}", publisher.id);
+    let response = other.delete::<()>(&url);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    assert_eq!(count_publishers(&app), 1);
+}
+
+#[test]
+fn delete_trusted_publisher_of_other_crate() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+    let publisher = create_publisher(&app, "foo", user_id);
+    app.db(|conn| CrateBuilder::new("bar", user_id).expect_build(conn));
+
+    let url = format!("/api/v1/crates/bar/trusted_publishers/{}", publisher.id);
+    user.delete::<()>(&url).assert_not_found();
+
+    assert_eq!(count_publishers(&app), 1);
+}
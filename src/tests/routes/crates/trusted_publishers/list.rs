@@ -0,0 +1,59 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::NewTrustedPublisher;
+use http::StatusCode;
+
+const URL: &str = "/api/v1/crates/foo/trusted_publishers";
+
+#[test]
+fn list_trusted_publishers() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+
+    app.db(|conn| {
+        let krate = CrateBuilder::new("foo", user_id).expect_build(conn);
+        let other = CrateBuilder::new("bar", user_id).expect_build(conn);
+
+        for (crate_id, environment) in [
+            (krate.id, None),
+            (krate.id, Some("release")),
+            (other.id, None),
+        ] {
+            NewTrustedPublisher {
+                crate_id,
+                repository: "rust-lang/foo",
+                workflow_filename: "release.yml",
+                environment,
+                created_by: user_id,
+            }
+            .insert(conn)
+            .unwrap();
+        }
+    });
+
+    let response = user.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".trusted_publishers[].id" => insta::any_id_redaction(),
+        ".trusted_publishers[].created_at" => "[datetime]",
+    });
+}
+
+#[test]
+fn list_trusted_publishers_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    anon.get::<()>(URL).assert_forbidden();
+}
+
+#[test]
+fn list_trusted_publishers_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let other = app.db_new_user("other");
+    let response = other.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
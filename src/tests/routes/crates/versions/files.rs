@@ -0,0 +1,74 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::{RequestHelper, TestApp};
+use http::{header, StatusCode};
+use insta::{assert_json_snapshot, assert_snapshot};
+
+#[test]
+fn list_and_show_files() {
+    let (_, anon, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("foo", "1.0.0")
+        .add_file("foo-1.0.0/src/lib.rs", "pub fn foo() {}\n")
+        .add_file(
+            "foo-1.0.0/assets/logo.png",
+            &[0x89, 0x50, 0x4e, 0x47, 0xff][..],
+        );
+    token.publish_crate(crate_to_publish).good();
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.0.0/files");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.0.0/files/src/lib.rs");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/plain; charset=utf-8"
+    );
+    assert_eq!(response.into_text(), "pub fn foo() {}\n");
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.0.0/files/assets/logo.png");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/octet-stream"
+    );
+}
+
+#[test]
+fn show_missing_files() {
+    let (_, anon, _, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    for path in [
+        "src/main.rs",
+        "src",
+        "../foo-1.0.0/Cargo.toml",
+        "%2e%2e/Cargo.toml",
+    ] {
+        let url = format!("/api/v1/crates/foo/1.0.0/files/{path}");
+        let response = anon.get::<()>(&url);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{path}");
+    }
+
+    let response = anon.get::<()>("/api/v1/crates/foo/2.0.0/files/Cargo.toml");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"crate `foo` does not have a version `2.0.0`"}]}"###);
+}
+
+#[test]
+fn file_listing_not_available_yet() {
+    let (app, anon, user) = TestApp::init().with_user();
+
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let response = anon.get::<()>("/api/v1/crates/foo/1.0.0/files");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -1,5 +1,6 @@
 mod authors;
 pub mod dependencies;
 pub mod download;
+mod files;
 mod read;
 pub mod yank_unyank;
@@ -1,12 +1,18 @@
 use crate::builders::{CrateBuilder, PublishBuilder};
 use crate::util::{RequestHelper, Response, TestApp};
 use crate::OkBool;
+use crates_io::models::YankCategory;
 use http::StatusCode;
+use insta::assert_snapshot;
 
 pub trait YankRequestHelper {
     /// Yank the specified version of the specified crate and run all pending background jobs
     fn yank(&self, krate_name: &str, version: &str) -> Response<OkBool>;
 
+    /// Yank the specified version of the specified crate with the given
+    /// request body and run all pending background jobs
+    fn yank_with_reason(&self, krate_name: &str, version: &str, body: &str) -> Response<OkBool>;
+
     /// Unyank the specified version of the specified crate and run all pending background jobs
     fn unyank(&self, krate_name: &str, version: &str) -> Response<OkBool>;
 }
@@ -19,6 +25,13 @@ impl<T: RequestHelper> YankRequestHelper for T {
         response
     }
 
+    fn yank_with_reason(&self, krate_name: &str, version: &str, body: &str) -> Response<OkBool> {
+        let url = format!("/api/v1/crates/{krate_name}/{version}/yank");
+        let response = self.delete_with_body(&url, body.to_string());
+        self.app().run_pending_background_jobs();
+        response
+    }
+
     fn unyank(&self, krate_name: &str, version: &str) -> Response<OkBool> {
         let url = format!("/api/v1/crates/{krate_name}/{version}/unyank");
         let response = self.put(&url, &[] as &[u8]);
@@ -92,6 +105,113 @@ fn unyank_records_an_audit_action() {
     assert_eq!(action.user.id, token.as_model().user_id);
 }
 
+#[test]
+fn yank_with_reason() {
+    let (app, anon, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("fyk", "1.0.0");
+    token.publish_crate(crate_to_publish).good();
+
+    let json = anon.show_version("fyk", "1.0.0");
+    assert_none!(json.version.yank_reason);
+
+    let body = json!({ "category": "security", "message": "  RUSTSEC-2023-0001  " });
+    token
+        .yank_with_reason("fyk", "1.0.0", &body.to_string())
+        .good();
+
+    let json = anon.show_version("fyk", "1.0.0");
+    assert!(json.version.yanked);
+    let reason = assert_some!(json.version.yank_reason);
+    assert_eq!(reason.category, YankCategory::Security);
+    assert_some_eq!(reason.message, "RUSTSEC-2023-0001");
+
+    let crates = app.crates_from_index_head("fyk");
+    assert_eq!(crates.len(), 1);
+    let reason = assert_some!(&crates[0].yank_reason);
+    assert_eq!(reason.category, "security");
+    assert_some_eq!(&reason.message, "RUSTSEC-2023-0001");
+
+    // the reason of an already yanked version can be updated
+    let body = json!({ "category": "broken_build" });
+    token
+        .yank_with_reason("fyk", "1.0.0", &body.to_string())
+        .good();
+
+    let json = anon.show_version("fyk", "1.0.0");
+    let reason = assert_some!(json.version.yank_reason);
+    assert_eq!(reason.category, YankCategory::BrokenBuild);
+    assert_none!(reason.message);
+    assert_eq!(json.version.audit_actions.len(), 3);
+
+    let crates = app.crates_from_index_head("fyk");
+    let reason = assert_some!(&crates[0].yank_reason);
+    assert_eq!(reason.category, "broken_build");
+    assert_none!(&reason.message);
+
+    // unyanking removes the reason again
+    token.unyank("fyk", "1.0.0").good();
+
+    let json = anon.show_version("fyk", "1.0.0");
+    assert_none!(json.version.yank_reason);
+
+    let crates = app.crates_from_index_head("fyk");
+    assert_none!(&crates[0].yank_reason);
+}
+
+#[test]
+fn yank_without_reason_after_yank_with_reason() {
+    let (app, anon, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("fyk", "1.0.0");
+    token.publish_crate(crate_to_publish).good();
+
+    let body = json!({ "category": "mistake" });
+    token
+        .yank_with_reason("fyk", "1.0.0", &body.to_string())
+        .good();
+    token.unyank("fyk", "1.0.0").good();
+    token.yank("fyk", "1.0.0").good();
+
+    let json = anon.show_version("fyk", "1.0.0");
+    assert!(json.version.yanked);
+    assert_none!(json.version.yank_reason);
+
+    let crates = app.crates_from_index_head("fyk");
+    assert_none!(&crates[0].yank_reason);
+}
+
+#[test]
+fn yank_with_invalid_reason() {
+    let (app, anon, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("fyk", "1.0.0");
+    token.publish_crate(crate_to_publish).good();
+
+    let response = token.yank_with_reason("fyk", "1.0.0", "not json");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_snapshot!(response.into_text());
+
+    let body = json!({ "category": "boredom" });
+    let response = token.yank_with_reason("fyk", "1.0.0", &body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_snapshot!(response.into_text());
+
+    let body = json!({ "category": "other", "message": "a".repeat(501) });
+    let response = token.yank_with_reason("fyk", "1.0.0", &body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "yank message must not be longer than 500 characters" }] })
+    );
+
+    let json = anon.show_version("fyk", "1.0.0");
+    assert!(!json.version.yanked);
+
+    let crates = app.crates_from_index_head("fyk");
+    assert_some_eq!(crates[0].yanked, false);
+}
+
 mod auth {
     use super::*;
     use crate::util::{MockAnonymousUser, MockCookieUser};
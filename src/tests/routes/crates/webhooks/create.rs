@@ -0,0 +1,118 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::schema::webhooks;
+use diesel::prelude::*;
+use http::StatusCode;
+use serde_json::Value;
+
+const URL: &str = "/api/v1/crates/foo/webhooks";
+
+static NEW_WEBHOOK: &[u8] =
+    br#"{ "webhook": { "url": "https://example.com/hook", "events": ["publish", "yank"] } }"#;
+
+#[test]
+fn create_webhook() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let response = user.put::<()>(URL, NEW_WEBHOOK);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.into_json();
+    let secret = json["webhook"]["secret"].as_str().unwrap();
+    assert_eq!(secret.len(), 32);
+
+    let stored_secret: String = app.db(|conn| {
+        webhooks::table
+            .select(webhooks::secret)
+            .first(conn)
+            .unwrap()
+    });
+    assert_eq!(stored_secret, secret);
+
+    assert_json_snapshot!(json, {
+        ".webhook.id" => insta::any_id_redaction(),
+        ".webhook.secret" => "[secret]",
+        ".webhook.created_at" => "[datetime]",
+    });
+}
+
+#[test]
+fn create_webhook_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    anon.put::<()>(URL, NEW_WEBHOOK).assert_forbidden();
+}
+
+#[test]
+fn create_webhook_with_token() {
+    let (app, _, user, token) = TestApp::init().with_token();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    token.put::<()>(URL, NEW_WEBHOOK).assert_forbidden();
+}
+
+#[test]
+fn create_webhook_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let other = app.db_new_user("other");
+    let response = other.put::<()>(URL, NEW_WEBHOOK);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "only owners have permission to manage webhooks" }] })
+    );
+}
+
+#[test]
+fn create_webhook_with_invalid_fields() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let assert_error = |body: Value, expected: &str| {
+        let response = user.put::<()>(URL, body.to_string());
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.into_json(),
+            json!({ "errors": [{ "detail": expected }] })
+        );
+    };
+
+    assert_error(
+        json!({ "webhook": { "url": "example.com/hook", "events": ["publish"] } }),
+        "url must be a valid `https://` URL",
+    );
+    assert_error(
+        json!({ "webhook": { "url": "ftp://example.com/hook", "events": ["publish"] } }),
+        "url must be a valid `https://` URL",
+    );
+    assert_error(
+        json!({ "webhook": { "url": "https://example.com/hook", "events": [] } }),
+        "at least one webhook event is required",
+    );
+    assert_error(
+        json!({ "webhook": { "url": "https://example.com/hook", "events": ["delete"] } }),
+        "invalid webhook event",
+    );
+}
+
+#[test]
+fn create_too_many_webhooks() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    for _ in 0..10 {
+        assert_eq!(user.put::<()>(URL, NEW_WEBHOOK).status(), StatusCode::OK);
+    }
+
+    let response = user.put::<()>(URL, NEW_WEBHOOK);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "crates can only have up to 10 webhooks" }] })
+    );
+}
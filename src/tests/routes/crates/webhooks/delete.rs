@@ -0,0 +1,75 @@
+use crate::builders::CrateBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{NewWebhook, Webhook, WebhookEvent};
+use crates_io::schema::webhooks;
+use diesel::prelude::*;
+use http::StatusCode;
+
+fn create_webhook(app: &TestApp, crate_name: &str, user_id: i32) -> Webhook {
+    app.db(|conn| {
+        let krate = CrateBuilder::new(crate_name, user_id).expect_build(conn);
+
+        NewWebhook::new(
+            krate.id,
+            "https://example.com/hook",
+            &[WebhookEvent::Publish],
+            user_id,
+        )
+        .insert(conn)
+        .unwrap()
+    })
+}
+
+fn count_webhooks(app: &TestApp) -> i64 {
+    app.db(|conn| webhooks::table.count().get_result(conn).unwrap())
+}
+
+#[test]
+fn delete_webhook() {
+    let (app, _, user) = TestApp::init().with_user();
+    let webhook = create_webhook(&app, "foo", user.as_model().id);
+
+    let url = format!("/api/v1/crates/foo/webhooks/{}", webhook.id);
+    let response = user.delete::<()>(&url);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "ok": true }));
+
+    assert_eq!(count_webhooks(&app), 0);
+}
+
+#[test]
+fn delete_webhook_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    let webhook = create_webhook(&app, "foo", user.as_model().id);
+
+    let url = format!("/api/v1/crates/foo/webhooks/{}", webhook.id);
+    anon.delete::<()>(&url).assert_forbidden();
+
+    assert_eq!(count_webhooks(&app), 1);
+}
+
+#[test]
+fn delete_webhook_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    let webhook = create_webhook(&app, "foo", user.as_model().id);
+
+    let other = app.db_new_user("other");
+    let url = format!("/api/v1/crates/foo/webhooks/{}", webhook.id);
+    let response = other.delete::<()>(&url);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    assert_eq!(count_webhooks(&app), 1);
+}
+
+#[test]
+fn delete_webhook_of_other_crate() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+    let webhook = create_webhook(&app, "foo", user_id);
+    app.db(|conn| CrateBuilder::new("bar", user_id).expect_build(conn));
+
+    let url = format!("/api/v1/crates/bar/webhooks/{}", webhook.id);
+    user.delete::<()>(&url).assert_not_found();
+
+    assert_eq!(count_webhooks(&app), 1);
+}
@@ -0,0 +1,84 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{NewWebhook, WebhookEvent};
+use http::StatusCode;
+
+const URL: &str = "/api/v1/crates/foo/webhooks";
+
+#[test]
+fn list_webhooks() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+
+    app.db(|conn| {
+        let krate = CrateBuilder::new("foo", user_id).expect_build(conn);
+        let other = CrateBuilder::new("bar", user_id).expect_build(conn);
+
+        for (crate_id, events) in [
+            (krate.id, &[WebhookEvent::Publish][..]),
+            (
+                krate.id,
+                &[WebhookEvent::OwnerChange, WebhookEvent::NewDependent][..],
+            ),
+            (other.id, &[WebhookEvent::Publish][..]),
+        ] {
+            NewWebhook::new(crate_id, "https://example.com/hook", events, user_id)
+                .insert(conn)
+                .unwrap();
+        }
+    });
+
+    let response = user.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".webhooks[].id" => insta::any_id_redaction(),
+        ".webhooks[].created_at" => "[datetime]",
+    });
+}
+
+#[test]
+fn list_webhooks_logged_out() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    anon.get::<()>(URL).assert_forbidden();
+}
+
+#[test]
+fn list_webhooks_as_non_owner() {
+    let (app, _, user) = TestApp::init().with_user();
+    app.db(|conn| CrateBuilder::new("foo", user.as_model().id).expect_build(conn));
+
+    let other = app.db_new_user("other");
+    let response = other.get::<()>(URL);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn list_deliveries_of_other_crate() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+
+    let webhook = app.db(|conn| {
+        let krate = CrateBuilder::new("foo", user_id).expect_build(conn);
+        CrateBuilder::new("bar", user_id).expect_build(conn);
+
+        NewWebhook::new(
+            krate.id,
+            "https://example.com/hook",
+            &[WebhookEvent::Publish],
+            user_id,
+        )
+        .insert(conn)
+        .unwrap()
+    });
+
+    let url = format!("/api/v1/crates/foo/webhooks/{}/deliveries", webhook.id);
+    let response = user.get::<()>(&url);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "deliveries": [] }));
+
+    let url = format!("/api/v1/crates/bar/webhooks/{}/deliveries", webhook.id);
+    user.get::<()>(&url).assert_not_found();
+}
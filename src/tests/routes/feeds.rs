@@ -0,0 +1,134 @@
+use crate::builders::PublishBuilder;
+use crate::routes::crates::versions::yank_unyank::YankRequestHelper;
+use crate::util::{MockCookieUser, RequestHelper, Response, TestApp};
+use crate::OkBool;
+use crates_io::views::atom;
+use http::{header, StatusCode};
+
+/// Checks the content type of the feed and returns its XML with the
+/// timestamps redacted.
+#[track_caller]
+fn feed_xml(response: Response<()>) -> String {
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], atom::CONTENT_TYPE);
+
+    response
+        .into_text()
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            match ["published", "updated"]
+                .iter()
+                .find(|name| trimmed.starts_with(&format!("<{name}>")))
+            {
+                Some(name) => format!("{indent}<{name}>[datetime]</{name}>\n"),
+                None => format!("{line}\n"),
+            }
+        })
+        .collect()
+}
+
+fn create_feed_token(user: &MockCookieUser) -> String {
+    let json = user
+        .put::<()>("/api/v1/me/feed_token", b"" as &[u8])
+        .into_json();
+    json["feed_token"]["token"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn crate_releases() {
+    let (_, anon, user, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("bar", "1.0.0"))
+        .good();
+    user.yank("foo", "1.0.0").good();
+
+    let response = anon.get("/api/v1/crates/foo/releases.atom");
+    insta::assert_snapshot!(feed_xml(response));
+}
+
+#[test]
+fn crate_releases_of_unknown_crate() {
+    let (_, anon) = TestApp::init().empty();
+    anon.get::<()>("/api/v1/crates/foo/releases.atom")
+        .assert_not_found();
+}
+
+#[test]
+fn new_crates() {
+    let (_, anon, _, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("bar", "2.0.0"))
+        .good();
+
+    let response = anon.get("/api/v1/feeds/crates.atom");
+    insta::assert_snapshot!(feed_xml(response));
+}
+
+#[test]
+fn updates() {
+    let (_, anon, user, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("bar", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+
+    user.put::<OkBool>("/api/v1/crates/foo/follow", b"" as &[u8])
+        .good();
+
+    let feed_token = create_feed_token(&user);
+    let query = format!("token={feed_token}");
+
+    // Feed readers use the feed token, not the session cookie
+    let response = anon.get_with_query("/api/v1/feeds/updates.atom", &query);
+    insta::assert_snapshot!(feed_xml(response));
+}
+
+#[test]
+fn updates_requires_valid_token() {
+    let (_, anon, user) = TestApp::init().with_user();
+
+    user.get::<()>("/api/v1/feeds/updates.atom")
+        .assert_forbidden();
+    anon.get_with_query::<()>("/api/v1/feeds/updates.atom", "token=invalid")
+        .assert_forbidden();
+
+    let feed_token = create_feed_token(&user);
+    let query = format!("token={feed_token}");
+    let response = anon.get_with_query::<()>("/api/v1/feeds/updates.atom", &query);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Regenerating the feed token invalidates the previous one
+    let new_feed_token = create_feed_token(&user);
+    anon.get_with_query::<()>("/api/v1/feeds/updates.atom", &query)
+        .assert_forbidden();
+
+    let query = format!("token={new_feed_token}");
+    let response = anon.get_with_query::<()>("/api/v1/feeds/updates.atom", &query);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    user.delete::<OkBool>("/api/v1/me/feed_token").good();
+    anon.get_with_query::<()>("/api/v1/feeds/updates.atom", &query)
+        .assert_forbidden();
+}
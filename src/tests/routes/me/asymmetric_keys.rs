@@ -0,0 +1,73 @@
+use crate::util::{RequestHelper, TestApp};
+use crates_io::util::asymmetric_token::PublicKey;
+use http::StatusCode;
+use insta::assert_snapshot;
+use p384::ecdsa::SigningKey;
+
+const URL: &str = "/api/v1/me/asymmetric_keys";
+
+fn public_key(seed: u8) -> String {
+    let key = SigningKey::from_bytes(&[seed; 48].into()).unwrap();
+    PublicKey::from(&key).to_paserk()
+}
+
+#[test]
+fn create_list_and_revoke() {
+    let (_, _, user) = TestApp::init().with_user();
+
+    let json = user.get::<()>(URL).into_json();
+    assert_eq!(json, json!({ "asymmetric_keys": [] }));
+
+    let body = json!({ "asymmetric_key": { "name": "laptop", "public_key": public_key(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.into_json();
+    assert_eq!(json["asymmetric_key"]["name"], "laptop");
+    assert_eq!(json["asymmetric_key"]["public_key"], public_key(1));
+    assert!(json["asymmetric_key"]["key_id"]
+        .as_str()
+        .unwrap()
+        .starts_with("k3.pid."));
+    assert_eq!(json["asymmetric_key"]["last_used_at"], json!(null));
+
+    let json = user.get::<()>(URL).into_json();
+    let keys = json["asymmetric_keys"].as_array().unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0]["name"], "laptop");
+
+    let id = keys[0]["id"].as_i64().unwrap();
+    let response = user.delete::<()>(&format!("{URL}/{id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = user.get::<()>(URL).into_json();
+    assert_eq!(json, json!({ "asymmetric_keys": [] }));
+}
+
+#[test]
+fn invalid_asymmetric_keys() {
+    let (app, anon, user) = TestApp::init().with_user();
+
+    let body = json!({ "asymmetric_key": { "name": "laptop", "public_key": public_key(1) } });
+    let response = anon.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "asymmetric_key": { "name": " ", "public_key": public_key(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"name must have a value"}]}"###);
+
+    let body = json!({ "asymmetric_key": { "name": "laptop", "public_key": "k4.public.AAAA" } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"public_key must be an ECDSA P-384 public key in PASERK `k3.public.` format"}]}"###);
+
+    let body = json!({ "asymmetric_key": { "name": "laptop", "public_key": public_key(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The same key can not be registered twice, not even by another user
+    let other_user = app.db_new_user("other");
+    let response = other_user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"this public key has already been registered"}]}"###);
+}
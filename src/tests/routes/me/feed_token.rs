@@ -0,0 +1,62 @@
+use crate::util::insta::assert_json_snapshot;
+use crate::util::{RequestHelper, TestApp};
+use crate::OkBool;
+use crates_io::schema::feed_tokens;
+use diesel::prelude::*;
+
+#[test]
+fn create_feed_token() {
+    let (app, _, user) = TestApp::init().with_user();
+
+    let json = user
+        .put::<()>("/api/v1/me/feed_token", b"" as &[u8])
+        .into_json();
+    assert_json_snapshot!(json, {
+        ".feed_token.token" => "[token]",
+        ".feed_token.url" => "[url]",
+        ".feed_token.created_at" => "[datetime]",
+    });
+
+    let token = json["feed_token"]["token"].as_str().unwrap();
+    let url = json["feed_token"]["url"].as_str().unwrap();
+    assert!(url.ends_with(&format!("/api/v1/feeds/updates.atom?token={token}")));
+
+    // Only the hash of the token is stored
+    let stored: Vec<u8> = app.db(|conn| {
+        feed_tokens::table
+            .select(feed_tokens::token)
+            .first(conn)
+            .unwrap()
+    });
+    assert_ne!(stored, token.as_bytes());
+}
+
+#[test]
+fn api_token_cannot_create_feed_token() {
+    let (_, _, _, token) = TestApp::init().with_token();
+    token
+        .put::<()>("/api/v1/me/feed_token", b"" as &[u8])
+        .assert_forbidden();
+}
+
+#[test]
+fn anonymous_user_cannot_create_feed_token() {
+    let (_, anon) = TestApp::init().empty();
+    anon.put::<()>("/api/v1/me/feed_token", b"" as &[u8])
+        .assert_forbidden();
+}
+
+#[test]
+fn revoke_feed_token() {
+    let (app, _, user) = TestApp::init().with_user();
+
+    // Revoking is idempotent
+    user.delete::<OkBool>("/api/v1/me/feed_token").good();
+
+    user.put::<()>("/api/v1/me/feed_token", b"" as &[u8])
+        .into_json();
+    user.delete::<OkBool>("/api/v1/me/feed_token").good();
+
+    let count: i64 = app.db(|conn| feed_tokens::table.count().get_result(conn).unwrap());
+    assert_eq!(count, 0);
+}
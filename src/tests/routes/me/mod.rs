@@ -1,4 +1,8 @@
+mod asymmetric_keys;
 mod email_notifications;
+mod feed_token;
 pub mod get;
+mod second_factor;
+mod signing_keys;
 pub mod tokens;
 mod updates;
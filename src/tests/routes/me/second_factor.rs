@@ -0,0 +1,197 @@
+use crate::util::{
+    encode_session_data, MockCookieUser, MockRequestExt, RequestHelper, Response, TestApp,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use crates_io::models::TotpSecret;
+use crates_io::util::totp;
+use http::{header, Method, StatusCode};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::pkcs8::EncodePublicKey;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+const SECOND_FACTOR_REQUIRED: &str =
+    r#"{"errors":[{"detail":"this action requires a recent second factor verification"}]}"#;
+
+const TOKENS_URL: &str = "/api/v1/me/tokens";
+const NEW_TOKEN: &str = r#"{"api_token":{"name":"bar"}}"#;
+const VERIFY_URL: &str = "/api/private/second_factor/verify";
+const CHALLENGE: &str = "Y2hhbGxlbmdl";
+
+/// Sends a request with a cookie session that contains the given data in
+/// addition to the `user_id`, like the data that is stored in the session by
+/// previous requests of the frontend.
+fn run_with_session(
+    user: &MockCookieUser,
+    data: &[(&str, String)],
+    method: Method,
+    url: &str,
+    body: &str,
+) -> Response<()> {
+    let mut map: HashMap<String, String> = data
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+    map.insert("user_id".into(), user.as_model().id.to_string());
+
+    let cookie = encode_session_data(user.app().as_inner().session_key(), &map);
+
+    let mut request = user.request_builder(method, url);
+    request.header(header::COOKIE, &cookie);
+    *request.body_mut() = body.to_string().into();
+    user.run(request)
+}
+
+fn verified_at(minutes_ago: i64) -> (&'static str, String) {
+    let timestamp = (Utc::now() - Duration::minutes(minutes_ago)).timestamp();
+    ("second_factor_verified_at", timestamp.to_string())
+}
+
+#[test]
+fn totp_enrollment() {
+    let (app, _, user) = TestApp::init().with_user();
+    let user_id = user.as_model().id;
+
+    // Users without a second factor don't have to verify anything
+    let response = user.put::<()>(TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = user.put::<()>("/api/v1/me/totp", b"" as &[u8]);
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.into_json();
+    assert!(json["uri"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/crates.io:"));
+
+    let secret = app.db(|conn| TotpSecret::find(conn, user_id).unwrap().unwrap().secret);
+    assert_eq!(json["secret"], totp::encode_secret(&secret));
+
+    // The secret is not used before the enrollment is confirmed
+    let response = user.put::<()>(TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let step = totp::time_step(Utc::now());
+    let body = json!({ "code": totp::generate(&secret, step + 5) });
+    let response = user.put::<()>("/api/v1/me/totp/confirm", body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = json!({ "code": totp::generate(&secret, step) });
+    let response = user.put::<()>("/api/v1/me/totp/confirm", body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = user.get::<()>("/api/v1/me/second_factor").into_json();
+    assert_eq!(json["totp_enabled"], true);
+
+    // Sensitive actions now require a recent verification
+    let response = user.put::<()>(TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.into_text(), SECOND_FACTOR_REQUIRED);
+
+    let response = user.delete::<()>("/api/v1/me/totp");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Codes can only be used once
+    let body = json!({ "totp": totp::generate(&secret, step) });
+    let response = user.put::<()>(VERIFY_URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = json!({ "totp": totp::generate(&secret, step + 1) });
+    let response = user.put::<()>(VERIFY_URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(header::SET_COOKIE));
+
+    let session = [verified_at(1)];
+    let response = run_with_session(&user, &session, Method::PUT, TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Verifications expire after a while
+    let session = [verified_at(20)];
+    let response = run_with_session(&user, &session, Method::PUT, TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let session = [verified_at(1)];
+    let url = "/api/v1/me/totp";
+    let response = run_with_session(&user, &session, Method::DELETE, url, "");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = user.put::<()>(TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn webauthn_enrollment() {
+    let (_, _, user) = TestApp::init().with_user();
+
+    let json = user
+        .get::<()>("/api/private/second_factor/challenge")
+        .into_json();
+    assert_eq!(json["rp_id"], "crates.io");
+    assert_eq!(json["credential_ids"], json!([]));
+
+    let signing_key = SigningKey::from_bytes(&[1; 32].into()).unwrap();
+    let public_key = signing_key.verifying_key().to_public_key_der().unwrap();
+
+    let client_data = json!({
+        "type": "webauthn.create",
+        "challenge": CHALLENGE,
+        "origin": "https://crates.io",
+    });
+    let body = json!({
+        "webauthn_credential": {
+            "name": "security key",
+            "credential_id": "Y3JlZGVudGlhbA",
+            "client_data_json": URL_SAFE_NO_PAD.encode(client_data.to_string()),
+            "public_key": URL_SAFE_NO_PAD.encode(public_key.as_bytes()),
+        }
+    });
+
+    // The registration has to use the challenge of the session
+    let url = "/api/v1/me/webauthn_credentials";
+    let response = user.put::<()>(url, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let session = [("webauthn_challenge", CHALLENGE.to_string())];
+    let response = run_with_session(&user, &session, Method::PUT, url, &body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.into_json();
+    assert_eq!(json["webauthn_credential"]["name"], "security key");
+
+    let response = user.put::<()>(TOKENS_URL, NEW_TOKEN);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.into_text(), SECOND_FACTOR_REQUIRED);
+
+    let client_data = json!({
+        "type": "webauthn.get",
+        "challenge": CHALLENGE,
+        "origin": "https://crates.io",
+    })
+    .to_string();
+
+    let mut authenticator_data = Sha256::digest(b"crates.io").to_vec();
+    authenticator_data.push(0x01);
+    authenticator_data.extend_from_slice(&1u32.to_be_bytes());
+
+    let mut message = authenticator_data.clone();
+    message.extend_from_slice(&Sha256::digest(&client_data));
+    let signature: Signature = signing_key.sign(&message);
+
+    let body = json!({
+        "webauthn": {
+            "credential_id": "Y3JlZGVudGlhbA",
+            "client_data_json": URL_SAFE_NO_PAD.encode(&client_data),
+            "authenticator_data": URL_SAFE_NO_PAD.encode(&authenticator_data),
+            "signature": URL_SAFE_NO_PAD.encode(signature.to_der().as_bytes()),
+        }
+    });
+
+    let response = run_with_session(&user, &session, Method::PUT, VERIFY_URL, &body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The signature counter didn't increase, so the assertion was replayed
+    let response = run_with_session(&user, &session, Method::PUT, VERIFY_URL, &body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,87 @@
+use crate::util::{RequestHelper, TestApp};
+use http::StatusCode;
+use insta::{assert_json_snapshot, assert_snapshot};
+use p256::ecdsa::SigningKey;
+use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+const URL: &str = "/api/v1/me/signing_keys";
+
+pub fn public_key_pem(seed: u8) -> String {
+    let key = SigningKey::from_bytes(&[seed; 32].into()).unwrap();
+    p256::PublicKey::from(key.verifying_key())
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap()
+}
+
+#[test]
+fn create_list_and_revoke() {
+    let (_, _, user) = TestApp::init().with_user();
+
+    let json = user.get::<()>(URL).into_json();
+    assert_eq!(json, json!({ "signing_keys": [] }));
+
+    let body = json!({ "signing_key": { "name": "laptop", "public_key": public_key_pem(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".signing_key.id" => "[id]",
+        ".signing_key.created_at" => "[datetime]",
+    });
+
+    let json = user.get::<()>(URL).into_json();
+    let keys = json["signing_keys"].as_array().unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0]["name"], "laptop");
+
+    let id = keys[0]["id"].as_i64().unwrap();
+    let response = user.delete::<()>(&format!("{URL}/{id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = user.get::<()>(URL).into_json();
+    assert_eq!(json, json!({ "signing_keys": [] }));
+}
+
+#[test]
+fn invalid_signing_keys() {
+    let (app, anon, user) = TestApp::init().with_user();
+
+    let body = json!({ "signing_key": { "name": "laptop", "public_key": public_key_pem(1) } });
+    let response = anon.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "signing_key": { "name": " ", "public_key": public_key_pem(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"name must have a value"}]}"###);
+
+    let body = json!({ "signing_key": { "name": "laptop", "public_key": "ssh-ed25519 AAAA" } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"public_key must be an ECDSA P-256 public key in PEM format"}]}"###);
+
+    let body = json!({ "signing_key": { "name": "laptop", "public_key": public_key_pem(1) } });
+    let response = user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The same key can not be registered twice, not even by another user
+    let other_user = app.db_new_user("other");
+    let response = other_user.put::<()>(URL, body.to_string());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_snapshot!(response.into_text(), @r###"{"errors":[{"detail":"this signing key has already been registered"}]}"###);
+}
+
+#[test]
+fn cannot_revoke_keys_of_other_users() {
+    let (app, _, user) = TestApp::init().with_user();
+
+    let body = json!({ "signing_key": { "name": "laptop", "public_key": public_key_pem(1) } });
+    let json = user.put::<()>(URL, body.to_string()).into_json();
+    let id = json["signing_key"]["id"].as_i64().unwrap();
+
+    let other_user = app.db_new_user("other");
+    let response = other_user.delete::<()>(&format!("{URL}/{id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = user.get::<()>(URL).into_json();
+    assert_eq!(json["signing_keys"].as_array().unwrap().len(), 1);
+}
@@ -233,6 +233,26 @@ fn create_token_with_invalid_endpoint_scope() {
     );
 }
 
+#[test]
+fn create_token_with_crate_and_account_scopes() {
+    let (_, _, user) = TestApp::init().with_user();
+
+    let json = json!({
+        "api_token": {
+            "name": "bar",
+            "crate_scopes": ["tokio"],
+            "endpoint_scopes": ["publish-update", "read-private"],
+        }
+    });
+
+    let response = user.put::<()>("/api/v1/me/tokens", serde_json::to_vec(&json).unwrap());
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "crate scopes can not be combined with account endpoint scopes" }] })
+    );
+}
+
 #[test]
 fn create_token_with_expiry_date() {
     let (_app, _, user) = TestApp::init().with_user();
@@ -17,6 +17,16 @@ fn list_with_api_token_is_forbidden() {
     token.get("/api/v1/me/tokens").assert_forbidden();
 }
 
+#[test]
+fn list_with_manage_tokens_scope() {
+    let endpoint_scopes = Some(vec![EndpointScope::ManageTokens]);
+    let (_, _, _, token) = TestApp::init().with_scoped_token(None, endpoint_scopes);
+    let response = token.get::<()>("/api/v1/me/tokens");
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.into_json();
+    assert_eq!(json["api_tokens"].as_array().unwrap().len(), 1);
+}
+
 #[test]
 fn list_empty() {
     let (_, _, user) = TestApp::init().with_user();
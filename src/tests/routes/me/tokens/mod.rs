@@ -2,3 +2,4 @@ pub mod create;
 pub mod delete;
 pub mod delete_current;
 pub mod list;
+pub mod usage;
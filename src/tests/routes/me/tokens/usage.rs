@@ -0,0 +1,67 @@
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::token::MAX_USAGES_PER_TOKEN;
+use crates_io::models::NewApiTokenUsage;
+use http::StatusCode;
+use serde_json::Value;
+
+#[test]
+fn usage_logged_out() {
+    let (_, anon) = TestApp::init().empty();
+    anon.get("/api/v1/me/tokens/1/usage").assert_forbidden();
+}
+
+#[test]
+fn usage_with_api_token_is_forbidden() {
+    let (_, _, _, token) = TestApp::init().with_token();
+    let url = format!("/api/v1/me/tokens/{}/usage", token.as_model().id);
+    token.get(&url).assert_forbidden();
+}
+
+#[test]
+fn usage_of_other_users_token() {
+    let (app, _, _, token) = TestApp::init().with_token();
+    let user2 = app.db_new_user("baz");
+    let url = format!("/api/v1/me/tokens/{}/usage", token.as_model().id);
+    user2.get::<()>(&url).assert_not_found();
+}
+
+#[test]
+fn usage_is_recorded() {
+    let (_, _, user, token) = TestApp::init().with_token();
+    let url = format!("/api/v1/me/tokens/{}/usage", token.as_model().id);
+    let json: Value = user.get(&url).good();
+    assert_eq!(json["usage"].as_array().unwrap().len(), 0);
+
+    let response = token.get::<()>("/api/v1/crates?following=1");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json: Value = user.get(&url).good();
+    let usage = json["usage"].as_array().unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0]["endpoint"], "GET /api/v1/crates");
+    assert_eq!(usage[0]["user_agent"], "conduit-test");
+    assert!(usage[0]["used_at"].is_string());
+}
+
+#[test]
+fn usage_is_bounded() {
+    let (app, _, user, token) = TestApp::init().with_token();
+    let api_token_id = token.as_model().id;
+
+    app.db(|conn| {
+        for _ in 0..MAX_USAGES_PER_TOKEN + 5 {
+            NewApiTokenUsage {
+                api_token_id,
+                ip_address: None,
+                user_agent: None,
+                endpoint: Some("GET /api/v1/me".into()),
+            }
+            .insert(conn);
+        }
+    });
+
+    let url = format!("/api/v1/me/tokens/{api_token_id}/usage");
+    let json: Value = user.get(&url).good();
+    let usage = json["usage"].as_array().unwrap();
+    assert_eq!(usage.len(), MAX_USAGES_PER_TOKEN as usize);
+}
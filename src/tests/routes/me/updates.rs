@@ -1,6 +1,7 @@
 use crate::builders::{CrateBuilder, VersionBuilder};
 use crate::util::{RequestHelper, TestApp};
 use crate::OkBool;
+use crates_io::models::token::EndpointScope;
 use crates_io::schema::versions;
 use crates_io::views::EncodableVersion;
 use diesel::prelude::*;
@@ -13,6 +14,25 @@ fn api_token_cannot_get_user_updates() {
     token.get("/api/v1/me/updates").assert_forbidden();
 }
 
+#[test]
+fn api_token_with_read_private_scope_can_get_user_updates() {
+    let endpoint_scopes = Some(vec![EndpointScope::ReadPrivate]);
+    let (_, _, _, token) = TestApp::init().with_scoped_token(None, endpoint_scopes);
+    let response = token.get::<()>("/api/v1/me/updates");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Read-only tokens can't be used to change anything
+    let response = token.put::<()>("/api/v1/crates/foo/follow", b"" as &[u8]);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn api_token_with_other_scopes_cannot_get_user_updates() {
+    let endpoint_scopes = Some(vec![EndpointScope::PublishUpdate, EndpointScope::Follow]);
+    let (_, _, _, token) = TestApp::init().with_scoped_token(None, endpoint_scopes);
+    token.get("/api/v1/me/updates").assert_forbidden();
+}
+
 #[test]
 fn following() {
     #[derive(Deserialize)]
@@ -14,10 +14,14 @@
 pub mod categories;
 pub mod category_slugs;
 pub mod crates;
+pub mod feeds;
 pub mod keywords;
 pub mod me;
 pub mod metrics;
+pub mod prefixes;
+pub mod publishes;
 pub mod session;
 pub mod summary;
+pub mod trusted_publishing;
 pub mod users;
 pub mod versions;
@@ -0,0 +1,106 @@
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::NewCrateNamePrefix;
+use http::StatusCode;
+
+const URL: &str = "/api/v1/prefixes/mycompany/owners";
+
+fn owner_logins(json: serde_json::Value) -> Vec<String> {
+    json["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["login"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn manage_prefix_owners() {
+    let (app, anon, user) = TestApp::init().with_user();
+    app.db_new_user("other");
+
+    app.db(|conn| {
+        let prefix = NewCrateNamePrefix {
+            prefix: "mycompany",
+        }
+        .insert(conn)
+        .unwrap();
+        prefix.add_owner(conn, user.as_model().id).unwrap();
+    });
+
+    let json = anon.get::<()>(URL).into_json();
+    assert_eq!(owner_logins(json), ["foo"]);
+
+    // Prefixes are looked up by their canonical name
+    let json = anon
+        .get::<()>("/api/v1/prefixes/MyCompany/owners")
+        .into_json();
+    assert_eq!(owner_logins(json), ["foo"]);
+
+    let body = json!({ "owners": ["other"] }).to_string();
+    let response = user.put::<()>(URL, body.clone());
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "ok": true }));
+
+    // Adding an existing owner again is a no-op
+    let response = user.put::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = anon.get::<()>(URL).into_json();
+    assert_eq!(owner_logins(json), ["foo", "other"]);
+
+    let body = json!({ "owners": ["foo"] }).to_string();
+    let response = user.delete_with_body::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = anon.get::<()>(URL).into_json();
+    assert_eq!(owner_logins(json), ["other"]);
+
+    // The removed owner can no longer manage the prefix
+    let body = json!({ "owners": ["foo"] }).to_string();
+    let response = user.put::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "only owners have permission to modify the owners of this prefix" }] })
+    );
+}
+
+#[test]
+fn invalid_prefix_owner_requests() {
+    let (app, anon, user) = TestApp::init().with_user();
+
+    app.db(|conn| {
+        let prefix = NewCrateNamePrefix {
+            prefix: "mycompany",
+        }
+        .insert(conn)
+        .unwrap();
+        prefix.add_owner(conn, user.as_model().id).unwrap();
+    });
+
+    let response = anon.get::<()>("/api/v1/prefixes/unknown/owners");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = json!({ "owners": ["other"] }).to_string();
+    let response = anon.put::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "owners": ["unknown"] }).to_string();
+    let response = user.put::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "could not find user with login `unknown`" }] })
+    );
+
+    let body = json!({ "owners": ["foo"] }).to_string();
+    let response = user.delete_with_body::<()>(URL, body);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": "cannot remove all owners of a prefix" }] })
+    );
+
+    let json = anon.get::<()>(URL).into_json();
+    assert_eq!(owner_logins(json), ["foo"]);
+}
@@ -0,0 +1,106 @@
+use crate::builders::PublishBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::schema::versions;
+use diesel::prelude::*;
+use http::StatusCode;
+use insta::assert_json_snapshot;
+
+#[test]
+fn publish_status_transitions_to_succeeded() {
+    let (app, _, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("foo", "1.0.0");
+    let response = token.put::<()>("/api/v1/crates/new", crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.into_json();
+    let publish_id = json["publish"]["id"].as_i64().unwrap();
+    assert_eq!(json["publish"]["status"], "queued");
+
+    // The tarball is only uploaded by the background job
+    assert_eq!(app.stored_files(), Vec::<String>::new());
+
+    let url = format!("/api/v1/publishes/{publish_id}");
+    let response = token.get::<()>(&url);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
+    });
+
+    app.run_pending_background_jobs();
+
+    let response = token.get::<()>(&url);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
+    });
+
+    let expected_files = vec!["crates/foo/foo-1.0.0.crate", "index/3/f/foo"];
+    assert_eq!(app.stored_files(), expected_files);
+}
+
+#[test]
+fn publish_status_reports_checksum_mismatch() {
+    let (app, _, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("foo", "1.0.0");
+    let response = token.put::<()>("/api/v1/crates/new", crate_to_publish);
+    assert_eq!(response.status(), StatusCode::OK);
+    let publish_id = response.into_json()["publish"]["id"].as_i64().unwrap();
+
+    app.db(|conn| {
+        diesel::update(versions::table)
+            .set(
+                versions::checksum
+                    .eq("0000000000000000000000000000000000000000000000000000000000000000"),
+            )
+            .execute(conn)
+            .unwrap();
+    });
+
+    app.run_pending_background_jobs();
+
+    let response = token.get::<()>(&format!("/api/v1/publishes/{publish_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json(), {
+        ".publish.id" => "[id]",
+        ".publish.created_at" => "[datetime]",
+        ".publish.updated_at" => "[datetime]",
+    });
+
+    // Neither the tarball nor the index file must have been uploaded
+    assert_eq!(app.stored_files(), Vec::<String>::new());
+}
+
+#[test]
+fn publish_status_requires_authentication() {
+    let (_, anon, _, token) = TestApp::full().with_token();
+
+    let crate_to_publish = PublishBuilder::new("foo", "1.0.0");
+    let publish_id = token.publish_crate(crate_to_publish).good().publish.id;
+
+    let url = format!("/api/v1/publishes/{publish_id}");
+    anon.get::<()>(&url).assert_forbidden();
+}
+
+#[test]
+fn publish_status_of_other_user() {
+    let (app, _, _, token) = TestApp::full().with_token();
+    let user2 = app.db_new_user("bar");
+
+    let crate_to_publish = PublishBuilder::new("foo", "1.0.0");
+    let publish_id = token.publish_crate(crate_to_publish).good().publish.id;
+
+    let url = format!("/api/v1/publishes/{publish_id}");
+    user2.get::<()>(&url).assert_not_found();
+}
+
+#[test]
+fn unknown_publish() {
+    let (_, _, user) = TestApp::init().with_user();
+    user.get::<()>("/api/v1/publishes/42").assert_not_found();
+}
@@ -0,0 +1,217 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{trustpub, MockAnonymousUser, RequestHelper, Response, TestApp};
+use crates_io::models::NewTrustedPublisher;
+use http::{header, Method, StatusCode};
+use serde_json::Value;
+
+const URL: &str = "/api/v1/trusted_publishing/tokens";
+
+/// Creates the `foo` crate with a trusted publisher for the `release.yml`
+/// workflow of the `rust-lang/foo` repository.
+fn setup(environment: Option<&str>) -> (TestApp, MockAnonymousUser) {
+    let (app, anon, user) = TestApp::full().with_user();
+    let user_id = user.as_model().id;
+
+    app.db(|conn| {
+        let krate = CrateBuilder::new("foo", user_id)
+            .version("1.0.0")
+            .expect_build(conn);
+
+        NewTrustedPublisher {
+            crate_id: krate.id,
+            repository: "rust-lang/foo",
+            workflow_filename: "release.yml",
+            environment,
+            created_by: user_id,
+        }
+        .insert(conn)
+        .unwrap();
+    });
+
+    (app, anon)
+}
+
+fn exchange(anon: &MockAnonymousUser, krate: &str, jwt: &str) -> Response<()> {
+    let body = json!({ "crate": krate, "jwt": jwt });
+    anon.put::<()>(URL, body.to_string())
+}
+
+fn assert_error(response: Response<()>, expected: &str) {
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.into_json(),
+        json!({ "errors": [{ "detail": expected }] })
+    );
+}
+
+/// Issues a publish request that is authenticated with the given token.
+fn publish_with_token(
+    anon: &MockAnonymousUser,
+    token: &str,
+    publish: PublishBuilder,
+) -> Response<()> {
+    let mut request = anon.request_builder(Method::PUT, "/api/v1/crates/new");
+    request
+        .headers_mut()
+        .insert(header::AUTHORIZATION, token.parse().unwrap());
+    *request.body_mut() = publish.into();
+    anon.run(request)
+}
+
+#[test]
+fn exchange_and_publish() {
+    let (app, anon) = setup(None);
+
+    let jwt = trustpub::sign(&trustpub::claims("rust-lang/foo"));
+    let response = exchange(&anon, "foo", &jwt);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.into_json();
+    assert_json_snapshot!(json, {
+        ".api_token.id" => insta::any_id_redaction(),
+        ".api_token.created_at" => "[datetime]",
+        ".api_token.last_used_at" => "[datetime]",
+        ".api_token.expired_at" => "[datetime]",
+        ".api_token.token" => insta::api_token_redaction(),
+    });
+
+    let token = json["api_token"]["token"].as_str().unwrap();
+    let response = publish_with_token(&anon, token, PublishBuilder::new("foo", "1.1.0"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    app.run_pending_background_jobs();
+
+    let expected_files = vec!["crates/foo/foo-1.1.0.crate", "index/3/f/foo"];
+    assert_eq!(app.stored_files(), expected_files);
+}
+
+#[test]
+fn minted_token_is_scoped_to_crate() {
+    let (app, anon) = setup(None);
+
+    let jwt = trustpub::sign(&trustpub::claims("rust-lang/foo"));
+    let json: Value = exchange(&anon, "foo", &jwt).into_json();
+    let token = json["api_token"]["token"].as_str().unwrap();
+
+    // Publishing a new crate requires the `publish-new` endpoint scope
+    let response = publish_with_token(&anon, token, PublishBuilder::new("bar", "1.0.0"));
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let mut request = anon.request_builder(Method::DELETE, "/api/v1/crates/foo/1.0.0/yank");
+    request
+        .headers_mut()
+        .insert(header::AUTHORIZATION, token.parse().unwrap());
+    let response = anon.run::<()>(request);
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    assert_eq!(app.stored_files(), Vec::<String>::new());
+}
+
+#[test]
+fn exchange_with_environment() {
+    let (_app, anon) = setup(Some("release"));
+
+    let mut claims = trustpub::claims("rust-lang/foo");
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "no matching trusted publisher found",
+    );
+
+    claims["environment"] = "staging".into();
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "no matching trusted publisher found",
+    );
+
+    claims["environment"] = "release".into();
+    let jwt = trustpub::sign(&claims);
+    assert_eq!(exchange(&anon, "foo", &jwt).status(), StatusCode::OK);
+}
+
+#[test]
+fn exchange_with_other_repository() {
+    let (_app, anon) = setup(None);
+
+    let jwt = trustpub::sign(&trustpub::claims("rust-lang/bar"));
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "no matching trusted publisher found",
+    );
+}
+
+#[test]
+fn exchange_with_other_workflow() {
+    let (_app, anon) = setup(None);
+
+    let mut claims = trustpub::claims("rust-lang/foo");
+    claims["workflow_ref"] = "rust-lang/foo/.github/workflows/ci.yml@refs/heads/main".into();
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "no matching trusted publisher found",
+    );
+}
+
+#[test]
+fn exchange_for_unknown_crate() {
+    let (_app, anon) = setup(None);
+
+    let jwt = trustpub::sign(&trustpub::claims("rust-lang/foo"));
+    assert_error(
+        exchange(&anon, "unknown", &jwt),
+        "no matching trusted publisher found",
+    );
+}
+
+#[test]
+fn exchange_with_invalid_jwt() {
+    let (_app, anon) = setup(None);
+
+    let response = exchange(&anon, "foo", "not-a-jwt");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let jwt = trustpub::sign_with_key_id(&trustpub::claims("rust-lang/foo"), "unknown-key");
+    assert_error(exchange(&anon, "foo", &jwt), "unknown key id unknown-key");
+}
+
+#[test]
+fn exchange_with_expired_jwt() {
+    let (_app, anon) = setup(None);
+
+    let mut claims = trustpub::claims("rust-lang/foo");
+    claims["exp"] = (chrono::Utc::now().timestamp() - 10 * 60).into();
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "invalid OIDC token: ExpiredSignature",
+    );
+}
+
+#[test]
+fn exchange_with_wrong_audience() {
+    let (_app, anon) = setup(None);
+
+    let mut claims = trustpub::claims("rust-lang/foo");
+    claims["aud"] = "example.com".into();
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "invalid OIDC token: InvalidAudience",
+    );
+}
+
+#[test]
+fn exchange_with_wrong_issuer() {
+    let (_app, anon) = setup(None);
+
+    let mut claims = trustpub::claims("rust-lang/foo");
+    claims["iss"] = "https://example.com".into();
+    let jwt = trustpub::sign(&claims);
+    assert_error(
+        exchange(&anon, "foo", &jwt),
+        "invalid OIDC token: InvalidIssuer",
+    );
+}
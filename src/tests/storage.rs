@@ -0,0 +1,60 @@
+use crate::builders::PublishBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::config;
+use crates_io::storage::StorageConfig;
+use http::StatusCode;
+use std::path::Path;
+
+fn file_system_storage(path: &Path, cdn_prefix: Option<&str>) -> impl FnOnce(&mut config::Server) {
+    let mut storage = StorageConfig::file_system(path.into());
+    storage.cdn_prefix = cdn_prefix.map(ToString::to_string);
+
+    |config| config.storage = storage
+}
+
+#[test]
+fn file_system_files_are_served_directly() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, anon, _, token) = TestApp::full()
+        .with_config(file_system_storage(dir.path(), None))
+        .with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    assert!(dir.path().join("crates/foo/foo-1.0.0.crate").is_file());
+    assert!(dir.path().join("index/3/f/foo").is_file());
+
+    anon.get::<()>("/api/v1/crates/foo/1.0.0/download")
+        .assert_redirect_ends_with("/crates/foo/foo-1.0.0.crate");
+
+    let response = anon.get::<()>("/crates/foo/foo-1.0.0.crate");
+    assert_eq!(response.status(), StatusCode::OK);
+    let stored = std::fs::read(dir.path().join("crates/foo/foo-1.0.0.crate")).unwrap();
+    assert_eq!(response.into_bytes(), stored);
+
+    let response = anon.get::<()>("/index/3/f/foo");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.into_text().contains(r#""name":"foo""#));
+}
+
+#[test]
+fn file_system_files_are_not_served_with_cdn() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, anon, _, token) = TestApp::full()
+        .with_config(file_system_storage(dir.path(), Some("static.example.com")))
+        .with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    assert!(dir.path().join("crates/foo/foo-1.0.0.crate").is_file());
+
+    anon.get::<()>("/api/v1/crates/foo/1.0.0/download")
+        .assert_redirect_ends_with("https://static.example.com/crates/foo/foo-1.0.0.crate");
+
+    anon.get::<()>("/crates/foo/foo-1.0.0.crate")
+        .assert_not_found();
+}
@@ -46,6 +46,7 @@ pub mod insta;
 mod mock_request;
 mod response;
 mod test_app;
+pub mod trustpub;
 
 pub(crate) use chaosproxy::ChaosProxy;
 use mock_request::MockRequest;
@@ -64,14 +65,19 @@ pub use test_app::TestApp;
 /// The implementation matches roughly what is happening inside of our
 /// session middleware.
 pub fn encode_session_header(session_key: &cookie::Key, user_id: i32) -> String {
-    let cookie_name = "cargo_session";
-
     // build session data map
     let mut map = HashMap::new();
     map.insert("user_id".into(), user_id.to_string());
 
+    encode_session_data(session_key, &map)
+}
+
+/// Creates a `Cookie` header for mock requests with arbitrary session data.
+pub fn encode_session_data(session_key: &cookie::Key, map: &HashMap<String, String>) -> String {
+    let cookie_name = "cargo_session";
+
     // encode the map into a cookie value string
-    let encoded = session::encode(&map);
+    let encoded = session::encode(map);
 
     // put the cookie into a signed cookie jar
     let cookie = Cookie::build(cookie_name, encoded).finish();
@@ -4,6 +4,7 @@ use crates_io::github::{
     GithubUser,
 };
 use crates_io::util::errors::{not_found, AppResult};
+use jsonwebtoken::jwk::JwkSet;
 use oauth2::AccessToken;
 
 pub(crate) const MOCK_GITHUB_DATA: MockData = MockData {
@@ -172,6 +173,10 @@ impl GitHubClient for MockGitHubClient {
     fn public_keys(&self, _username: &str, _password: &str) -> AppResult<Vec<GitHubPublicKey>> {
         Ok(self.data.public_keys.iter().map(Into::into).collect())
     }
+
+    fn actions_oidc_keys(&self) -> AppResult<JwkSet> {
+        Ok(serde_json::from_str(super::trustpub::JWKS).unwrap())
+    }
 }
 
 pub(crate) struct MockData {
@@ -6,6 +6,7 @@ use crates_io::config::{self, BalanceCapacityConfig, Base, DatabasePools, DbPool
 use crates_io::models::token::{CrateScope, EndpointScope};
 use crates_io::rate_limiter::{LimitedAction, RateLimiterConfig};
 use crates_io::storage::StorageConfig;
+use crates_io::worker::jobs::GitIndexBatching;
 use crates_io::worker::swirl::Runner;
 use crates_io::worker::{Environment, RunnerExt};
 use crates_io::{App, Emails, Env};
@@ -76,6 +77,7 @@ impl TestApp {
             proxy: None,
             index: None,
             build_job_runner: false,
+            git_index_batching: None,
             use_chaos_proxy: false,
         }
     }
@@ -204,6 +206,7 @@ pub struct TestAppBuilder {
     proxy: Option<String>,
     index: Option<UpstreamIndex>,
     build_job_runner: bool,
+    git_index_batching: Option<GitIndexBatching>,
     use_chaos_proxy: bool,
 }
 
@@ -259,6 +262,8 @@ impl TestAppBuilder {
                 None,
                 None,
                 app.storage.clone(),
+                app.emails.clone(),
+                self.git_index_batching,
             );
 
             let runner = Runner::new(app.primary_database.clone(), Arc::new(environment))
@@ -344,6 +349,11 @@ impl TestAppBuilder {
         self
     }
 
+    pub fn with_git_index_batching(mut self, batching: GitIndexBatching) -> Self {
+        self.git_index_batching = Some(batching);
+        self
+    }
+
     pub fn without_test_database_pool(mut self) -> Self {
         self.config.use_test_database_pool = false;
         self
@@ -432,6 +442,12 @@ fn simple_config() -> config::Server {
         cdn_user_agent: "Amazon CloudFront".to_string(),
         balance_capacity,
 
+        // The tests use a local HTTP server to receive the webhook deliveries.
+        allow_http_webhooks: true,
+        mirror: None,
+        private_registry: false,
+        index_urls: vec!["sparse+https://crates.io/index/".into()],
+
         // The frontend code is not needed for the backend tests.
         serve_dist: false,
         serve_html: false,
@@ -453,7 +469,7 @@ fn build_app(config: config::Server, proxy: Option<String>) -> (Arc<App>, axum::
 
     // Use the in-memory email backend for all tests, allowing tests to analyze the emails sent by
     // the application. This will also prevent cluttering the filesystem.
-    app.emails = Emails::new_in_memory();
+    app.emails = Arc::new(Emails::new_in_memory());
 
     // Use a custom mock for the GitHub client, allowing to define the GitHub users and
     // organizations without actually having to create GitHub accounts.
@@ -0,0 +1,83 @@
+use crate::builders::CrateBuilder;
+use crate::util::insta::assert_json_snapshot;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::worker::jobs::ImportAdvisories;
+use crates_io::worker::swirl::BackgroundJob;
+use http::StatusCode;
+use std::path::PathBuf;
+
+const ADVISORY_DB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/worker/advisory-db");
+
+fn import_advisories(app: &TestApp, path: impl Into<PathBuf>) {
+    app.db(|conn| assert_ok!(ImportAdvisories::new(path.into()).enqueue(conn)));
+    app.run_pending_background_jobs();
+}
+
+fn version_advisories(anon: &impl RequestHelper) -> Vec<(String, Vec<String>)> {
+    let json = anon.get::<()>("/api/v1/crates/foo/versions").into_json();
+    json["versions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|version| {
+            let num = version["num"].as_str().unwrap().to_string();
+            let advisories = serde_json::from_value(version["advisories"].clone()).unwrap();
+            (num, advisories)
+        })
+        .collect()
+}
+
+#[test]
+fn import_advisories_flags_affected_versions() {
+    let (app, anon, user) = TestApp::full().with_user();
+    app.db(|conn| {
+        CrateBuilder::new("foo", user.as_model().id)
+            .version("0.9.0")
+            .version("1.0.0")
+            .version("1.2.3")
+            .version("2.0.0")
+            .version("2.0.1")
+            .expect_build(conn);
+    });
+
+    let response = anon.get::<()>("/api/v1/crates/foo/advisories");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_json(), json!({ "advisories": [] }));
+
+    import_advisories(&app, ADVISORY_DB);
+
+    let response = anon.get::<()>("/api/v1/crates/foo/advisories");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_json_snapshot!(response.into_json());
+
+    let unmaintained = "RUSTSEC-2023-0002".to_string();
+    let both = vec!["RUSTSEC-2023-0001".to_string(), unmaintained.clone()];
+    assert_eq!(
+        version_advisories(&anon),
+        vec![
+            ("2.0.1".into(), vec![unmaintained.clone()]),
+            ("2.0.0".into(), both.clone()),
+            ("1.2.3".into(), vec![unmaintained.clone()]),
+            ("1.0.0".into(), both.clone()),
+            ("0.9.0".into(), vec![unmaintained.clone()]),
+        ]
+    );
+
+    let json = anon.show_version("foo", "1.0.0");
+    assert_eq!(json.version.advisories, both);
+
+    // Importing the same database again does not change anything
+    import_advisories(&app, ADVISORY_DB);
+    assert_eq!(version_advisories(&anon)[1].1, both);
+
+    // Advisories that are no longer part of the database are removed
+    let empty_db = tempfile::tempdir().unwrap();
+    std::fs::create_dir(empty_db.path().join("crates")).unwrap();
+    import_advisories(&app, empty_db.path());
+
+    let response = anon.get::<()>("/api/v1/crates/foo/advisories");
+    assert_eq!(response.into_json(), json!({ "advisories": [] }));
+    assert!(version_advisories(&anon)
+        .iter()
+        .all(|(_, advisories)| advisories.is_empty()));
+}
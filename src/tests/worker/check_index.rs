@@ -0,0 +1,95 @@
+use crate::builders::{CrateBuilder, PublishBuilder};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::schema::versions;
+use crates_io::worker::jobs::{check_index, CheckIndex, Difference, IndexSource};
+use crates_io::worker::swirl::BackgroundJob;
+use crates_io_index::testing::UpstreamIndex;
+use crates_io_index::{Credentials, Repository, RepositoryConfig};
+use diesel::prelude::*;
+
+fn check(app: &TestApp) -> Vec<(String, IndexSource, Vec<Difference>)> {
+    let config = RepositoryConfig {
+        index_location: UpstreamIndex::url(),
+        credentials: Credentials::Missing,
+    };
+    let repo = Repository::open(&config).unwrap();
+
+    let storage = &app.as_inner().storage;
+    let drifts = app.db(|conn| check_index(conn, &repo, storage).unwrap());
+    drifts
+        .into_iter()
+        .map(|drift| (drift.krate, drift.source, drift.differences))
+        .collect()
+}
+
+#[test]
+fn check_index_reports_and_fixes_drift() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+
+    assert_eq!(check(&app), vec![]);
+
+    app.db(|conn| {
+        // Change the database without syncing the index
+        diesel::update(versions::table.filter(versions::num.eq("1.1.0")))
+            .set(versions::yanked.eq(true))
+            .execute(conn)
+            .unwrap();
+
+        CrateBuilder::new("bar", user.as_model().id)
+            .version("1.0.0")
+            .expect_build(conn);
+    });
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let storage = &app.as_inner().storage;
+    rt.block_on(storage.sync_index("baz", Some(String::new())))
+        .unwrap();
+
+    let yanked = || Difference::Field {
+        version: "1.1.0".into(),
+        field: "yanked".into(),
+        expected: true.into(),
+        actual: false.into(),
+    };
+
+    let expected = vec![
+        (
+            "bar".into(),
+            IndexSource::Git,
+            vec![Difference::MissingFile],
+        ),
+        (
+            "bar".into(),
+            IndexSource::Sparse,
+            vec![Difference::MissingFile],
+        ),
+        (
+            "baz".into(),
+            IndexSource::Sparse,
+            vec![Difference::UnexpectedFile],
+        ),
+        ("foo".into(), IndexSource::Git, vec![yanked()]),
+        ("foo".into(), IndexSource::Sparse, vec![yanked()]),
+    ];
+    assert_eq!(check(&app), expected);
+
+    app.db(|conn| assert_ok!(CheckIndex::new(true).enqueue(conn)));
+    app.run_pending_background_jobs();
+
+    assert_eq!(check(&app), vec![]);
+
+    let crates = app.crates_from_index_head("foo");
+    assert_eq!(crates[1].yanked, Some(true));
+    assert_eq!(app.crates_from_index_head("bar").len(), 1);
+}
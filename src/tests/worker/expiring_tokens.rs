@@ -0,0 +1,80 @@
+use crate::util::TestApp;
+use chrono::{Duration, NaiveDateTime, Utc};
+use crates_io::models::ApiToken;
+use crates_io::schema::api_tokens;
+use crates_io::worker::jobs::ProcessExpiringTokens;
+use crates_io::worker::swirl::BackgroundJob;
+use diesel::prelude::*;
+
+fn process_expiring_tokens(app: &TestApp) {
+    let job = ProcessExpiringTokens::new(3, 30);
+    app.db(|conn| assert_ok!(job.enqueue(conn)));
+    app.run_pending_background_jobs();
+}
+
+fn sent_subjects(app: &TestApp) -> Vec<String> {
+    app.as_inner()
+        .emails
+        .mails_in_memory()
+        .unwrap()
+        .into_iter()
+        .map(|email| email.subject)
+        .collect()
+}
+
+#[test]
+fn notify_and_revoke_expiring_tokens() {
+    let (app, _, user) = TestApp::full().with_user();
+    let user_id = user.as_model().id;
+
+    let now = Utc::now().naive_utc();
+    let insert = |name: &str, expired_at: NaiveDateTime| {
+        app.db(|conn| {
+            ApiToken::insert_with_scopes(conn, user_id, name, None, None, Some(expired_at))
+                .unwrap()
+                .model
+                .id
+        })
+    };
+
+    let expiring = insert("expiring", now + Duration::days(2));
+    let later = insert("later", now + Duration::days(10));
+    let recently_expired = insert("recently-expired", now - Duration::days(5));
+    let long_expired = insert("long-expired", now - Duration::days(40));
+    let without_expiry =
+        app.db(|conn| ApiToken::insert(conn, user_id, "forever").unwrap().model.id);
+
+    process_expiring_tokens(&app);
+
+    assert_eq!(
+        sent_subjects(&app),
+        vec![r#"Your API token "expiring" is about to expire"#]
+    );
+
+    let tokens: Vec<(i32, bool, Option<NaiveDateTime>)> = app.db(|conn| {
+        api_tokens::table
+            .select((
+                api_tokens::id,
+                api_tokens::revoked,
+                api_tokens::expiry_notification_at,
+            ))
+            .order(api_tokens::id)
+            .load(conn)
+            .unwrap()
+    });
+
+    let state = |id: i32| {
+        let (_, revoked, notified_at) = tokens.iter().find(|token| token.0 == id).unwrap();
+        (*revoked, notified_at.is_some())
+    };
+
+    assert_eq!(state(expiring), (false, true));
+    assert_eq!(state(later), (false, false));
+    assert_eq!(state(recently_expired), (false, false));
+    assert_eq!(state(long_expired), (true, false));
+    assert_eq!(state(without_expiry), (false, false));
+
+    // The owners are only reminded once
+    process_expiring_tokens(&app);
+    assert_eq!(sent_subjects(&app).len(), 1);
+}
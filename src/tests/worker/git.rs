@@ -1,7 +1,8 @@
-use crate::builders::PublishBuilder;
+use crate::builders::{CrateBuilder, PublishBuilder};
 use crate::util::{RequestHelper, TestApp};
 use crates_io::models::Crate;
-use crates_io::worker::jobs;
+use crates_io::worker::jobs::{self, GitIndexBatching};
+use crates_io::worker::swirl::BackgroundJob;
 use diesel::prelude::*;
 use http::StatusCode;
 
@@ -66,3 +67,76 @@ fn index_smoke_test() {
     );
     assert_ok_eq!(upstream.crate_exists("serde"), false);
 }
+
+fn enqueue_git_syncs(app: &TestApp, names: &[&str], user_id: i32) {
+    app.db(|conn| {
+        for name in names {
+            CrateBuilder::new(name, user_id).expect_build(conn);
+        }
+
+        // `serde` already exists in the index and is deleted
+        use crates_io::schema::crates;
+        let krate: Crate = assert_ok!(Crate::by_name("serde").first(conn));
+        assert_ok!(diesel::delete(crates::table.find(krate.id)).execute(conn));
+
+        for name in names.iter().chain(&["serde"]) {
+            assert_ok!(jobs::SyncToGitIndex::new(*name).enqueue(conn));
+        }
+    });
+}
+
+#[test]
+fn batched_sync_to_git_index_with_single_commit() {
+    let (app, _, user, token) = TestApp::full()
+        .with_git_index_batching(GitIndexBatching::SingleCommit)
+        .with_token();
+    let upstream = app.upstream_index();
+
+    token
+        .publish_crate(PublishBuilder::new("serde", "1.0.0"))
+        .good();
+
+    enqueue_git_syncs(&app, &["foo", "bar"], user.as_model().id);
+    app.run_pending_background_jobs();
+
+    assert_ok_eq!(
+        upstream.list_commits(),
+        vec![
+            "Initial Commit",
+            "Create crate `serde`",
+            "Update 3 crates\n\n\
+            Create crate `bar`\n\
+            Create crate `foo`\n\
+            Delete crate `serde`\n",
+        ]
+    );
+    assert_ok_eq!(upstream.crate_exists("foo"), true);
+    assert_ok_eq!(upstream.crate_exists("bar"), true);
+    assert_ok_eq!(upstream.crate_exists("serde"), false);
+}
+
+#[test]
+fn batched_sync_to_git_index_with_commit_per_crate() {
+    let (app, _, user, token) = TestApp::full()
+        .with_git_index_batching(GitIndexBatching::CommitPerCrate)
+        .with_token();
+    let upstream = app.upstream_index();
+
+    token
+        .publish_crate(PublishBuilder::new("serde", "1.0.0"))
+        .good();
+
+    enqueue_git_syncs(&app, &["foo", "bar"], user.as_model().id);
+    app.run_pending_background_jobs();
+
+    assert_ok_eq!(
+        upstream.list_commits(),
+        vec![
+            "Initial Commit",
+            "Create crate `serde`",
+            "Create crate `bar`",
+            "Create crate `foo`",
+            "Delete crate `serde`",
+        ]
+    );
+}
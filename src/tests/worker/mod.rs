@@ -1 +1,7 @@
+mod advisories;
+mod check_index;
+mod expiring_tokens;
 mod git;
+mod notifications;
+mod verify_storage;
+mod webhooks;
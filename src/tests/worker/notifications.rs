@@ -0,0 +1,80 @@
+use crate::builders::PublishBuilder;
+use crate::routes::crates::versions::yank_unyank::YankRequestHelper;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{Crate, OwnerKind};
+use crates_io::schema::{crate_owners, crates, emails};
+use diesel::prelude::*;
+use insta::assert_snapshot;
+
+fn add_owner(app: &TestApp, crate_id: i32, login: &str, email_notifications: bool) {
+    let user = app.db_new_user(login);
+    let user_id = user.as_model().id;
+
+    app.db(|conn| {
+        diesel::update(emails::table.filter(emails::user_id.eq(user_id)))
+            .set(emails::email.eq(format!("{login}@example.com")))
+            .execute(conn)
+            .unwrap();
+
+        // Changing the email address resets the verification status
+        diesel::update(emails::table.filter(emails::user_id.eq(user_id)))
+            .set(emails::verified.eq(true))
+            .execute(conn)
+            .unwrap();
+
+        diesel::insert_into(crate_owners::table)
+            .values((
+                crate_owners::crate_id.eq(crate_id),
+                crate_owners::owner_id.eq(user_id),
+                crate_owners::owner_kind.eq(OwnerKind::User),
+                crate_owners::email_notifications.eq(email_notifications),
+            ))
+            .execute(conn)
+            .unwrap();
+    });
+}
+
+fn sent_emails(app: &TestApp) -> String {
+    app.as_inner()
+        .emails
+        .mails_in_memory()
+        .unwrap()
+        .into_iter()
+        .map(|email| {
+            format!(
+                "To: {}\nSubject: {}\n\n{}",
+                email.to, email.subject, email.body
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n----------\n\n")
+}
+
+#[test]
+fn version_notifications() {
+    let (app, _, user, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    let crate_id: i32 = app.db(|conn| {
+        Crate::by_name("foo")
+            .select(crates::id)
+            .first(conn)
+            .unwrap()
+    });
+    add_owner(&app, crate_id, "bar", true);
+    add_owner(&app, crate_id, "baz", false);
+
+    // The first publish only notified the user that published the crate
+    assert_eq!(app.as_inner().emails.mails_in_memory().unwrap().len(), 1);
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+    user.yank("foo", "1.1.0").good();
+    token.unyank("foo", "1.1.0").good();
+
+    assert_snapshot!(sent_emails(&app));
+}
@@ -0,0 +1,89 @@
+use crate::builders::PublishBuilder;
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{StorageIssue, StorageIssueKind};
+use crates_io::worker::jobs::VerifyStorage;
+use crates_io::worker::swirl::BackgroundJob;
+use hyper::body::Bytes;
+use sha2::{Digest, Sha256};
+
+fn verify_storage(app: &TestApp) -> Vec<(StorageIssueKind, String, String)> {
+    app.db(|conn| assert_ok!(VerifyStorage.enqueue(conn)));
+    app.run_pending_background_jobs();
+
+    let issues = app.db(|conn| StorageIssue::all(conn).unwrap());
+    issues
+        .into_iter()
+        .map(|issue| (issue.kind, issue.crate_name, issue.version))
+        .collect()
+}
+
+#[test]
+fn verify_storage_reports_issues() {
+    let (app, _, _, token) = TestApp::full().with_token();
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.1.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("bar", "1.0.0"))
+        .good();
+
+    assert_eq!(verify_storage(&app), vec![]);
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let storage = &app.as_inner().storage;
+    let corrupted = Bytes::from_static(b"corrupted");
+    rt.block_on(async {
+        storage.delete_crate_file("foo", "1.1.0").await.unwrap();
+        let bytes = corrupted.clone();
+        storage
+            .upload_crate_file("bar", "1.0.0", bytes)
+            .await
+            .unwrap();
+        let bytes = Bytes::from_static(b"orphaned");
+        storage
+            .upload_crate_file("baz", "0.1.0", bytes)
+            .await
+            .unwrap();
+    });
+
+    let expected = vec![
+        (
+            StorageIssueKind::ChecksumMismatch,
+            "bar".into(),
+            "1.0.0".into(),
+        ),
+        (StorageIssueKind::Missing, "foo".into(), "1.1.0".into()),
+        (StorageIssueKind::Orphaned, "baz".into(), "0.1.0".into()),
+    ];
+    assert_eq!(verify_storage(&app), expected);
+
+    let issues = app.db(|conn| StorageIssue::all(conn).unwrap());
+    let mismatch = &issues[0];
+    assert!(mismatch.version_id.is_some());
+    assert_eq!(mismatch.expected_checksum.as_ref().unwrap().len(), 64);
+    let actual_checksum = hex::encode(Sha256::digest(&corrupted));
+    assert_eq!(mismatch.actual_checksum.as_ref(), Some(&actual_checksum));
+    assert_eq!(issues[2].version_id, None);
+
+    // The results of the previous run are replaced
+    rt.block_on(storage.delete_crate_file("baz", "0.1.0"))
+        .unwrap();
+
+    let expected = vec![
+        (
+            StorageIssueKind::ChecksumMismatch,
+            "bar".into(),
+            "1.0.0".into(),
+        ),
+        (StorageIssueKind::Missing, "foo".into(), "1.1.0".into()),
+    ];
+    assert_eq!(verify_storage(&app), expected);
+}
@@ -0,0 +1,222 @@
+use crate::builders::{DependencyBuilder, PublishBuilder};
+use crate::routes::crates::versions::yank_unyank::YankRequestHelper;
+use crate::util::insta::{self, assert_json_snapshot};
+use crate::util::{RequestHelper, TestApp};
+use crates_io::models::{Crate, WebhookEvent};
+use crates_io::schema::{crates, webhook_deliveries};
+use crates_io::worker::jobs;
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use http::StatusCode;
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Debug)]
+struct ReceivedRequest {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// A minimal HTTP server that records the requests it receives and responds
+/// to all of them with the given status code.
+struct Receiver {
+    url: String,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl Receiver {
+    fn start(status: u16) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+
+                let mut headers = HashMap::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(": ") {
+                        headers.insert(name.to_lowercase(), value.to_string());
+                    }
+                }
+
+                let length = headers
+                    .get("content-length")
+                    .map_or(0, |length| length.parse().unwrap());
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                received
+                    .lock()
+                    .unwrap()
+                    .push(ReceivedRequest { headers, body });
+
+                let response = format!(
+                    "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Checks the signatures of all received requests and returns their
+    /// event headers and payloads.
+    fn verified_payloads(&self, secret: &str) -> Vec<(String, Value)> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+                mac.update(&request.body);
+                let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+                assert_eq!(request.headers["x-crates-io-signature"], expected);
+                assert_eq!(request.headers["content-type"], "application/json");
+                assert_some!(request.headers.get("x-crates-io-delivery"));
+
+                let event = request.headers["x-crates-io-event"].clone();
+                let payload = serde_json::from_slice(&request.body).unwrap();
+                (event, payload)
+            })
+            .collect()
+    }
+}
+
+fn create_webhook(user: &impl RequestHelper, krate: &str, url: &str, events: &[&str]) -> Value {
+    let body = json!({ "webhook": { "url": url, "events": events } });
+    let response = user.put::<()>(
+        &format!("/api/v1/crates/{krate}/webhooks"),
+        body.to_string(),
+    );
+    response.into_json()["webhook"].clone()
+}
+
+#[test]
+fn webhook_deliveries() {
+    let (app, _, user, token) = TestApp::full().with_token();
+    let foo_receiver = Receiver::start(200);
+    let bar_receiver = Receiver::start(200);
+
+    token
+        .publish_crate(PublishBuilder::new("bar", "1.0.0"))
+        .good();
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+
+    let events = ["publish", "yank", "unyank", "owner-change"];
+    let foo_hook = create_webhook(&user, "foo", &foo_receiver.url, &events);
+    let bar_hook = create_webhook(&user, "bar", &bar_receiver.url, &["new-dependent"]);
+
+    // Only the first version of `foo` that depends on `bar` is reported to
+    // the webhook of `bar`
+    let publish = PublishBuilder::new("foo", "1.1.0").dependency(DependencyBuilder::new("bar"));
+    token.publish_crate(publish).good();
+    let publish = PublishBuilder::new("foo", "1.2.0").dependency(DependencyBuilder::new("bar"));
+    token.publish_crate(publish).good();
+
+    user.yank("foo", "1.1.0").good();
+    token.unyank("foo", "1.1.0").good();
+
+    // Invited users are only reported once they accepted the invitation
+    let other = app.db_new_user("other");
+    token.add_named_owner("foo", "other").good();
+    let crate_id: i32 = app.db(|conn| {
+        Crate::by_name("foo")
+            .select(crates::id)
+            .first(conn)
+            .unwrap()
+    });
+    let body = json!({
+        "crate_owner_invite": {
+            "invited_by_username": "",
+            "crate_name": "foo",
+            "crate_id": crate_id,
+            "created_at": "",
+            "accepted": true
+        }
+    });
+    let url = format!("/api/v1/me/crate_owner_invitations/{crate_id}");
+    assert_eq!(
+        other.put::<()>(&url, body.to_string()).status(),
+        StatusCode::OK
+    );
+    token.remove_named_owner("foo", "other").good();
+
+    app.run_pending_background_jobs();
+
+    let bar_secret = bar_hook["secret"].as_str().unwrap();
+    assert_json_snapshot!("bar_payloads", bar_receiver.verified_payloads(bar_secret), {
+        "[][1].timestamp" => "[datetime]",
+    });
+
+    let foo_secret = foo_hook["secret"].as_str().unwrap();
+    assert_json_snapshot!("foo_payloads", foo_receiver.verified_payloads(foo_secret), {
+        "[][1].timestamp" => "[datetime]",
+    });
+
+    let url = format!("/api/v1/crates/foo/webhooks/{}/deliveries", foo_hook["id"]);
+    assert_json_snapshot!("foo_deliveries", user.get::<()>(&url).into_json(), {
+        ".deliveries[].id" => insta::any_id_redaction(),
+        ".deliveries[].payload.timestamp" => "[datetime]",
+        ".deliveries[].created_at" => "[datetime]",
+        ".deliveries[].last_attempt_at" => "[datetime]",
+        ".deliveries[].delivered_at" => "[datetime]",
+    });
+}
+
+#[test]
+fn failed_webhook_deliveries() {
+    let (app, _, user, token) = TestApp::full().with_token();
+    let receiver = Receiver::start(500);
+
+    token
+        .publish_crate(PublishBuilder::new("foo", "1.0.0"))
+        .good();
+    let webhook = create_webhook(&user, "foo", &receiver.url, &["publish"]);
+
+    // Deliveries are retried by the background worker until the maximum
+    // number of attempts is reached, so this simulates the last attempt.
+    app.db(|conn| {
+        let krate: Crate = Crate::by_name("foo").first(conn).unwrap();
+        jobs::enqueue_webhooks(conn, krate.id, WebhookEvent::Publish, json!({})).unwrap();
+
+        diesel::update(webhook_deliveries::table)
+            .set(webhook_deliveries::attempts.eq(jobs::MAX_ATTEMPTS - 1))
+            .execute(conn)
+            .unwrap();
+    });
+
+    app.run_pending_background_jobs();
+
+    assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+
+    let url = format!("/api/v1/crates/foo/webhooks/{}/deliveries", webhook["id"]);
+    let json = user.get::<()>(&url).into_json();
+    let delivery = &json["deliveries"][0];
+    assert_eq!(delivery["attempts"], jobs::MAX_ATTEMPTS);
+    assert_eq!(delivery["last_status"], 500);
+    assert_eq!(
+        delivery["last_error"],
+        "Webhook responded with status 500 Internal Server Error"
+    );
+    assert!(delivery["delivered_at"].is_null());
+    assert!(delivery["failed_at"].is_string());
+}
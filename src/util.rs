@@ -4,13 +4,16 @@ pub use self::bytes_request::BytesRequest;
 pub use self::io_util::{read_fill, read_le_u32};
 pub use self::request_helpers::*;
 
+pub mod asymmetric_token;
 mod bytes_request;
 pub mod errors;
 mod io_util;
 mod request_helpers;
 pub mod rfc3339;
 pub mod token;
+pub mod totp;
 pub mod tracing;
+pub mod webauthn;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Maximums {
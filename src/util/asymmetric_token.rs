@@ -0,0 +1,485 @@
+//! Asymmetric tokens, as sent by the `cargo:paseto` credential provider of
+//! cargo.
+//!
+//! Instead of sending a secret token, cargo signs a short-lived PASETO
+//! `v3.public` token with the secret key of the user for every request. The
+//! token contains claims about the request, like the crate that is published,
+//! and is verified against the public key that the user registered.
+//!
+//! see <https://rust-lang.github.io/rfcs/3231-cargo-asymmetric-tokens.html>
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use p384::ecdsa::signature::{Signer, Verifier};
+use p384::ecdsa::{Signature, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha384};
+
+const TOKEN_HEADER: &str = "v3.public.";
+const PUBLIC_KEY_HEADER: &str = "k3.public.";
+const KEY_ID_HEADER: &str = "k3.pid.";
+
+/// Length of a P-384 signature, consisting of the `r` and `s` values.
+const SIGNATURE_LENGTH: usize = 96;
+
+/// Length of the SHA-384 digest prefix that is used as a key ID.
+const KEY_ID_LENGTH: usize = 33;
+
+/// Tokens are only accepted if they were issued at most this many minutes
+/// before or after the current time, which limits how long an intercepted
+/// token can be replayed.
+pub const REPLAY_WINDOW_MINUTES: i64 = 15;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AsymmetricTokenError {
+    #[error("the token is not a valid PASETO v3.public token")]
+    InvalidFormat,
+    #[error("the footer of the token is invalid")]
+    InvalidFooter,
+    #[error("the signature of the token is invalid")]
+    InvalidSignature,
+    #[error("the claims of the token are invalid")]
+    InvalidClaims,
+    #[error("the token was signed for `{0}` instead of this registry")]
+    UrlMismatch(String),
+    #[error("the token was issued outside of the accepted time window")]
+    Expired,
+    #[error("the token contains a challenge, but this registry does not issue challenges")]
+    UnexpectedChallenge,
+    #[error("the token was not signed for this operation")]
+    MutationMismatch,
+}
+
+/// An ECDSA P-384 public key, which users register in its PASERK
+/// `k3.public.` encoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(VerifyingKey);
+
+impl PublicKey {
+    pub fn from_paserk(value: &str) -> Option<Self> {
+        let encoded = value.strip_prefix(PUBLIC_KEY_HEADER)?;
+        let bytes = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+        // PASERK only allows the compressed point encoding
+        if bytes.len() != 49 {
+            return None;
+        }
+
+        VerifyingKey::from_sec1_bytes(&bytes).ok().map(Self)
+    }
+
+    pub fn to_paserk(&self) -> String {
+        format!(
+            "{PUBLIC_KEY_HEADER}{}",
+            URL_SAFE_NO_PAD.encode(self.compressed())
+        )
+    }
+
+    /// Returns the PASERK `k3.pid.` ID of the key, which cargo sends in the
+    /// `kip` field of the token footer.
+    pub fn key_id(&self) -> String {
+        let digest = Sha384::new()
+            .chain_update(KEY_ID_HEADER)
+            .chain_update(self.to_paserk())
+            .finalize();
+
+        let id = URL_SAFE_NO_PAD.encode(&digest[..KEY_ID_LENGTH]);
+        format!("{KEY_ID_HEADER}{id}")
+    }
+
+    fn compressed(&self) -> Vec<u8> {
+        self.0.to_encoded_point(true).as_bytes().to_vec()
+    }
+}
+
+impl From<&SigningKey> for PublicKey {
+    fn from(key: &SigningKey) -> Self {
+        Self(*key.verifying_key())
+    }
+}
+
+/// The unencrypted footer of a token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Footer {
+    /// The index URL of the registry that the token was signed for.
+    pub url: String,
+    /// The PASERK ID of the public key that verifies the token.
+    pub kip: String,
+}
+
+/// The signed claims of a token.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Claims {
+    /// The time the token was issued at, in RFC 3339 format.
+    pub iat: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    /// The operation that the token was signed for, if it is used for a
+    /// request that changes the registry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vers: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub v: Option<u8>,
+}
+
+impl Claims {
+    /// Checks that the token was issued within the [`REPLAY_WINDOW_MINUTES`] around
+    /// `now`, and that it doesn't depend on features that this registry
+    /// doesn't support.
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), AsymmetricTokenError> {
+        if !matches!(self.v, None | Some(1)) {
+            return Err(AsymmetricTokenError::InvalidClaims);
+        }
+
+        if self.challenge.is_some() {
+            return Err(AsymmetricTokenError::UnexpectedChallenge);
+        }
+
+        let iat = DateTime::parse_from_rfc3339(&self.iat)
+            .map_err(|_| AsymmetricTokenError::InvalidClaims)?;
+
+        if (now - iat.with_timezone(&Utc)).abs() > Duration::minutes(REPLAY_WINDOW_MINUTES) {
+            return Err(AsymmetricTokenError::Expired);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the token was signed for the given operation, or for a
+    /// read-only request if `mutation` is `None`.
+    pub fn validate_mutation(
+        &self,
+        mutation: Option<&Mutation>,
+    ) -> Result<(), AsymmetricTokenError> {
+        let expected = match mutation {
+            None => (None, None, None, None),
+            Some(Mutation::Publish { name, vers, cksum }) => {
+                (Some("publish"), Some(name), Some(vers), Some(cksum))
+            }
+            Some(Mutation::Yank { name, vers }) => (Some("yank"), Some(name), Some(vers), None),
+            Some(Mutation::Unyank { name, vers }) => (Some("unyank"), Some(name), Some(vers), None),
+            Some(Mutation::Owners { name }) => (Some("owners"), Some(name), None, None),
+        };
+
+        let actual = (
+            self.mutation.as_deref(),
+            self.name.as_ref(),
+            self.vers.as_ref(),
+            self.cksum.as_ref(),
+        );
+
+        if actual != expected {
+            return Err(AsymmetricTokenError::MutationMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+/// An operation that changes the registry, which a token has to be signed
+/// for explicitly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    Publish {
+        name: String,
+        vers: String,
+        /// The hex-encoded SHA-256 checksum of the crate file.
+        cksum: String,
+    },
+    Yank {
+        name: String,
+        vers: String,
+    },
+    Unyank {
+        name: String,
+        vers: String,
+    },
+    Owners {
+        name: String,
+    },
+}
+
+/// A parsed, but not yet verified token.
+#[derive(Debug)]
+pub struct AsymmetricToken {
+    message: Vec<u8>,
+    signature: Signature,
+    raw_footer: Vec<u8>,
+    pub footer: Footer,
+}
+
+impl AsymmetricToken {
+    /// Checks whether the value of an `Authorization` header looks like an
+    /// asymmetric token, instead of a regular API token.
+    pub fn is_asymmetric(value: &str) -> bool {
+        value.starts_with(TOKEN_HEADER)
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AsymmetricTokenError> {
+        use AsymmetricTokenError::*;
+
+        let value = value.strip_prefix(TOKEN_HEADER).ok_or(InvalidFormat)?;
+        let (payload, footer) = value.split_once('.').ok_or(InvalidFooter)?;
+
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| InvalidFormat)?;
+        if payload.len() < SIGNATURE_LENGTH {
+            return Err(InvalidFormat);
+        }
+
+        let (message, signature) = payload.split_at(payload.len() - SIGNATURE_LENGTH);
+        let signature = Signature::from_slice(signature).map_err(|_| InvalidSignature)?;
+
+        let raw_footer = URL_SAFE_NO_PAD.decode(footer).map_err(|_| InvalidFooter)?;
+        let footer = serde_json::from_slice(&raw_footer).map_err(|_| InvalidFooter)?;
+
+        Ok(Self {
+            message: message.to_vec(),
+            signature,
+            raw_footer,
+            footer,
+        })
+    }
+
+    /// Verifies the signature of the token with the given key and returns
+    /// its claims.
+    pub fn verify(&self, key: &PublicKey) -> Result<Claims, AsymmetricTokenError> {
+        let message = pre_auth_encode(&[
+            &key.compressed(),
+            TOKEN_HEADER.as_bytes(),
+            &self.message,
+            &self.raw_footer,
+            b"",
+        ]);
+
+        key.0
+            .verify(&message, &self.signature)
+            .map_err(|_| AsymmetricTokenError::InvalidSignature)?;
+
+        serde_json::from_slice(&self.message).map_err(|_| AsymmetricTokenError::InvalidClaims)
+    }
+
+    /// Creates a signed token, like the `cargo:paseto` credential provider.
+    pub fn sign(key: &SigningKey, claims: &Claims, url: &str) -> String {
+        let public_key = PublicKey::from(key);
+
+        let message = serde_json::to_vec(claims).unwrap();
+        let footer = Footer {
+            url: url.to_string(),
+            kip: public_key.key_id(),
+        };
+        let footer = serde_json::to_vec(&footer).unwrap();
+
+        let signed = pre_auth_encode(&[
+            &public_key.compressed(),
+            TOKEN_HEADER.as_bytes(),
+            &message,
+            &footer,
+            b"",
+        ]);
+        let signature: Signature = key.sign(&signed);
+
+        let mut payload = message;
+        payload.extend_from_slice(&signature.to_bytes());
+
+        format!(
+            "{TOKEN_HEADER}{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(footer)
+        )
+    }
+}
+
+/// The pre-authentication encoding of PASETO, which encodes the number of
+/// pieces and the length of each piece as little-endian 64-bit integers.
+fn pre_auth_encode(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut output = (pieces.len() as u64).to_le_bytes().to_vec();
+    for piece in pieces {
+        output.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        output.extend_from_slice(piece);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::{assert_err_eq, assert_ok, assert_ok_eq};
+
+    const URL: &str = "sparse+https://crates.io/index/";
+
+    fn signing_key(byte: u8) -> SigningKey {
+        SigningKey::from_bytes(&[byte; 48].into()).unwrap()
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            iat: Utc::now().to_rfc3339(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pae() {
+        // Test vectors from the PASETO specification
+        assert_eq!(pre_auth_encode(&[]), b"\x00\x00\x00\x00\x00\x00\x00\x00");
+        assert_eq!(
+            pre_auth_encode(&[b""]),
+            b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00"
+        );
+        assert_eq!(
+            pre_auth_encode(&[b"test"]),
+            b"\x01\x00\x00\x00\x00\x00\x00\x00\x04\x00\x00\x00\x00\x00\x00\x00test"
+        );
+    }
+
+    #[test]
+    fn public_key_paserk() {
+        let key = PublicKey::from(&signing_key(1));
+        let paserk = key.to_paserk();
+        assert!(paserk.starts_with("k3.public."));
+        assert_eq!(PublicKey::from_paserk(&paserk), Some(key.clone()));
+
+        assert!(key.key_id().starts_with("k3.pid."));
+        assert_eq!(key.key_id().len(), "k3.pid.".len() + 44);
+        assert_ne!(key.key_id(), PublicKey::from(&signing_key(2)).key_id());
+
+        assert_eq!(PublicKey::from_paserk("k3.public.invalid"), None);
+        assert_eq!(PublicKey::from_paserk(&paserk.replace("k3", "k4")), None);
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let secret = signing_key(1);
+        let key = PublicKey::from(&secret);
+        let claims = claims();
+
+        let token = AsymmetricToken::sign(&secret, &claims, URL);
+        assert!(AsymmetricToken::is_asymmetric(&token));
+
+        let parsed = assert_ok!(AsymmetricToken::parse(&token));
+        assert_eq!(parsed.footer.url, URL);
+        assert_eq!(parsed.footer.kip, key.key_id());
+        assert_ok_eq!(parsed.verify(&key), claims);
+
+        let other_key = PublicKey::from(&signing_key(2));
+        assert_err_eq!(
+            parsed.verify(&other_key),
+            AsymmetricTokenError::InvalidSignature
+        );
+
+        // The footer is covered by the signature
+        let (payload, _) = token.rsplit_once('.').unwrap();
+        let footer = Footer {
+            url: "https://example.com".into(),
+            kip: key.key_id(),
+        };
+        let footer = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&footer).unwrap());
+        let tampered = assert_ok!(AsymmetricToken::parse(&format!("{payload}.{footer}")));
+        assert_err_eq!(
+            tampered.verify(&key),
+            AsymmetricTokenError::InvalidSignature
+        );
+
+        assert_err_eq!(
+            AsymmetricToken::parse("cio1234567890"),
+            AsymmetricTokenError::InvalidFormat
+        );
+        assert_err_eq!(
+            AsymmetricToken::parse(payload),
+            AsymmetricTokenError::InvalidFooter
+        );
+    }
+
+    #[test]
+    fn validate_claims() {
+        let now = Utc::now();
+        assert_ok!(claims().validate(now));
+
+        let old = Claims {
+            iat: (now - Duration::hours(1)).to_rfc3339(),
+            ..Default::default()
+        };
+        assert_err_eq!(old.validate(now), AsymmetricTokenError::Expired);
+
+        let future = Claims {
+            iat: (now + Duration::hours(1)).to_rfc3339(),
+            ..Default::default()
+        };
+        assert_err_eq!(future.validate(now), AsymmetricTokenError::Expired);
+
+        let challenge = Claims {
+            challenge: Some("abc".into()),
+            ..claims()
+        };
+        assert_err_eq!(
+            challenge.validate(now),
+            AsymmetricTokenError::UnexpectedChallenge
+        );
+
+        let invalid = Claims {
+            iat: "yesterday".into(),
+            ..Default::default()
+        };
+        assert_err_eq!(invalid.validate(now), AsymmetricTokenError::InvalidClaims);
+    }
+
+    #[test]
+    fn validate_mutation() {
+        let publish = Mutation::Publish {
+            name: "foo".into(),
+            vers: "1.0.0".into(),
+            cksum: "abc".into(),
+        };
+        let publish_claims = Claims {
+            mutation: Some("publish".into()),
+            name: Some("foo".into()),
+            vers: Some("1.0.0".into()),
+            cksum: Some("abc".into()),
+            ..claims()
+        };
+        assert_ok!(publish_claims.validate_mutation(Some(&publish)));
+        assert_err_eq!(
+            publish_claims.validate_mutation(None),
+            AsymmetricTokenError::MutationMismatch
+        );
+        assert_ok!(claims().validate_mutation(None));
+        assert_err_eq!(
+            claims().validate_mutation(Some(&publish)),
+            AsymmetricTokenError::MutationMismatch
+        );
+
+        let other_version = Mutation::Publish {
+            name: "foo".into(),
+            vers: "1.0.1".into(),
+            cksum: "abc".into(),
+        };
+        assert_err_eq!(
+            publish_claims.validate_mutation(Some(&other_version)),
+            AsymmetricTokenError::MutationMismatch
+        );
+
+        let owners = Claims {
+            mutation: Some("owners".into()),
+            name: Some("foo".into()),
+            ..claims()
+        };
+        let mutation = Mutation::Owners { name: "foo".into() };
+        assert_ok!(owners.validate_mutation(Some(&mutation)));
+
+        let yank = Mutation::Yank {
+            name: "foo".into(),
+            vers: "1.0.0".into(),
+        };
+        assert_err_eq!(
+            owners.validate_mutation(Some(&yank)),
+            AsymmetricTokenError::MutationMismatch
+        );
+    }
+}
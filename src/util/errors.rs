@@ -63,6 +63,12 @@ pub fn account_locked(reason: &str, until: Option<NaiveDateTime>) -> BoxedAppErr
     })
 }
 
+/// Returns an error with status 403 for sensitive actions that the user has
+/// to verify their second factor for first.
+pub fn second_factor_required() -> BoxedAppError {
+    Box::new(json::SecondFactorRequired)
+}
+
 pub fn forbidden() -> BoxedAppError {
     Box::new(json::Forbidden)
 }
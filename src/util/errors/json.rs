@@ -225,6 +225,21 @@ impl fmt::Display for AccountLocked {
     }
 }
 
+#[derive(Debug)]
+pub(super) struct SecondFactorRequired;
+
+impl AppError for SecondFactorRequired {
+    fn response(&self) -> Response {
+        json_error(&self.to_string(), StatusCode::FORBIDDEN)
+    }
+}
+
+impl fmt::Display for SecondFactorRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "this action requires a recent second factor verification".fmt(f)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct OwnershipInvitationExpired {
     pub(crate) crate_name: String,
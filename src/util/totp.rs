@@ -0,0 +1,177 @@
+//! Time-based one-time passwords, as generated by authenticator apps.
+//!
+//! The codes use the parameters that all common authenticator apps support:
+//! HMAC-SHA1, six digits and a time step of 30 seconds.
+//!
+//! see <https://datatracker.ietf.org/doc/html/rfc6238>
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha1::Sha1;
+use url::Url;
+
+/// Length of the shared secret in bytes, as recommended by RFC 4226.
+const SECRET_LENGTH: usize = 20;
+
+const STEP_SECONDS: i64 = 30;
+
+const DIGITS: u32 = 6;
+
+/// Codes of this many time steps before and after the current one are
+/// accepted too, since the clocks of phones are not always accurate.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0; SECRET_LENGTH];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+/// Encodes the secret in the unpadded base32 encoding that users can type
+/// into their authenticator app.
+pub fn encode_secret(secret: &[u8]) -> String {
+    let mut encoded = String::with_capacity((secret.len() * 8 + 4) / 5);
+
+    for chunk in secret.chunks(5) {
+        let mut buffer = [0u8; 5];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let bits = buffer
+            .iter()
+            .fold(0u64, |bits, &byte| bits << 8 | byte as u64);
+
+        let chars = (chunk.len() * 8 + 4) / 5;
+        for i in 0..chars {
+            let index = (bits >> (35 - i * 5)) & 0x1f;
+            encoded.push(BASE32_ALPHABET[index as usize] as char);
+        }
+    }
+
+    encoded
+}
+
+/// Returns the `otpauth://` URI that authenticator apps can scan as a QR code.
+pub fn provisioning_uri(secret: &[u8], issuer: &str, account_name: &str) -> String {
+    let mut url = Url::parse("otpauth://totp/").expect("the base URI is valid");
+    url.set_path(&format!("{issuer}:{account_name}"));
+    url.query_pairs_mut()
+        .append_pair("secret", &encode_secret(secret))
+        .append_pair("issuer", issuer);
+    url.to_string()
+}
+
+pub fn time_step(time: DateTime<Utc>) -> i64 {
+    time.timestamp().div_euclid(STEP_SECONDS)
+}
+
+/// Generates the code of the given time step.
+pub fn generate(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation, as described in RFC 4226
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    let code = value % 10u32.pow(DIGITS);
+    format!("{code:0width$}", width = DIGITS as usize)
+}
+
+/// Verifies the code against the codes of the current time step and its
+/// neighbours, and returns the time step that it was generated for.
+///
+/// Codes of time steps up to and including `last_used_step` are rejected,
+/// so that every code can only be used once.
+pub fn verify(
+    secret: &[u8],
+    code: &str,
+    current_step: i64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+
+    let first_step = current_step - ALLOWED_DRIFT_STEPS;
+    let first_step = match last_used_step {
+        Some(last_used_step) => first_step.max(last_used_step + 1),
+        None => first_step,
+    };
+
+    (first_step..=current_step + ALLOWED_DRIFT_STEPS).find(|&step| generate(secret, step) == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// The SHA1 secret of the test vectors in RFC 6238.
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    fn step(timestamp: i64) -> i64 {
+        time_step(Utc.timestamp_opt(timestamp, 0).unwrap())
+    }
+
+    #[test]
+    fn rfc_6238_test_vectors() {
+        // The test vectors use eight digits, so only the last six are compared
+        assert_eq!(generate(SECRET, step(59)), "287082");
+        assert_eq!(generate(SECRET, step(1111111109)), "081804");
+        assert_eq!(generate(SECRET, step(1111111111)), "050471");
+        assert_eq!(generate(SECRET, step(1234567890)), "005924");
+        assert_eq!(generate(SECRET, step(2000000000)), "279037");
+    }
+
+    #[test]
+    fn secret_encoding() {
+        assert_eq!(encode_secret(b""), "");
+        assert_eq!(encode_secret(b"f"), "MY");
+        assert_eq!(encode_secret(b"foob"), "MZXW6YQ");
+        assert_eq!(encode_secret(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(encode_secret(SECRET), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    }
+
+    #[test]
+    fn uri() {
+        assert_eq!(
+            provisioning_uri(SECRET, "crates.io", "foo bar"),
+            "otpauth://totp/crates.io:foo%20bar?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=crates.io"
+        );
+    }
+
+    #[test]
+    fn verification() {
+        let current = step(1111111109);
+        let code = generate(SECRET, current);
+
+        assert_eq!(verify(SECRET, &code, current, None), Some(current));
+        assert_eq!(
+            verify(SECRET, &format!(" {code} "), current, None),
+            Some(current)
+        );
+        assert_eq!(verify(SECRET, &code, current + 1, None), Some(current));
+        assert_eq!(verify(SECRET, &code, current - 1, None), Some(current));
+        assert_eq!(verify(SECRET, &code, current + 2, None), None);
+
+        // Codes can only be used once
+        assert_eq!(verify(SECRET, &code, current, Some(current)), None);
+        assert_eq!(
+            verify(SECRET, &code, current, Some(current - 1)),
+            Some(current)
+        );
+
+        assert_eq!(verify(SECRET, "000000", current, None), None);
+        assert_eq!(verify(SECRET, "12345", current, None), None);
+    }
+}
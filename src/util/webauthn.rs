@@ -0,0 +1,260 @@
+//! Verification of WebAuthn credentials, like security keys and passkeys,
+//! that users enroll as a second factor.
+//!
+//! Only credentials with ECDSA P-256 keys (COSE algorithm `-7`) are
+//! supported, which every common authenticator can create. Attestations are
+//! not verified, since any authenticator is accepted. Instead of the CBOR
+//! encoded attestation object, the frontend sends the DER-encoded public key,
+//! as returned by `AuthenticatorAttestationResponse.getPublicKey()`.
+//!
+//! see <https://www.w3.org/TR/webauthn-2/>
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::DecodePublicKey;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const CHALLENGE_LENGTH: usize = 32;
+
+/// Length of the authenticator data without any extensions, consisting of
+/// the SHA-256 hash of the relying party ID, the flags and the signature
+/// counter.
+const AUTHENTICATOR_DATA_LENGTH: usize = 37;
+
+/// Flag of the authenticator data that is set if the user was present.
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum WebauthnError {
+    #[error("the client data is invalid")]
+    InvalidClientData,
+    #[error("the client data was created for another ceremony")]
+    TypeMismatch,
+    #[error("the client data does not contain the expected challenge")]
+    ChallengeMismatch,
+    #[error("the client data was created for another origin")]
+    OriginMismatch,
+    #[error("the authenticator data is invalid")]
+    InvalidAuthenticatorData,
+    #[error("the authenticator data was created for another relying party")]
+    RelyingPartyMismatch,
+    #[error("the user was not present")]
+    UserNotPresent,
+    #[error("the public key is not a DER-encoded ECDSA P-256 key")]
+    InvalidPublicKey,
+    #[error("the signature is invalid")]
+    InvalidSignature,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Generates a random challenge in the base64url encoding that browsers use
+/// for the `challenge` field of the client data.
+pub fn generate_challenge() -> String {
+    let mut challenge = [0; CHALLENGE_LENGTH];
+    OsRng.fill_bytes(&mut challenge);
+    URL_SAFE_NO_PAD.encode(challenge)
+}
+
+/// Verifies the response of a `navigator.credentials.create()` call and
+/// returns the public key that can be stored for the credential.
+pub fn verify_registration(
+    client_data_json: &[u8],
+    public_key: &[u8],
+    challenge: &str,
+    rp_id: &str,
+) -> Result<Vec<u8>, WebauthnError> {
+    verify_client_data(client_data_json, "webauthn.create", challenge, rp_id)?;
+
+    VerifyingKey::from_public_key_der(public_key).map_err(|_| WebauthnError::InvalidPublicKey)?;
+
+    Ok(public_key.to_vec())
+}
+
+/// Verifies the response of a `navigator.credentials.get()` call and returns
+/// the signature counter of the authenticator.
+pub fn verify_assertion(
+    public_key: &[u8],
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+    challenge: &str,
+    rp_id: &str,
+) -> Result<u32, WebauthnError> {
+    verify_client_data(client_data_json, "webauthn.get", challenge, rp_id)?;
+
+    if authenticator_data.len() < AUTHENTICATOR_DATA_LENGTH {
+        return Err(WebauthnError::InvalidAuthenticatorData);
+    }
+
+    if authenticator_data[..32] != Sha256::digest(rp_id.as_bytes())[..] {
+        return Err(WebauthnError::RelyingPartyMismatch);
+    }
+
+    if authenticator_data[32] & FLAG_USER_PRESENT == 0 {
+        return Err(WebauthnError::UserNotPresent);
+    }
+
+    let key = VerifyingKey::from_public_key_der(public_key)
+        .map_err(|_| WebauthnError::InvalidPublicKey)?;
+    let signature = Signature::from_der(signature).map_err(|_| WebauthnError::InvalidSignature)?;
+
+    let mut message = authenticator_data.to_vec();
+    message.extend_from_slice(&Sha256::digest(client_data_json));
+    key.verify(&message, &signature)
+        .map_err(|_| WebauthnError::InvalidSignature)?;
+
+    let sign_count: [u8; 4] = authenticator_data[33..37].try_into().unwrap();
+    Ok(u32::from_be_bytes(sign_count))
+}
+
+fn verify_client_data(
+    client_data_json: &[u8],
+    expected_type: &str,
+    challenge: &str,
+    rp_id: &str,
+) -> Result<(), WebauthnError> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| WebauthnError::InvalidClientData)?;
+
+    if client_data.ty != expected_type {
+        return Err(WebauthnError::TypeMismatch);
+    }
+
+    if client_data.challenge != challenge {
+        return Err(WebauthnError::ChallengeMismatch);
+    }
+
+    if client_data.origin != format!("https://{rp_id}") {
+        return Err(WebauthnError::OriginMismatch);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::SigningKey;
+    use p256::pkcs8::EncodePublicKey;
+
+    const RP_ID: &str = "crates.io";
+    const CHALLENGE: &str = "Y2hhbGxlbmdl";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[1; 32].into()).unwrap()
+    }
+
+    fn public_key() -> Vec<u8> {
+        let key = VerifyingKey::from(&signing_key());
+        key.to_public_key_der().unwrap().as_bytes().to_vec()
+    }
+
+    fn client_data_json(ty: &str, origin: &str) -> Vec<u8> {
+        serde_json::to_vec(&json!({ "type": ty, "challenge": CHALLENGE, "origin": origin }))
+            .unwrap()
+    }
+
+    fn authenticator_data(rp_id: &str, flags: u8, sign_count: u32) -> Vec<u8> {
+        let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend_from_slice(&sign_count.to_be_bytes());
+        data
+    }
+
+    fn sign(authenticator_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+        let mut message = authenticator_data.to_vec();
+        message.extend_from_slice(&Sha256::digest(client_data));
+        let signature: Signature = signing_key().sign(&message);
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    #[test]
+    fn registration() {
+        let client_data = client_data_json("webauthn.create", "https://crates.io");
+        let public_key = public_key();
+
+        assert_ok_eq!(
+            verify_registration(&client_data, &public_key, CHALLENGE, RP_ID),
+            public_key
+        );
+        assert_err_eq!(
+            verify_registration(&client_data, &public_key, "other", RP_ID),
+            WebauthnError::ChallengeMismatch
+        );
+        assert_err_eq!(
+            verify_registration(&client_data, b"invalid", CHALLENGE, RP_ID),
+            WebauthnError::InvalidPublicKey
+        );
+
+        let client_data = client_data_json("webauthn.get", "https://crates.io");
+        assert_err_eq!(
+            verify_registration(&client_data, &public_key, CHALLENGE, RP_ID),
+            WebauthnError::TypeMismatch
+        );
+
+        let client_data = client_data_json("webauthn.create", "https://evil.example");
+        assert_err_eq!(
+            verify_registration(&client_data, &public_key, CHALLENGE, RP_ID),
+            WebauthnError::OriginMismatch
+        );
+    }
+
+    #[test]
+    fn assertion() {
+        let public_key = public_key();
+        let client_data = client_data_json("webauthn.get", "https://crates.io");
+        let auth_data = authenticator_data(RP_ID, FLAG_USER_PRESENT, 42);
+        let signature = sign(&auth_data, &client_data);
+
+        let verify = |auth_data: &[u8], signature: &[u8]| {
+            verify_assertion(
+                &public_key,
+                &client_data,
+                auth_data,
+                signature,
+                CHALLENGE,
+                RP_ID,
+            )
+        };
+
+        assert_ok_eq!(verify(&auth_data, &signature), 42);
+
+        // The signature doesn't match the authenticator data
+        let other_auth_data = authenticator_data(RP_ID, FLAG_USER_PRESENT, 43);
+        assert_err_eq!(
+            verify(&other_auth_data, &signature),
+            WebauthnError::InvalidSignature
+        );
+
+        let auth_data = authenticator_data("evil.example", FLAG_USER_PRESENT, 42);
+        let signature = sign(&auth_data, &client_data);
+        assert_err_eq!(
+            verify(&auth_data, &signature),
+            WebauthnError::RelyingPartyMismatch
+        );
+
+        let auth_data = authenticator_data(RP_ID, 0, 42);
+        let signature = sign(&auth_data, &client_data);
+        assert_err_eq!(
+            verify(&auth_data, &signature),
+            WebauthnError::UserNotPresent
+        );
+
+        assert_err_eq!(
+            verify(&auth_data[..36], &signature),
+            WebauthnError::InvalidAuthenticatorData
+        );
+    }
+}
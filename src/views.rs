@@ -1,22 +1,70 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use secrecy::ExposeSecret;
 use url::Url;
 
 use crate::github;
 use crate::models::{
-    ApiToken, Category, Crate, CrateOwnerInvitation, CreatedApiToken, Dependency, DependencyKind,
-    Keyword, Owner, ReverseDependency, Team, TopVersions, User, Version, VersionDownload,
-    VersionOwnerAction,
+    Advisory, ApiToken, Category, Crate, CrateAuditAction, CrateOwnerInvitation, CreatedApiToken,
+    Dependency, DependencyKind, DeprecationState, Keyword, Owner, Publish, PublishStatus,
+    ReverseDependency, SigningKey, Team, TopVersions, TrustedPublisher, User, Version,
+    VersionAction, VersionDownload, VersionOwnerAction, Webhook, WebhookDelivery, WebhookEvent,
+    YankReason,
 };
 use crate::util::rfc3339;
 
+pub mod atom;
 pub mod krate_publish;
-pub use self::krate_publish::{EncodableCrateDependency, PublishMetadata};
+pub use self::krate_publish::{EncodableCrateDependency, PublishMetadata, PublishSignature};
 
 /// Hosts in this list are known to not be hosting documentation,
 /// and are possibly of malicious intent e.g. ad tracking networks, etc.
 const DOCUMENTATION_BLOCKLIST: &[&str] = &["rust-ci.org", "rustless.org", "ironframework.io"];
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableAdvisory {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub date: NaiveDate,
+    pub url: Option<String>,
+    pub aliases: Vec<String>,
+    pub informational: Option<String>,
+    pub patched: Vec<String>,
+    pub unaffected: Vec<String>,
+    /// The versions of the crate that are affected by the advisory.
+    pub affected_versions: Vec<String>,
+}
+
+impl EncodableAdvisory {
+    pub fn from(advisory: Advisory, affected_versions: Vec<String>) -> Self {
+        let Advisory {
+            id,
+            title,
+            description,
+            date,
+            url,
+            aliases,
+            informational,
+            patched,
+            unaffected,
+            ..
+        } = advisory;
+
+        Self {
+            id,
+            title,
+            description,
+            date,
+            url,
+            aliases,
+            informational,
+            patched,
+            unaffected,
+            affected_versions,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EncodableCategory {
     pub id: String,
@@ -220,10 +268,21 @@ pub struct EncodableCrate {
     pub homepage: Option<String>,
     pub documentation: Option<String>,
     pub repository: Option<String>,
+    /// The deprecation state set by the crate owners, if the crate is
+    /// deprecated or unmaintained.
+    pub deprecation: Option<EncodableCrateDeprecation>,
     pub links: EncodableCrateLinks,
     pub exact_match: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct EncodableCrateDeprecation {
+    pub state: DeprecationState,
+    pub message: Option<String>,
+    /// The name of the crate that replaces the deprecated crate.
+    pub successor: Option<String>,
+}
+
 impl EncodableCrate {
     #[allow(clippy::too_many_arguments)]
     pub fn from(
@@ -245,6 +304,9 @@ impl EncodableCrate {
             homepage,
             documentation,
             repository,
+            deprecation,
+            deprecation_message,
+            successor,
             ..
         } = krate;
         let versions_link = match versions {
@@ -255,6 +317,11 @@ impl EncodableCrate {
         let category_ids = categories.map(|cats| cats.iter().map(|cat| cat.slug.clone()).collect());
         let badges = badges.map(|_| vec![]);
         let documentation = Self::remove_blocked_documentation_urls(documentation);
+        let deprecation = deprecation.map(|state| EncodableCrateDeprecation {
+            state,
+            message: deprecation_message,
+            successor,
+        });
 
         let max_version = top_versions
             .and_then(|v| v.highest.as_ref())
@@ -300,6 +367,7 @@ impl EncodableCrate {
             exact_match,
             description,
             repository,
+            deprecation,
             links: EncodableCrateLinks {
                 version_downloads: format!("/api/v1/crates/{name}/downloads"),
                 versions: versions_link,
@@ -583,6 +651,30 @@ pub struct EncodableAuditAction {
     pub time: NaiveDateTime,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableCrateAuditAction {
+    pub id: i32,
+    pub action: String,
+    /// The user that performed the action, or `None` if it was performed by
+    /// the crates.io team.
+    pub user: Option<EncodablePublicUser>,
+    pub details: serde_json::Value,
+    #[serde(with = "rfc3339")]
+    pub time: NaiveDateTime,
+}
+
+impl EncodableCrateAuditAction {
+    pub fn from(action: CrateAuditAction, user: Option<User>) -> Self {
+        Self {
+            id: action.id,
+            action: action.action.into(),
+            user: user.map(User::into),
+            details: action.details,
+            time: action.time,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EncodableVersion {
     pub id: i32,
@@ -599,6 +691,10 @@ pub struct EncodableVersion {
     pub downloads: i32,
     pub features: serde_json::Value,
     pub yanked: bool,
+    /// The reason given for the most recent yank, if the version is yanked.
+    pub yank_reason: Option<YankReason>,
+    /// The ids of the security advisories that affect this version.
+    pub advisories: Vec<String>,
     // NOTE: Used by shields.io, altering `license` requires a PR with shields.io
     pub license: Option<String>,
     pub links: EncodableVersionLinks,
@@ -615,6 +711,7 @@ impl EncodableVersion {
         crate_name: &str,
         published_by: Option<User>,
         audit_actions: Vec<(VersionOwnerAction, User)>,
+        advisories: Vec<String>,
     ) -> Self {
         let Version {
             id,
@@ -631,6 +728,16 @@ impl EncodableVersion {
             ..
         } = version;
 
+        let yank_reason = if yanked {
+            audit_actions
+                .iter()
+                .rev()
+                .find(|(audit_action, _)| audit_action.action == VersionAction::Yank)
+                .and_then(|(audit_action, _)| audit_action.yank_reason())
+        } else {
+            None
+        };
+
         let links = EncodableVersionLinks {
             dependencies: format!("/api/v1/crates/{crate_name}/{num}/dependencies"),
             version_downloads: format!("/api/v1/crates/{crate_name}/{num}/downloads"),
@@ -648,6 +755,8 @@ impl EncodableVersion {
             downloads,
             features,
             yanked,
+            yank_reason,
+            advisories,
             license,
             links,
             crate_size,
@@ -677,9 +786,189 @@ pub struct EncodableVersionLinks {
 pub struct GoodCrate {
     #[serde(rename = "crate")]
     pub krate: EncodableCrate,
+    pub publish: EncodablePublish,
     pub warnings: PublishWarnings,
 }
 
+/// The serialization format for the detached signature of a crate file.
+#[derive(Serialize, Debug)]
+pub struct EncodableVersionSignature {
+    /// The base64-encoded, DER-encoded ECDSA P-256 signature.
+    pub signature: String,
+    /// The location of the raw signature file in the storage backend.
+    pub url: String,
+    /// The signing key that the signature was verified against.
+    pub key: SigningKey,
+    pub signed_by: EncodablePublicUser,
+    #[serde(with = "rfc3339")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodablePublish {
+    pub id: i32,
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub version: String,
+    pub status: PublishStatus,
+    pub error: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "rfc3339")]
+    pub updated_at: NaiveDateTime,
+}
+
+impl EncodablePublish {
+    pub fn from(publish: Publish, crate_name: &str, version: &str) -> Self {
+        let Publish {
+            id,
+            status,
+            error,
+            created_at,
+            updated_at,
+            ..
+        } = publish;
+
+        Self {
+            id,
+            krate: crate_name.to_string(),
+            version: version.to_string(),
+            status,
+            error,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableTrustedPublisher {
+    pub id: i32,
+    pub repository: String,
+    pub workflow_filename: String,
+    pub environment: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: NaiveDateTime,
+}
+
+impl From<TrustedPublisher> for EncodableTrustedPublisher {
+    fn from(publisher: TrustedPublisher) -> Self {
+        let TrustedPublisher {
+            id,
+            repository,
+            workflow_filename,
+            environment,
+            created_at,
+            ..
+        } = publisher;
+
+        Self {
+            id,
+            repository,
+            workflow_filename,
+            environment,
+            created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableWebhook {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(with = "rfc3339")]
+    pub created_at: NaiveDateTime,
+}
+
+impl From<Webhook> for EncodableWebhook {
+    fn from(webhook: Webhook) -> Self {
+        let Webhook {
+            id,
+            url,
+            events,
+            created_at,
+            ..
+        } = webhook;
+
+        Self {
+            id,
+            url,
+            events,
+            created_at,
+        }
+    }
+}
+
+/// The webhook including its secret, which is only returned once, when the
+/// webhook is created.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableWebhookWithSecret {
+    #[serde(flatten)]
+    pub webhook: EncodableWebhook,
+    pub secret: String,
+}
+
+impl From<Webhook> for EncodableWebhookWithSecret {
+    fn from(webhook: Webhook) -> Self {
+        let secret = webhook.secret.clone();
+
+        Self {
+            webhook: webhook.into(),
+            secret,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableWebhookDelivery {
+    pub id: i64,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "rfc3339::option")]
+    pub last_attempt_at: Option<NaiveDateTime>,
+    #[serde(with = "rfc3339::option")]
+    pub delivered_at: Option<NaiveDateTime>,
+    #[serde(with = "rfc3339::option")]
+    pub failed_at: Option<NaiveDateTime>,
+}
+
+impl From<WebhookDelivery> for EncodableWebhookDelivery {
+    fn from(delivery: WebhookDelivery) -> Self {
+        let WebhookDelivery {
+            id,
+            event,
+            payload,
+            attempts,
+            last_status,
+            last_error,
+            created_at,
+            last_attempt_at,
+            delivered_at,
+            failed_at,
+            ..
+        } = delivery;
+
+        Self {
+            id,
+            event,
+            payload,
+            attempts,
+            last_status,
+            last_error,
+            created_at,
+            last_attempt_at,
+            delivered_at,
+            failed_at,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PublishWarnings {
     pub invalid_categories: Vec<String>,
@@ -768,6 +1057,8 @@ mod tests {
             downloads: 0,
             features: serde_json::from_str("{}").unwrap(),
             yanked: false,
+            yank_reason: None,
+            advisories: vec![],
             license: None,
             links: EncodableVersionLinks {
                 dependencies: "".to_string(),
@@ -829,6 +1120,7 @@ mod tests {
             homepage: None,
             documentation: None,
             repository: None,
+            deprecation: None,
             links: EncodableCrateLinks {
                 version_downloads: "".to_string(),
                 versions: None,
@@ -0,0 +1,211 @@
+//! Rendering of Atom feeds, as specified in [RFC 4287].
+//!
+//! [RFC 4287]: https://www.rfc-editor.org/rfc/rfc4287
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+pub const CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+#[derive(Debug)]
+pub struct Feed {
+    /// A permanent, unique identifier of the feed, usually its URL.
+    pub id: String,
+    pub title: String,
+    /// The URL of the web page that corresponds to the feed.
+    pub link: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub struct Entry {
+    /// A permanent, unique identifier of the entry, usually its URL.
+    pub id: String,
+    pub title: String,
+    /// The URL of the web page that corresponds to the entry.
+    pub link: String,
+    /// Additional links of the entry, as `(rel, title, href)` tuples.
+    pub related: Vec<(&'static str, &'static str, String)>,
+    pub author: Option<Author>,
+    pub categories: Vec<&'static str>,
+    pub summary: String,
+    pub published: NaiveDateTime,
+    pub updated: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct Author {
+    pub name: String,
+    pub uri: Option<String>,
+}
+
+impl Feed {
+    /// Renders the feed as an XML document.
+    ///
+    /// The `updated` timestamp of the feed is the most recent `updated`
+    /// timestamp of its entries, or the current time if it has no entries.
+    pub fn render(&self) -> String {
+        let updated = self
+            .entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .unwrap_or_else(|| Utc::now().naive_utc());
+
+        let mut xml = String::new();
+        xml.push_str(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+        xml.push('\n');
+        xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+        xml.push('\n');
+        element(&mut xml, 1, "id", &self.id);
+        element(&mut xml, 1, "title", &self.title);
+        link(&mut xml, 1, "self", None, &self.id);
+        link(&mut xml, 1, "alternate", None, &self.link);
+        element(&mut xml, 1, "updated", &format_date(updated));
+
+        for entry in &self.entries {
+            entry.render(&mut xml);
+        }
+
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+impl Entry {
+    fn render(&self, xml: &mut String) {
+        xml.push_str("  <entry>\n");
+        element(xml, 2, "id", &self.id);
+        element(xml, 2, "title", &self.title);
+        link(xml, 2, "alternate", None, &self.link);
+        for (rel, title, href) in &self.related {
+            link(xml, 2, rel, Some(title), href);
+        }
+
+        if let Some(author) = &self.author {
+            xml.push_str("    <author>\n");
+            element(xml, 3, "name", &author.name);
+            if let Some(uri) = &author.uri {
+                element(xml, 3, "uri", uri);
+            }
+            xml.push_str("    </author>\n");
+        }
+
+        for category in &self.categories {
+            let _ = writeln!(xml, r#"    <category term="{}"/>"#, escape(category));
+        }
+
+        element(xml, 2, "summary", &self.summary);
+        element(xml, 2, "published", &format_date(self.published));
+        element(xml, 2, "updated", &format_date(self.updated));
+        xml.push_str("  </entry>\n");
+    }
+}
+
+fn element(xml: &mut String, depth: usize, name: &str, text: &str) {
+    let indent = "  ".repeat(depth);
+    let _ = writeln!(xml, "{indent}<{name}>{}</{name}>", escape(text));
+}
+
+fn link(xml: &mut String, depth: usize, rel: &str, title: Option<&str>, href: &str) {
+    let indent = "  ".repeat(depth);
+    let _ = write!(xml, r#"{indent}<link rel="{rel}" href="{}""#, escape(href));
+    if let Some(title) = title {
+        let _ = write!(xml, r#" title="{}""#, escape(title));
+    }
+    xml.push_str("/>\n");
+}
+
+fn format_date(date: NaiveDateTime) -> String {
+    DateTime::<Utc>::from_naive_utc_and_offset(date, Utc).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Escapes the characters that have a special meaning in XML text and
+/// attribute values.
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("foo"), "foo");
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let date = NaiveDate::from_ymd_opt(2023, 11, 6)
+            .unwrap()
+            .and_hms_opt(9, 4, 15)
+            .unwrap();
+
+        let feed = Feed {
+            id: "https://crates.io/feed.atom".into(),
+            title: "Releases of <foo>".into(),
+            link: "https://crates.io/crates/foo".into(),
+            entries: vec![Entry {
+                id: "https://crates.io/crates/foo/1.0.0".into(),
+                title: "foo 1.0.0".into(),
+                link: "https://crates.io/crates/foo/1.0.0".into(),
+                related: vec![("related", "README", "https://crates.io/readme?a&b".into())],
+                author: Some(Author {
+                    name: "foo".into(),
+                    uri: None,
+                }),
+                categories: vec!["yanked"],
+                summary: "foo 1.0.0 was published by foo.".into(),
+                published: date,
+                updated: date,
+            }],
+        };
+
+        insta::assert_snapshot!(feed.render(), @r###"
+        <?xml version="1.0" encoding="utf-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <id>https://crates.io/feed.atom</id>
+          <title>Releases of &lt;foo&gt;</title>
+          <link rel="self" href="https://crates.io/feed.atom"/>
+          <link rel="alternate" href="https://crates.io/crates/foo"/>
+          <updated>2023-11-06T09:04:15Z</updated>
+          <entry>
+            <id>https://crates.io/crates/foo/1.0.0</id>
+            <title>foo 1.0.0</title>
+            <link rel="alternate" href="https://crates.io/crates/foo/1.0.0"/>
+            <link rel="related" href="https://crates.io/readme?a&amp;b" title="README"/>
+            <author>
+              <name>foo</name>
+            </author>
+            <category term="yanked"/>
+            <summary>foo 1.0.0 was published by foo.</summary>
+            <published>2023-11-06T09:04:15Z</published>
+            <updated>2023-11-06T09:04:15Z</updated>
+          </entry>
+        </feed>
+        "###);
+    }
+}
@@ -13,6 +13,18 @@ pub struct PublishMetadata {
     pub vers: String,
     pub readme: Option<String>,
     pub readme_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PublishSignature>,
+}
+
+/// A detached signature of the uploaded crate file, created with one of the
+/// signing keys of the publishing user.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PublishSignature {
+    /// The fingerprint of the signing key.
+    pub key: String,
+    /// The base64-encoded, DER-encoded ECDSA P-256 signature.
+    pub signature: String,
 }
 
 #[derive(Debug)]
@@ -1,6 +1,8 @@
 use crate::cloudfront::CloudFront;
+use crate::email::Emails;
 use crate::fastly::Fastly;
 use crate::storage::Storage;
+use crate::worker::jobs::GitIndexBatching;
 use crate::worker::swirl::PerformError;
 use crates_io_index::Repository;
 use reqwest::blocking::Client;
@@ -13,6 +15,8 @@ pub struct Environment {
     cloudfront: Option<CloudFront>,
     fastly: Option<Fastly>,
     pub storage: AssertUnwindSafe<Arc<Storage>>,
+    emails: AssertUnwindSafe<Arc<Emails>>,
+    git_index_batching: Option<GitIndexBatching>,
 }
 
 impl Environment {
@@ -22,6 +26,8 @@ impl Environment {
         cloudfront: Option<CloudFront>,
         fastly: Option<Fastly>,
         storage: Arc<Storage>,
+        emails: Arc<Emails>,
+        git_index_batching: Option<GitIndexBatching>,
     ) -> Self {
         Self {
             index: Mutex::new(index),
@@ -29,6 +35,8 @@ impl Environment {
             cloudfront,
             fastly,
             storage: AssertUnwindSafe(storage),
+            emails: AssertUnwindSafe(emails),
+            git_index_batching,
         }
     }
 
@@ -51,4 +59,12 @@ impl Environment {
     pub(crate) fn fastly(&self) -> Option<&Fastly> {
         self.fastly.as_ref()
     }
+
+    pub(crate) fn emails(&self) -> &Emails {
+        &self.emails
+    }
+
+    pub(crate) fn git_index_batching(&self) -> Option<GitIndexBatching> {
+        self.git_index_batching
+    }
 }
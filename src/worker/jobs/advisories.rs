@@ -0,0 +1,289 @@
+//! Import security advisories from a local checkout of a RustSec-format
+//! advisory database (e.g. <https://github.com/rustsec/advisory-db>).
+//!
+//! Every advisory is stored as a Markdown file in the
+//! `crates/<crate_name>/` directory of the database. The metadata of the
+//! advisory is a TOML code block at the start of the file, followed by the
+//! title (as a `#` heading) and the description of the advisory.
+
+use crate::models::{Crate, NewAdvisory, Version, VersionAdvisory};
+use crate::schema::{advisories, version_advisories, versions};
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use anyhow::{anyhow, Context};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct ImportAdvisories {
+    path: PathBuf,
+}
+
+impl ImportAdvisories {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl BackgroundJob for ImportAdvisories {
+    const JOB_NAME: &'static str = "import_advisories";
+
+    type Context = Arc<Environment>;
+
+    /// Imports all advisories of the advisory database and flags the
+    /// versions that are affected by them.
+    ///
+    /// Advisories that are no longer part of the database, or that have
+    /// been withdrawn, are removed again. Since versions that are published
+    /// after the import are not flagged automatically, this job is meant to
+    /// be run periodically.
+    #[instrument(skip_all, fields(path = %self.path.display()))]
+    fn run(&self, state: PerformState<'_>, _env: &Self::Context) -> Result<(), PerformError> {
+        let conn = state.conn;
+
+        info!("Importing advisories");
+
+        let files = advisory_files(&self.path)?;
+        let mut imported_ids = Vec::with_capacity(files.len());
+
+        for path in files {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+
+            let advisory = match AdvisoryFile::parse(&content) {
+                Ok(advisory) => advisory,
+                Err(error) => {
+                    warn!(path = %path.display(), "Skipping invalid advisory: {error}");
+                    continue;
+                }
+            };
+
+            let metadata = &advisory.metadata.advisory;
+            if metadata.withdrawn.is_some() {
+                debug!(advisory.id = %metadata.id, "Skipping withdrawn advisory");
+                continue;
+            }
+
+            let krate: Option<Crate> = Crate::by_exact_name(&metadata.package)
+                .first(conn)
+                .optional()?;
+            let Some(krate) = krate else {
+                debug!(advisory.id = %metadata.id, "Skipping advisory for unknown crate");
+                continue;
+            };
+
+            conn.transaction(|conn| import_advisory(&advisory, &krate, conn))?;
+            imported_ids.push(metadata.id.clone());
+        }
+
+        let imported = imported_ids.len();
+        let deleted = diesel::delete(advisories::table)
+            .filter(advisories::id.ne_all(imported_ids))
+            .execute(conn)?;
+
+        info!(imported, deleted, "Finished importing advisories");
+
+        Ok(())
+    }
+}
+
+/// Returns the paths of all advisory files in the `crates` directory of the
+/// advisory database, sorted by path.
+fn advisory_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let crates_path = path.join("crates");
+    let crates = fs::read_dir(&crates_path)
+        .with_context(|| format!("Failed to read {}", crates_path.display()))?;
+
+    let mut files = Vec::new();
+    for entry in crates {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        for entry in fs::read_dir(entry.path())? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "md") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn import_advisory(
+    advisory: &AdvisoryFile,
+    krate: &Crate,
+    conn: &mut PgConnection,
+) -> anyhow::Result<()> {
+    let metadata = &advisory.metadata.advisory;
+    let date = NaiveDate::parse_from_str(&metadata.date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date in advisory {}", metadata.id))?;
+
+    let advisory = NewAdvisory {
+        id: &metadata.id,
+        crate_id: krate.id,
+        title: &advisory.title,
+        description: &advisory.description,
+        date,
+        url: metadata.url.as_deref(),
+        aliases: &metadata.aliases,
+        informational: metadata.informational.as_deref(),
+        patched: &advisory.metadata.versions.patched,
+        unaffected: &advisory.metadata.versions.unaffected,
+    }
+    .upsert(conn)?;
+
+    let versions: Vec<(i32, String)> = Version::belonging_to(krate)
+        .select((versions::id, versions::num))
+        .load(conn)?;
+
+    let affected = versions
+        .into_iter()
+        .filter(|(_, num)| {
+            semver::Version::parse(num).is_ok_and(|version| advisory.affects(&version))
+        })
+        .map(|(version_id, _)| VersionAdvisory {
+            version_id,
+            advisory_id: advisory.id.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    diesel::delete(VersionAdvisory::belonging_to(&advisory)).execute(conn)?;
+    diesel::insert_into(version_advisories::table)
+        .values(&affected)
+        .execute(conn)?;
+
+    debug!(
+        advisory.id = %advisory.id,
+        affected_versions = affected.len(),
+        "Imported advisory"
+    );
+
+    Ok(())
+}
+
+/// The parsed content of an advisory file.
+#[derive(Debug)]
+struct AdvisoryFile {
+    metadata: AdvisoryMetadata,
+    title: String,
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdvisoryMetadata {
+    advisory: AdvisorySection,
+    #[serde(default)]
+    versions: VersionsSection,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdvisorySection {
+    id: String,
+    package: String,
+    date: String,
+    url: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    informational: Option<String>,
+    withdrawn: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct VersionsSection {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+impl AdvisoryFile {
+    fn parse(content: &str) -> anyhow::Result<Self> {
+        let content = content.trim_start();
+        let content = content
+            .strip_prefix("```toml")
+            .ok_or_else(|| anyhow!("missing TOML front matter"))?;
+
+        let (toml, markdown) = content
+            .split_once("\n```")
+            .ok_or_else(|| anyhow!("unterminated TOML front matter"))?;
+
+        let metadata: AdvisoryMetadata = toml::from_str(toml)?;
+
+        let markdown = markdown.trim_start();
+        let (title, description) = markdown.split_once('\n').unwrap_or((markdown, ""));
+        let title = title
+            .strip_prefix("# ")
+            .ok_or_else(|| anyhow!("missing title"))?
+            .trim()
+            .to_string();
+
+        let description = description.trim().to_string();
+
+        Ok(Self {
+            metadata,
+            title,
+            description,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+
+    #[test]
+    fn parse_advisory_file() {
+        let content = r#"```toml
+[advisory]
+id = "RUSTSEC-2023-0001"
+package = "foo"
+date = "2023-01-02"
+url = "https://example.com/advisory"
+aliases = ["CVE-2023-0001"]
+
+[versions]
+patched = [">= 1.2.3"]
+unaffected = ["< 1.0.0"]
+```
+
+# Memory corruption in `foo`
+
+Affected versions of this crate did not check bounds.
+
+The fix was released in 1.2.3.
+"#;
+
+        let advisory = assert_ok!(AdvisoryFile::parse(content));
+        assert_eq!(advisory.metadata.advisory.id, "RUSTSEC-2023-0001");
+        assert_eq!(advisory.metadata.advisory.package, "foo");
+        assert_eq!(advisory.metadata.advisory.aliases, vec!["CVE-2023-0001"]);
+        assert_none!(advisory.metadata.advisory.informational);
+        assert_eq!(advisory.metadata.versions.patched, vec![">= 1.2.3"]);
+        assert_eq!(advisory.metadata.versions.unaffected, vec!["< 1.0.0"]);
+        assert_eq!(advisory.title, "Memory corruption in `foo`");
+        assert_eq!(
+            advisory.description,
+            "Affected versions of this crate did not check bounds.\n\nThe fix was released in 1.2.3."
+        );
+    }
+
+    #[test]
+    fn parse_invalid_advisory_file() {
+        assert_err!(AdvisoryFile::parse("# Title only"));
+        assert_err!(AdvisoryFile::parse("```toml\n[advisory]\nid = \"X\"\n"));
+
+        let content = "```toml\n[advisory]\nid = \"X\"\n```\n\n# Title\n";
+        assert_err!(AdvisoryFile::parse(content));
+
+        let content = "```toml\n[advisory]\nid = \"X\"\npackage = \"foo\"\ndate = \"2023-01-02\"\n```\n\nNo title\n";
+        assert_err!(AdvisoryFile::parse(content));
+    }
+}
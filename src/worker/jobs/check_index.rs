@@ -0,0 +1,393 @@
+//! Check that the git index and the sparse index match the database.
+
+use crate::schema::crates;
+use crate::storage::Storage;
+use crate::worker::jobs::git::get_index_data;
+use crate::worker::jobs::{SyncToGitIndex, SyncToSparseIndex};
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use anyhow::Context;
+use crates_io_index::Repository;
+use diesel::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct CheckIndex {
+    /// Whether index sync jobs are enqueued for the crates that drifted.
+    fix: bool,
+}
+
+impl CheckIndex {
+    pub fn new(fix: bool) -> Self {
+        Self { fix }
+    }
+}
+
+impl BackgroundJob for CheckIndex {
+    const JOB_NAME: &'static str = "check_index";
+
+    type Context = Arc<Environment>;
+
+    #[instrument(skip_all, fields(fix = self.fix))]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        info!("Checking the index for drift from the database");
+
+        let conn = state.conn;
+
+        let drifts = {
+            let repo = env.lock_index()?;
+            check_index(conn, &repo, &env.storage)?
+        };
+
+        for drift in &drifts {
+            warn!(krate.name = %drift.krate, source = %drift.source, "{drift}");
+        }
+
+        info!(drifts = drifts.len(), "Finished index check");
+
+        if self.fix {
+            enqueue_fixes(conn, &drifts)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Where an index file is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexSource {
+    Git,
+    Sparse,
+}
+
+impl fmt::Display for IndexSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexSource::Git => f.write_str("git index"),
+            IndexSource::Sparse => f.write_str("sparse index"),
+        }
+    }
+}
+
+/// The differences between the index file of a crate and the index data
+/// from the database.
+#[derive(Debug, PartialEq)]
+pub struct IndexDrift {
+    pub krate: String,
+    pub source: IndexSource,
+    pub differences: Vec<Difference>,
+}
+
+impl fmt::Display for IndexDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` in the {}:", self.krate, self.source)?;
+        for difference in &self.differences {
+            write!(f, "\n  - {difference}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    /// The crate exists in the database, but has no index file.
+    MissingFile,
+    /// The crate has an index file, but doesn't exist in the database.
+    UnexpectedFile,
+    /// A line of the index file could not be parsed.
+    InvalidEntry {
+        line: usize,
+        error: String,
+    },
+    MissingVersion {
+        version: String,
+    },
+    UnexpectedVersion {
+        version: String,
+    },
+    /// A field of a version, like `yanked` or `cksum`, has a different value
+    /// than in the database.
+    Field {
+        version: String,
+        field: String,
+        expected: Value,
+        actual: Value,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::MissingFile => f.write_str("index file is missing"),
+            Difference::UnexpectedFile => f.write_str("index file of a crate that doesn't exist"),
+            Difference::InvalidEntry { line, error } => {
+                write!(f, "line {line} is not a valid index entry: {error}")
+            }
+            Difference::MissingVersion { version } => write!(f, "version {version} is missing"),
+            Difference::UnexpectedVersion { version } => {
+                write!(f, "version {version} doesn't exist")
+            }
+            Difference::Field {
+                version,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{field}` of version {version} is {actual} instead of {expected}"
+            ),
+        }
+    }
+}
+
+/// Compares the index files of all crates in the git index and the sparse
+/// index with the index data from the database.
+///
+/// The git repository is expected to be up-to-date with its remote.
+pub fn check_index(
+    conn: &mut PgConnection,
+    repo: &Repository,
+    storage: &Storage,
+) -> anyhow::Result<Vec<IndexDrift>> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to initialize tokio runtime")?;
+
+    // Index files are named after the lowercase crate names, so the names
+    // from the different sources are matched by their lowercase variant.
+    let mut names = BTreeMap::new();
+
+    let git_files = repo.get_files_modified_since(None)?;
+    for path in git_files {
+        if let Some(name) = index_file_name(&path) {
+            names.insert(name.to_lowercase(), name.to_string());
+        }
+    }
+
+    let sparse_files = rt
+        .block_on(storage.list_index_files())
+        .context("Failed to list the sparse index files")?;
+
+    for name in sparse_files {
+        names.insert(name.to_lowercase(), name);
+    }
+
+    let db_names: Vec<String> = crates::table.select(crates::name).load(conn)?;
+    for name in db_names {
+        names.insert(name.to_lowercase(), name);
+    }
+
+    info!(crates = names.len(), "Comparing index files");
+
+    let mut drifts = Vec::new();
+    for name in names.into_values() {
+        let expected = get_index_data(&name, conn)
+            .with_context(|| format!("Failed to get index data of `{name}`"))?;
+
+        let git = match std::fs::read_to_string(repo.index_file(&name)) {
+            Ok(content) => Some(content),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        let sparse = match rt.block_on(storage.download_index_file(&name)) {
+            Ok(content) => Some(String::from_utf8(content.to_vec())?),
+            Err(object_store::Error::NotFound { .. }) => None,
+            Err(error) => return Err(error.into()),
+        };
+
+        for (source, actual) in [(IndexSource::Git, git), (IndexSource::Sparse, sparse)] {
+            let differences = diff_index_files(expected.as_deref(), actual.as_deref());
+            if !differences.is_empty() {
+                let krate = name.clone();
+                drifts.push(IndexDrift {
+                    krate,
+                    source,
+                    differences,
+                });
+            }
+        }
+    }
+
+    Ok(drifts)
+}
+
+/// Enqueues the index sync jobs for the crates that drifted, so that their
+/// index files are regenerated from the database.
+pub fn enqueue_fixes(conn: &mut PgConnection, drifts: &[IndexDrift]) -> anyhow::Result<()> {
+    for drift in drifts {
+        info!(krate.name = %drift.krate, source = %drift.source, "Enqueueing index sync");
+        match drift.source {
+            IndexSource::Git => SyncToGitIndex::new(&drift.krate).enqueue(conn)?,
+            IndexSource::Sparse => SyncToSparseIndex::new(&drift.krate).enqueue(conn)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the crate name of a path in the index repository, or `None` if
+/// the path is not an index file, like the `config.json` file.
+fn index_file_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    (Repository::relative_index_file(name) == path).then_some(name)
+}
+
+fn diff_index_files(expected: Option<&str>, actual: Option<&str>) -> Vec<Difference> {
+    let (expected, actual) = match (expected, actual) {
+        (None, None) => return vec![],
+        (Some(_), None) => return vec![Difference::MissingFile],
+        (None, Some(_)) => return vec![Difference::UnexpectedFile],
+        (Some(expected), Some(actual)) if expected == actual => return vec![],
+        (Some(expected), Some(actual)) => (expected, actual),
+    };
+
+    let mut differences = Vec::new();
+
+    // The index data from the database is always valid
+    let expected = parse_entries(expected).unwrap_or_default();
+    let actual = match parse_entries(actual) {
+        Ok(actual) => actual,
+        Err(difference) => return vec![difference],
+    };
+
+    for (version, expected_entry) in &expected {
+        let Some(actual_entry) = actual.get(version) else {
+            let version = version.clone();
+            differences.push(Difference::MissingVersion { version });
+            continue;
+        };
+
+        let fields = expected_entry.keys().chain(actual_entry.keys());
+        let mut fields: Vec<_> = fields.collect();
+        fields.sort();
+        fields.dedup();
+
+        for field in fields {
+            let expected = expected_entry.get(field).unwrap_or(&Value::Null);
+            let actual = actual_entry.get(field).unwrap_or(&Value::Null);
+            if expected != actual {
+                differences.push(Difference::Field {
+                    version: version.clone(),
+                    field: field.clone(),
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
+        }
+    }
+
+    for version in actual.keys() {
+        if !expected.contains_key(version) {
+            let version = version.clone();
+            differences.push(Difference::UnexpectedVersion { version });
+        }
+    }
+
+    differences
+}
+
+type Entry = serde_json::Map<String, Value>;
+
+/// Parses the lines of an index file into entries, keyed by their version.
+fn parse_entries(content: &str) -> Result<BTreeMap<String, Entry>, Difference> {
+    let mut entries = BTreeMap::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid = |error: String| Difference::InvalidEntry { line: i + 1, error };
+
+        let entry: Entry = serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+        let version = entry.get("vers").and_then(Value::as_str);
+        let version = version.ok_or_else(|| invalid("`vers` is missing".into()))?;
+
+        entries.insert(version.to_string(), entry);
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOO_1_0_0: &str =
+        r#"{"name":"foo","vers":"1.0.0","deps":[],"cksum":"aaa","features":{},"yanked":false}"#;
+    const FOO_1_1_0: &str =
+        r#"{"name":"foo","vers":"1.1.0","deps":[],"cksum":"bbb","features":{},"yanked":false}"#;
+
+    #[test]
+    fn test_diff_index_files() {
+        let expected = format!("{FOO_1_0_0}\n{FOO_1_1_0}\n");
+
+        assert_eq!(diff_index_files(None, None), vec![]);
+        assert_eq!(diff_index_files(Some(&expected), Some(&expected)), vec![]);
+        assert_eq!(
+            diff_index_files(Some(&expected), None),
+            vec![Difference::MissingFile]
+        );
+        assert_eq!(
+            diff_index_files(None, Some(&expected)),
+            vec![Difference::UnexpectedFile]
+        );
+
+        let actual = format!("{FOO_1_0_0}\n");
+        assert_eq!(
+            diff_index_files(Some(&expected), Some(&actual)),
+            vec![Difference::MissingVersion {
+                version: "1.1.0".into()
+            }]
+        );
+        assert_eq!(
+            diff_index_files(Some(&actual), Some(&expected)),
+            vec![Difference::UnexpectedVersion {
+                version: "1.1.0".into()
+            }]
+        );
+
+        let yanked = FOO_1_1_0
+            .replace(r#""yanked":false"#, r#""yanked":true"#)
+            .replace("bbb", "ccc");
+        let actual = format!("{FOO_1_0_0}\n{yanked}\n");
+        assert_eq!(
+            diff_index_files(Some(&expected), Some(&actual)),
+            vec![
+                Difference::Field {
+                    version: "1.1.0".into(),
+                    field: "cksum".into(),
+                    expected: "bbb".into(),
+                    actual: "ccc".into(),
+                },
+                Difference::Field {
+                    version: "1.1.0".into(),
+                    field: "yanked".into(),
+                    expected: false.into(),
+                    actual: true.into(),
+                },
+            ]
+        );
+
+        let actual = format!("{FOO_1_0_0}\n{{\n");
+        let differences = diff_index_files(Some(&expected), Some(&actual));
+        assert!(matches!(
+            differences.as_slice(),
+            [Difference::InvalidEntry { line: 2, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_index_file_name() {
+        assert_eq!(index_file_name(Path::new("3/f/foo")), Some("foo"));
+        assert_eq!(index_file_name(Path::new("se/rd/serde")), Some("serde"));
+        assert_eq!(index_file_name(Path::new("config.json")), None);
+        assert_eq!(index_file_name(Path::new("3/b/foo")), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::ApiTokenUsage;
 use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
 use crate::worker::Environment;
 use diesel::{sql_query, RunQueryDsl};
@@ -20,12 +21,19 @@ impl BackgroundJob for DailyDbMaintenance {
     /// We only need to keep 90 days of entries in `version_downloads`. Once we have a mechanism to
     /// archive daily download counts and drop historical data, we can drop this task and rely on
     /// auto-vacuum again.
+    ///
+    /// This task also prunes the recorded uses of API tokens, so that only a bounded history is
+    /// kept for each token.
     fn run(&self, state: PerformState<'_>, _env: &Self::Context) -> Result<(), PerformError> {
         let mut conn = state.fresh_connection()?;
 
         info!("Running VACUUM on version_downloads table");
         sql_query("VACUUM version_downloads;").execute(&mut conn)?;
         info!("Finished running VACUUM on version_downloads table");
+
+        info!("Pruning api_token_usages table");
+        let deleted = ApiTokenUsage::prune(&mut conn)?;
+        info!(deleted, "Finished pruning api_token_usages table");
         Ok(())
     }
 }
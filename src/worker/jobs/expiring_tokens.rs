@@ -0,0 +1,118 @@
+//! Remind users of API tokens that are about to expire, and revoke tokens
+//! that have been expired for a while.
+
+use crate::models::{insert_token_audit_actions, ApiToken, CrateAction};
+use crate::schema::{api_tokens, emails};
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::dsl::now;
+use diesel::prelude::*;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessExpiringTokens {
+    /// Number of days before `expired_at` at which the owner of a token is
+    /// reminded that it is about to expire.
+    notify_days: i64,
+    /// Number of days after `expired_at` at which a token is revoked.
+    revoke_days: i64,
+}
+
+impl ProcessExpiringTokens {
+    pub fn new(notify_days: i64, revoke_days: i64) -> Self {
+        Self {
+            notify_days,
+            revoke_days,
+        }
+    }
+}
+
+impl BackgroundJob for ProcessExpiringTokens {
+    const JOB_NAME: &'static str = "process_expiring_tokens";
+
+    type Context = Arc<Environment>;
+
+    #[instrument(skip_all)]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        let conn = state.conn;
+
+        let current_time = Utc::now().naive_utc();
+        let notify_threshold = current_time + Duration::days(self.notify_days);
+        let revoke_threshold = current_time - Duration::days(self.revoke_days);
+
+        send_expiry_notifications(conn, env, notify_threshold)?;
+        revoke_expired_tokens(conn, revoke_threshold)?;
+
+        Ok(())
+    }
+}
+
+/// Sends a reminder to the owners of all tokens that expire before
+/// `threshold` and whose owners have not been reminded yet.
+fn send_expiry_notifications(
+    conn: &mut PgConnection,
+    env: &Environment,
+    threshold: NaiveDateTime,
+) -> QueryResult<()> {
+    let tokens: Vec<(i32, String, NaiveDateTime, String)> = api_tokens::table
+        .inner_join(emails::table.on(emails::user_id.eq(api_tokens::user_id)))
+        .filter(api_tokens::revoked.eq(false))
+        .filter(api_tokens::expiry_notification_at.is_null())
+        .filter(api_tokens::expired_at.gt(now))
+        .filter(api_tokens::expired_at.le(threshold))
+        .filter(emails::verified.eq(true))
+        .select((
+            api_tokens::id,
+            api_tokens::name,
+            api_tokens::expired_at.assume_not_null(),
+            emails::email,
+        ))
+        .order(api_tokens::id)
+        .load(conn)?;
+
+    info!(tokens = tokens.len(), "Sending token expiry notifications");
+
+    for (id, name, expired_at, email) in tokens {
+        let result = env
+            .emails()
+            .send_token_expiry_notification(&email, &name, expired_at);
+
+        // Tokens whose owners could not be notified are tried again the next
+        // time that this job runs.
+        if let Err(error) = result {
+            warn!(%email, "Failed to send token expiry notification: {error}");
+            continue;
+        }
+
+        diesel::update(api_tokens::table.find(id))
+            .set(api_tokens::expiry_notification_at.eq(now.nullable()))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Revokes all tokens that expired before `threshold` and records their
+/// revocation in the audit log of the crates that they could be used for.
+fn revoke_expired_tokens(conn: &mut PgConnection, threshold: NaiveDateTime) -> QueryResult<()> {
+    let tokens: Vec<ApiToken> = api_tokens::table
+        .filter(api_tokens::revoked.eq(false))
+        .filter(api_tokens::expired_at.lt(threshold))
+        .select(ApiToken::as_select())
+        .load(conn)?;
+
+    info!(tokens = tokens.len(), "Revoking expired tokens");
+
+    for token in tokens {
+        conn.transaction(|conn| {
+            diesel::update(&token)
+                .set(api_tokens::revoked.eq(true))
+                .execute(conn)?;
+
+            insert_token_audit_actions(conn, &token, None, CrateAction::ExpireToken)
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! List the files inside of uploaded crate files.
+
+use crate::models::VersionFileListing;
+use crate::schema::{crates, versions};
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use anyhow::Context;
+use crates_io_tarball::list_files;
+use diesel::prelude::*;
+use std::sync::Arc;
+
+/// Crate files have already been checked against the size limits when they
+/// were published, so this only protects the worker against crate files
+/// that somehow slipped through.
+const MAX_UNPACK_SIZE: u64 = 512 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+pub struct ListCrateFiles {
+    version_id: i32,
+}
+
+impl ListCrateFiles {
+    pub fn new(version_id: i32) -> Self {
+        Self { version_id }
+    }
+}
+
+impl BackgroundJob for ListCrateFiles {
+    const JOB_NAME: &'static str = "list_crate_files";
+    const PRIORITY: i16 = 10;
+
+    type Context = Arc<Environment>;
+
+    /// Downloads the crate file of the version from the storage backend and
+    /// saves the list of files inside of it to the database.
+    #[instrument(skip_all, fields(krate.name, krate.version))]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        info!(version_id = ?self.version_id, "Listing crate files");
+
+        let conn = state.conn;
+
+        let version: Option<(String, String)> = versions::table
+            .find(self.version_id)
+            .inner_join(crates::table)
+            .select((crates::name, versions::num))
+            .first(conn)
+            .optional()?;
+
+        let Some((crate_name, version)) = version else {
+            debug!("Skipping file listing because the version has been deleted");
+            return Ok(());
+        };
+
+        let span = tracing::Span::current();
+        span.record("krate.name", tracing::field::display(&crate_name));
+        span.record("krate.version", tracing::field::display(&version));
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to initialize tokio runtime")?;
+
+        let future = env.storage.download_crate_file(&crate_name, &version);
+        let tarball = rt
+            .block_on(future)
+            .context("Failed to download crate file")?;
+
+        let pkg_name = format!("{crate_name}-{version}");
+        let files = list_files(&pkg_name, &*tarball, MAX_UNPACK_SIZE)?;
+
+        let files = serde_json::to_value(files)?;
+        VersionFileListing::upsert(conn, self.version_id, &files)?;
+
+        Ok(())
+    }
+}
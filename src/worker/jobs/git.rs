@@ -1,13 +1,15 @@
 use crate::models;
+use crate::schema::background_jobs;
 use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
 use crate::worker::Environment;
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use chrono::Utc;
 use crates_io_index::{Crate, Repository};
 use diesel::prelude::*;
 use sentry::Level;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
 
@@ -29,46 +31,193 @@ impl BackgroundJob for SyncToGitIndex {
 
     type Context = Arc<Environment>;
 
-    /// Regenerates or removes an index file for a single crate
+    /// Regenerates or removes an index file for a single crate, or for all
+    /// crates with pending sync jobs if batching is enabled.
     #[instrument(skip_all, fields(krate.name = ? self.krate))]
     fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
-        info!("Syncing to git index");
+        let conn = state.conn;
 
-        let new = get_index_data(&self.krate, state.conn).context("Failed to get index data")?;
+        let Some(batching) = env.git_index_batching() else {
+            info!("Syncing to git index");
 
-        let repo = env.lock_index()?;
-        let dst = repo.index_file(&self.krate);
+            let repo = env.lock_index()?;
+            if let Some(change) = write_index_file(&self.krate, conn, &repo)? {
+                repo.commit_and_push(&change.message, &change.path)?;
+            } else {
+                debug!("Skipping sync because index is up-to-date");
+            }
 
-        // Read the previous crate contents
-        let old = match fs::read_to_string(&dst) {
-            Ok(content) => Some(content),
-            Err(error) if error.kind() == ErrorKind::NotFound => None,
-            Err(error) => return Err(error.into()),
+            return Ok(());
         };
 
-        match (old, new) {
-            (None, Some(new)) => {
-                fs::create_dir_all(dst.parent().unwrap())?;
-                let mut file = File::create(&dst)?;
-                file.write_all(new.as_bytes())?;
-                repo.commit_and_push(&format!("Create crate `{}`", self.krate), &dst)?;
-            }
-            (Some(old), Some(new)) if old != new => {
-                let mut file = File::create(&dst)?;
-                file.write_all(new.as_bytes())?;
-                repo.commit_and_push(&format!("Update crate `{}`", self.krate), &dst)?;
+        let mut crates = vec![self.krate.clone()];
+        crates.extend(take_pending_sync_jobs(conn, self)?);
+        crates.sort();
+        crates.dedup();
+
+        info!(
+            crates = crates.len(),
+            ?batching,
+            "Syncing batch to git index"
+        );
+
+        let repo = env.lock_index()?;
+
+        let mut changes = Vec::new();
+        for krate in &crates {
+            changes.extend(write_index_file(krate, conn, &repo)?);
+        }
+
+        if changes.is_empty() {
+            debug!("Skipping sync because index is up-to-date");
+            return Ok(());
+        }
+
+        match batching {
+            GitIndexBatching::SingleCommit => {
+                let message = batch_commit_message(&changes);
+                let paths = changes.iter().map(|c| c.path.as_path()).collect::<Vec<_>>();
+                repo.commit(&message, &paths)?;
             }
-            (Some(_old), None) => {
-                fs::remove_file(&dst)?;
-                repo.commit_and_push(&format!("Delete crate `{}`", self.krate), &dst)?;
+            GitIndexBatching::CommitPerCrate => {
+                for change in &changes {
+                    repo.commit(&change.message, &[&change.path])?;
+                }
             }
-            _ => debug!("Skipping sync because index is up-to-date"),
         }
 
+        repo.push()?;
+
         Ok(())
     }
 }
 
+/// How the `sync_to_git_index` jobs that are pending at the same time are
+/// combined, to reduce the number of pushes during bursts of publishes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GitIndexBatching {
+    /// All changed index files are committed in a single commit.
+    SingleCommit,
+    /// Every changed index file is committed in its own commit, but all
+    /// commits are pushed at once.
+    CommitPerCrate,
+}
+
+impl GitIndexBatching {
+    /// Reads the batching mode from the `GIT_INDEX_BATCHING` environment
+    /// variable, which is either `single-commit` or `commit-per-crate`.
+    ///
+    /// Returns `None` if batching is disabled.
+    pub fn from_environment() -> anyhow::Result<Option<Self>> {
+        let Some(mode) = crates_io_env_vars::var("GIT_INDEX_BATCHING")? else {
+            return Ok(None);
+        };
+
+        match mode.as_str() {
+            "single-commit" => Ok(Some(Self::SingleCommit)),
+            "commit-per-crate" => Ok(Some(Self::CommitPerCrate)),
+            _ => Err(anyhow!("Invalid GIT_INDEX_BATCHING value: {mode}")),
+        }
+    }
+}
+
+/// Maximum number of pending `sync_to_git_index` jobs that are combined into
+/// a single push.
+const MAX_BATCH_SIZE: i64 = 1000;
+
+/// Removes the other pending `sync_to_git_index` jobs from the queue and
+/// returns the names of their crates.
+///
+/// The jobs are removed in the transaction of the current job, so they are
+/// restored if the current job fails.
+fn take_pending_sync_jobs(
+    conn: &mut PgConnection,
+    current: &SyncToGitIndex,
+) -> anyhow::Result<Vec<String>> {
+    let current = serde_json::to_value(current)?;
+
+    let ids: Vec<i64> = background_jobs::table
+        .select(background_jobs::id)
+        .filter(background_jobs::job_type.eq(SyncToGitIndex::JOB_NAME))
+        .filter(background_jobs::data.ne(current))
+        .order(background_jobs::id)
+        .limit(MAX_BATCH_SIZE)
+        .for_update()
+        .skip_locked()
+        .load(conn)?;
+
+    let data: Vec<serde_json::Value> =
+        diesel::delete(background_jobs::table.filter(background_jobs::id.eq_any(ids)))
+            .returning(background_jobs::data)
+            .get_results(conn)?;
+
+    data.into_iter()
+        .map(|data| Ok(serde_json::from_value::<SyncToGitIndex>(data)?.krate))
+        .collect()
+}
+
+/// A change of an index file in the git index, which has not been committed
+/// yet.
+struct IndexFileChange {
+    path: PathBuf,
+    message: String,
+}
+
+/// Regenerates or removes the index file of a crate in the working folder of
+/// the git index.
+///
+/// Returns `None` if the index file is up-to-date.
+fn write_index_file(
+    krate: &str,
+    conn: &mut PgConnection,
+    repo: &Repository,
+) -> anyhow::Result<Option<IndexFileChange>> {
+    let new = get_index_data(krate, conn).context("Failed to get index data")?;
+
+    let dst = repo.index_file(krate);
+
+    // Read the previous crate contents
+    let old = match fs::read_to_string(&dst) {
+        Ok(content) => Some(content),
+        Err(error) if error.kind() == ErrorKind::NotFound => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let message = match (old, new) {
+        (None, Some(new)) => {
+            fs::create_dir_all(dst.parent().unwrap())?;
+            let mut file = File::create(&dst)?;
+            file.write_all(new.as_bytes())?;
+            format!("Create crate `{krate}`")
+        }
+        (Some(old), Some(new)) if old != new => {
+            let mut file = File::create(&dst)?;
+            file.write_all(new.as_bytes())?;
+            format!("Update crate `{krate}`")
+        }
+        (Some(_old), None) => {
+            fs::remove_file(&dst)?;
+            format!("Delete crate `{krate}`")
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(IndexFileChange { path: dst, message }))
+}
+
+/// Returns the message of a commit with multiple changed index files, which
+/// lists the messages of the individual changes in its body.
+fn batch_commit_message(changes: &[IndexFileChange]) -> String {
+    if let [change] = changes {
+        return change.message.clone();
+    }
+
+    let messages = changes.iter().map(|change| change.message.as_str());
+    let messages = messages.collect::<Vec<_>>().join("\n");
+
+    format!("Update {} crates\n\n{messages}\n", changes.len())
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SyncToSparseIndex {
     krate: String,
@@ -5,17 +5,37 @@ use diesel::prelude::*;
 use diesel::sql_types::{Int2, Jsonb, Text};
 use std::fmt::Display;
 
+mod advisories;
+mod check_index;
 mod daily_db_maintenance;
 pub mod dump_db;
+mod expiring_tokens;
+mod files;
 mod git;
+mod notifications;
+mod publish;
 mod readmes;
 mod update_downloads;
+mod verify_storage;
+mod webhooks;
 
+pub use self::advisories::ImportAdvisories;
+pub use self::check_index::{
+    check_index, enqueue_fixes, CheckIndex, Difference, IndexDrift, IndexSource,
+};
 pub use self::daily_db_maintenance::DailyDbMaintenance;
 pub use self::dump_db::DumpDb;
-pub use self::git::{NormalizeIndex, SquashIndex, SyncToGitIndex, SyncToSparseIndex};
+pub use self::expiring_tokens::ProcessExpiringTokens;
+pub use self::files::ListCrateFiles;
+pub use self::git::{
+    GitIndexBatching, NormalizeIndex, SquashIndex, SyncToGitIndex, SyncToSparseIndex,
+};
+pub use self::notifications::SendVersionNotifications;
+pub use self::publish::ProcessPublish;
 pub use self::readmes::RenderAndUploadReadme;
 pub use self::update_downloads::UpdateDownloads;
+pub use self::verify_storage::{verify_storage, VerifyStorage};
+pub use self::webhooks::{enqueue_webhooks, DeliverWebhook, MAX_ATTEMPTS};
 
 /// Enqueue both index sync jobs (git and sparse) for a crate, unless they
 /// already exist in the background job queue.
@@ -0,0 +1,116 @@
+//! Notify crate owners about published, yanked and unyanked versions.
+
+use crate::models::{OwnerKind, VersionAction, VersionOwnerAction};
+use crate::schema::{
+    api_tokens, crate_owners, crates, emails, users, version_owner_actions, versions,
+};
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use diesel::prelude::*;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct SendVersionNotifications {
+    version_owner_action_id: i32,
+}
+
+impl SendVersionNotifications {
+    pub fn new(version_owner_action_id: i32) -> Self {
+        Self {
+            version_owner_action_id,
+        }
+    }
+}
+
+impl BackgroundJob for SendVersionNotifications {
+    const JOB_NAME: &'static str = "send_version_notifications";
+    const PRIORITY: i16 = 50;
+
+    type Context = Arc<Environment>;
+
+    /// Sends an email about the version owner action to every user owner of
+    /// the crate that has a verified email address and has not disabled the
+    /// `email_notifications` for the crate.
+    #[instrument(skip_all, fields(krate.name, krate.version))]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        let conn = state.conn;
+
+        let action: Option<VersionOwnerAction> = version_owner_actions::table
+            .find(self.version_owner_action_id)
+            .first(conn)
+            .optional()?;
+
+        let Some(action) = action else {
+            debug!("Skipping notifications because the version has been deleted");
+            return Ok(());
+        };
+
+        let (crate_id, crate_name, version): (i32, String, String) = versions::table
+            .find(action.version_id)
+            .inner_join(crates::table)
+            .select((crates::id, crates::name, versions::num))
+            .first(conn)?;
+
+        let span = tracing::Span::current();
+        span.record("krate.name", tracing::field::display(&crate_name));
+        span.record("krate.version", tracing::field::display(&version));
+
+        let actor: String = users::table
+            .find(action.user_id)
+            .select(users::gh_login)
+            .first(conn)?;
+
+        let token_name: Option<String> = action
+            .api_token_id
+            .map(|id| {
+                api_tokens::table
+                    .find(id)
+                    .select(api_tokens::name)
+                    .first(conn)
+            })
+            .transpose()?;
+
+        let recipients: Vec<String> = crate_owners::table
+            .inner_join(users::table)
+            .inner_join(emails::table.on(emails::user_id.eq(users::id)))
+            .filter(crate_owners::crate_id.eq(crate_id))
+            .filter(crate_owners::owner_kind.eq(OwnerKind::User))
+            .filter(crate_owners::deleted.eq(false))
+            .filter(crate_owners::email_notifications.eq(true))
+            .filter(emails::verified.eq(true))
+            .select(emails::email)
+            .order(users::id)
+            .load(conn)?;
+
+        let action = match action.action {
+            VersionAction::Publish => "published",
+            VersionAction::Yank => "yanked",
+            VersionAction::Unyank => "unyanked",
+        };
+
+        info!(
+            recipients = recipients.len(),
+            "Sending version notifications"
+        );
+
+        for email in recipients {
+            let result = env.emails().send_version_notification(
+                &email,
+                &crate_name,
+                &version,
+                action,
+                &actor,
+                token_name.as_deref(),
+            );
+
+            // A single undeliverable address should not prevent the other
+            // owners from being notified, and retrying the job would send
+            // duplicate emails to them.
+            if let Err(error) = result {
+                warn!(%email, "Failed to send version notification: {error}");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Finish publishes that were accepted by the `PUT /api/v1/crates/new`
+//! endpoint.
+
+use crate::models::{Publish, PublishStatus, VersionSignature};
+use crate::schema::{crates, versions};
+use crate::worker::jobs;
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use anyhow::Context;
+use diesel::prelude::*;
+use hex::ToHex;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessPublish {
+    publish_id: i32,
+}
+
+impl ProcessPublish {
+    pub fn new(publish_id: i32) -> Self {
+        Self { publish_id }
+    }
+}
+
+impl BackgroundJob for ProcessPublish {
+    const JOB_NAME: &'static str = "process_publish";
+    const PRIORITY: i16 = 100;
+
+    type Context = Arc<Environment>;
+
+    /// Verifies the uploaded tarball against the checksum that was saved for
+    /// the new version, uploads it (and its detached signature, if there is
+    /// one) to the storage backend and then enqueues the index sync jobs for
+    /// the crate and the job that lists the files inside of the crate file.
+    #[instrument(skip_all, fields(publish.id = self.publish_id, krate.name, krate.version))]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        info!("Processing publish");
+
+        let publish = Publish::find(state.conn, self.publish_id)?;
+        if publish.status == PublishStatus::Succeeded {
+            debug!("Skipping publish because it has already been processed");
+            return Ok(());
+        }
+
+        // The job itself runs inside of a database transaction, so the
+        // `processing` status is only visible to other connections if we
+        // are able to update it through a fresh connection.
+        if let Ok(mut fresh_conn) = state.fresh_connection() {
+            publish.mark_processing(&mut fresh_conn)?;
+        }
+
+        let conn = state.conn;
+
+        let (crate_name, version, checksum): (String, String, String) = versions::table
+            .find(publish.version_id)
+            .inner_join(crates::table)
+            .select((crates::name, versions::num, versions::checksum))
+            .first(conn)?;
+
+        let span = tracing::Span::current();
+        span.record("krate.name", tracing::field::display(&crate_name));
+        span.record("krate.version", tracing::field::display(&version));
+
+        let Some(tarball) = publish.tarball(conn)? else {
+            let error = "the uploaded tarball is no longer available";
+            warn!("Failed to process publish: {error}");
+            publish.mark_failed(error, conn)?;
+            return Ok(());
+        };
+
+        let hex_cksum: String = Sha256::digest(&tarball).encode_hex();
+        if hex_cksum != checksum {
+            let error = "the checksum of the uploaded tarball does not match the checksum of the published version";
+            warn!("Failed to process publish: {error}");
+            publish.mark_failed(error, conn)?;
+            return Ok(());
+        }
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to initialize tokio runtime")?;
+
+        let future = env
+            .storage
+            .upload_crate_file(&crate_name, &version, tarball.into());
+        rt.block_on(future).context("Failed to upload crate file")?;
+
+        if let Some(signature) = VersionSignature::find(conn, publish.version_id)? {
+            let future = env.storage.upload_crate_signature(
+                &crate_name,
+                &version,
+                signature.signature.into(),
+            );
+            rt.block_on(future)
+                .context("Failed to upload crate signature")?;
+        }
+
+        jobs::enqueue_sync_to_index(&crate_name, conn)?;
+        jobs::ListCrateFiles::new(publish.version_id).enqueue(conn)?;
+
+        publish.mark_succeeded(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! Verify that the stored crate files match the versions in the database.
+
+use crate::models::{NewStorageIssue, StorageIssue, StorageIssueKind};
+use crate::schema::{crates, versions};
+use crate::storage::Storage;
+use crate::worker::swirl::{BackgroundJob, PerformError, PerformState};
+use crate::worker::Environment;
+use anyhow::Context;
+use diesel::prelude::*;
+use futures_util::{StreamExt, TryStreamExt};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Number of crate files that are downloaded and hashed at the same time.
+const CONCURRENCY: usize = 10;
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyStorage;
+
+impl BackgroundJob for VerifyStorage {
+    const JOB_NAME: &'static str = "verify_storage";
+
+    type Context = Arc<Environment>;
+
+    #[instrument(skip_all)]
+    fn run(&self, state: PerformState<'_>, env: &Self::Context) -> Result<(), PerformError> {
+        let conn = state.conn;
+
+        let issues = verify_storage(conn, &env.storage)?;
+        info!(issues = issues.len(), "Finished storage verification");
+
+        StorageIssue::replace_all(conn, &issues)?;
+
+        Ok(())
+    }
+}
+
+/// Downloads the crate files of all versions and compares their SHA256
+/// checksums with the `versions.checksum` column.
+///
+/// Returns the versions whose crate files are missing or don't match their
+/// checksum, and the crate files that don't belong to any version.
+pub fn verify_storage(
+    conn: &mut PgConnection,
+    storage: &Storage,
+) -> anyhow::Result<Vec<NewStorageIssue>> {
+    let versions: Vec<(i32, String, String, String)> = versions::table
+        .inner_join(crates::table)
+        .select((
+            versions::id,
+            crates::name,
+            versions::num,
+            versions::checksum,
+        ))
+        .order((crates::name, versions::id))
+        .load(conn)?;
+
+    info!(versions = versions.len(), "Verifying crate files");
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to initialize tokio runtime")?;
+
+    rt.block_on(async {
+        let stored_files = storage
+            .list_crate_files()
+            .await
+            .context("Failed to list the stored crate files")?;
+
+        let stored_files: HashSet<_> = stored_files.into_iter().collect();
+
+        let mut issues: Vec<_> = futures_util::stream::iter(&versions)
+            .map(|(id, name, num, checksum)| {
+                let is_stored = stored_files.contains(&(name.clone(), num.clone()));
+                verify_crate_file(storage, *id, name, num, checksum, is_stored)
+            })
+            .buffered(CONCURRENCY)
+            .try_filter_map(|issue| async { Ok(issue) })
+            .try_collect()
+            .await?;
+
+        let known_files: HashSet<_> = versions
+            .iter()
+            .map(|(_, name, num, _)| (name.as_str(), num.as_str()))
+            .collect();
+
+        let mut orphaned_files: Vec<_> = stored_files
+            .iter()
+            .filter(|(name, num)| !known_files.contains(&(name.as_str(), num.as_str())))
+            .collect();
+
+        orphaned_files.sort();
+
+        issues.extend(orphaned_files.into_iter().map(|(name, num)| {
+            warn!(krate.name = %name, krate.version = %num, "Found orphaned crate file");
+            NewStorageIssue {
+                kind: StorageIssueKind::Orphaned,
+                crate_name: name.clone(),
+                version: num.clone(),
+                version_id: None,
+                expected_checksum: None,
+                actual_checksum: None,
+            }
+        }));
+
+        Ok(issues)
+    })
+}
+
+async fn verify_crate_file(
+    storage: &Storage,
+    version_id: i32,
+    name: &str,
+    num: &str,
+    expected_checksum: &str,
+    is_stored: bool,
+) -> anyhow::Result<Option<NewStorageIssue>> {
+    let issue = |kind, actual_checksum| NewStorageIssue {
+        kind,
+        crate_name: name.to_string(),
+        version: num.to_string(),
+        version_id: Some(version_id),
+        expected_checksum: Some(expected_checksum.to_string()),
+        actual_checksum,
+    };
+
+    if !is_stored {
+        warn!(krate.name = %name, krate.version = %num, "Crate file is missing");
+        return Ok(Some(issue(StorageIssueKind::Missing, None)));
+    }
+
+    let checksum = match storage.crate_file_checksum(name, num).await {
+        Ok(checksum) => checksum,
+        // The crate file might have been deleted since the files were listed
+        Err(object_store::Error::NotFound { .. }) => {
+            warn!(krate.name = %name, krate.version = %num, "Crate file is missing");
+            return Ok(Some(issue(StorageIssueKind::Missing, None)));
+        }
+        Err(error) => {
+            let context = format!("Failed to download the {name} {num} crate file");
+            return Err(anyhow::Error::from(error).context(context));
+        }
+    };
+
+    if checksum != expected_checksum {
+        warn!(krate.name = %name, krate.version = %num, "Crate file checksum mismatch");
+        return Ok(Some(issue(
+            StorageIssueKind::ChecksumMismatch,
+            Some(checksum),
+        )));
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,241 @@
+//! Deliver crate events to the webhooks that the crate owners registered.
+
+use crate::models::{Webhook, WebhookDelivery, WebhookEvent};
+use crate::schema::{crates, webhook_deliveries, webhooks};
+use crate::worker::swirl::{BackgroundJob, EnqueueError, PerformError, PerformState};
+use crate::worker::Environment;
+use chrono::Utc;
+use diesel::dsl::now;
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use reqwest::blocking::Client;
+use reqwest::header;
+use reqwest::redirect::Policy;
+use serde_json::Value;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Number of delivery attempts after which a delivery is marked as failed
+/// and not retried anymore.
+///
+/// Failed jobs are retried by the background worker with an exponential
+/// backoff, so the last attempt happens roughly 8.5 hours after the first.
+pub const MAX_ATTEMPTS: i32 = 10;
+
+/// Maximum time that a webhook has to respond to a delivery.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const USER_AGENT: &str = "crates.io webhooks (https://crates.io)";
+
+/// Inserts a delivery for every webhook of the crate that is subscribed to
+/// the event, and enqueues the background jobs that send them.
+///
+/// The `details` object is merged into the payload, next to the `event`,
+/// `crate` and `timestamp` fields that all payloads have in common.
+pub fn enqueue_webhooks(
+    conn: &mut PgConnection,
+    crate_id: i32,
+    event: WebhookEvent,
+    details: Value,
+) -> Result<(), EnqueueError> {
+    let webhook_ids: Vec<i32> = webhooks::table
+        .filter(webhooks::crate_id.eq(crate_id))
+        .select(Webhook::as_select())
+        .order(webhooks::id)
+        .load(conn)?
+        .into_iter()
+        .filter(|webhook| webhook.events.contains(&event))
+        .map(|webhook| webhook.id)
+        .collect();
+
+    if webhook_ids.is_empty() {
+        return Ok(());
+    }
+
+    let crate_name: String = crates::table
+        .find(crate_id)
+        .select(crates::name)
+        .first(conn)?;
+
+    let mut payload = json!({
+        "event": event,
+        "crate": crate_name,
+        "timestamp": Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    });
+    if let (Some(payload), Value::Object(details)) = (payload.as_object_mut(), details) {
+        payload.extend(details);
+    }
+
+    let deliveries = webhook_ids
+        .iter()
+        .map(|webhook_id| {
+            (
+                webhook_deliveries::webhook_id.eq(webhook_id),
+                webhook_deliveries::event.eq(event.as_str()),
+                webhook_deliveries::payload.eq(&payload),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let delivery_ids: Vec<i64> = diesel::insert_into(webhook_deliveries::table)
+        .values(&deliveries)
+        .returning(webhook_deliveries::id)
+        .get_results(conn)?;
+
+    for delivery_id in delivery_ids {
+        DeliverWebhook::new(delivery_id).enqueue(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Calculates the hex encoded HMAC-SHA256 signature of a payload, which is
+/// sent to the webhook in the `X-Crates-Io-Signature` header.
+fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeliverWebhook {
+    delivery_id: i64,
+}
+
+impl DeliverWebhook {
+    pub fn new(delivery_id: i64) -> Self {
+        Self { delivery_id }
+    }
+}
+
+impl BackgroundJob for DeliverWebhook {
+    const JOB_NAME: &'static str = "deliver_webhook";
+    const PRIORITY: i16 = 0;
+
+    type Context = Arc<Environment>;
+
+    /// Sends the payload of the delivery to the webhook.
+    ///
+    /// Failed attempts return an error, so that the background worker
+    /// retries the job with its exponential backoff, until the delivery is
+    /// given up on after [`MAX_ATTEMPTS`].
+    #[instrument(skip_all, fields(delivery.id = self.delivery_id))]
+    fn run(&self, state: PerformState<'_>, _env: &Self::Context) -> Result<(), PerformError> {
+        let delivery: Option<(WebhookDelivery, Webhook)> = webhook_deliveries::table
+            .find(self.delivery_id)
+            .inner_join(webhooks::table)
+            .select((WebhookDelivery::as_select(), Webhook::as_select()))
+            .first(state.conn)
+            .optional()?;
+
+        let Some((delivery, webhook)) = delivery else {
+            debug!("Skipping delivery because the webhook has been deleted");
+            return Ok(());
+        };
+
+        if delivery.delivered_at.is_some() || delivery.failed_at.is_some() {
+            debug!("Skipping delivery because it has already been completed");
+            return Ok(());
+        }
+
+        let attempts = delivery.attempts + 1;
+        let (status, error) = match send(&webhook, &delivery) {
+            Ok(status) => (Some(status), None),
+            Err(DeliveryError { status, message }) => (status, Some(message)),
+        };
+
+        let query = diesel::update(webhook_deliveries::table.find(delivery.id));
+        let attempt = (
+            webhook_deliveries::attempts.eq(attempts),
+            webhook_deliveries::last_status.eq(status),
+            webhook_deliveries::last_error.eq(error.clone()),
+            webhook_deliveries::last_attempt_at.eq(now),
+        );
+
+        let Some(error) = error else {
+            query
+                .set((attempt, webhook_deliveries::delivered_at.eq(now)))
+                .execute(state.conn)?;
+            return Ok(());
+        };
+
+        if attempts >= MAX_ATTEMPTS {
+            warn!(%error, "Giving up on webhook delivery after {attempts} attempts");
+            query
+                .set((attempt, webhook_deliveries::failed_at.eq(now)))
+                .execute(state.conn)?;
+            return Ok(());
+        }
+
+        // Returning an error rolls back the changes made with `state.conn`,
+        // so the attempt has to be recorded with a separate connection.
+        match state.fresh_connection() {
+            Ok(mut conn) => {
+                query.set(attempt).execute(&mut conn)?;
+            }
+            Err(err) => warn!("Failed to record webhook delivery attempt: {err}"),
+        }
+
+        Err(error.into())
+    }
+}
+
+struct DeliveryError {
+    status: Option<i32>,
+    message: String,
+}
+
+/// `POST`s the payload of the delivery to the webhook, returning the status
+/// code of the response if it was successful.
+fn send(webhook: &Webhook, delivery: &WebhookDelivery) -> Result<i32, DeliveryError> {
+    let error = |message: String| DeliveryError {
+        status: None,
+        message,
+    };
+
+    let client = Client::builder()
+        .timeout(TIMEOUT)
+        .redirect(Policy::none())
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|err| error(err.to_string()))?;
+
+    let body = serde_json::to_vec(&delivery.payload).map_err(|err| error(err.to_string()))?;
+    let signature = sign_payload(&webhook.secret, &body);
+
+    let response = client
+        .post(&webhook.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Crates-Io-Event", &delivery.event)
+        .header("X-Crates-Io-Delivery", delivery.id.to_string())
+        .header("X-Crates-Io-Signature", format!("sha256={signature}"))
+        .body(body)
+        .send()
+        .map_err(|err| error(err.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(DeliveryError {
+            status: Some(status.as_u16().into()),
+            message: format!("Webhook responded with status {status}"),
+        });
+    }
+
+    Ok(status.as_u16().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        let payload = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            sign_payload("key", payload),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
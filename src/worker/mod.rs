@@ -20,13 +20,21 @@ pub trait RunnerExt {
 
 impl RunnerExt for Runner<Arc<Environment>> {
     fn register_crates_io_job_types(self) -> Self {
-        self.register_job_type::<jobs::DailyDbMaintenance>()
+        self.register_job_type::<jobs::CheckIndex>()
+            .register_job_type::<jobs::DailyDbMaintenance>()
+            .register_job_type::<jobs::DeliverWebhook>()
             .register_job_type::<jobs::DumpDb>()
+            .register_job_type::<jobs::ImportAdvisories>()
+            .register_job_type::<jobs::ListCrateFiles>()
             .register_job_type::<jobs::NormalizeIndex>()
+            .register_job_type::<jobs::ProcessExpiringTokens>()
+            .register_job_type::<jobs::ProcessPublish>()
             .register_job_type::<jobs::RenderAndUploadReadme>()
+            .register_job_type::<jobs::SendVersionNotifications>()
             .register_job_type::<jobs::SquashIndex>()
             .register_job_type::<jobs::SyncToGitIndex>()
             .register_job_type::<jobs::SyncToSparseIndex>()
             .register_job_type::<jobs::UpdateDownloads>()
+            .register_job_type::<jobs::VerifyStorage>()
     }
 }